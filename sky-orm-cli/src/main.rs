@@ -1,19 +1,28 @@
 #![allow(clippy::unwrap_used)]
 
 mod schema;
+mod seed;
+mod triggers;
+mod validate;
 
 use clap::{Parser, Subcommand};
 use schema::GenerateSchema;
+use seed::Seed;
 use tracing::{error, level_filters::LevelFilter};
 use tracing_subscriber::{
     fmt::{format, layer},
     layer::SubscriberExt,
     util::SubscriberInitExt,
 };
+use triggers::GenerateTriggers;
+use validate::Validate;
 
 #[derive(Subcommand, Debug)]
 enum Subcommands {
     GenerateSchema(GenerateSchema),
+    GenerateTriggers(GenerateTriggers),
+    Seed(Seed),
+    Validate(Validate),
 }
 
 #[derive(Parser, Debug)]
@@ -33,9 +42,13 @@ async fn main() {
 
     let r = match args.command {
         Subcommands::GenerateSchema(cmd) => cmd.run().await,
+        Subcommands::GenerateTriggers(cmd) => cmd.run().await,
+        Subcommands::Seed(cmd) => cmd.run().await,
+        Subcommands::Validate(cmd) => cmd.run().await,
     };
 
     if let Err(e) = r {
         error!("Command execution failed: {e}");
+        std::process::exit(1);
     }
 }
@@ -1,10 +1,12 @@
+use std::path::Path;
+
 use clap::Parser;
 use eyre::Context;
 use futures::{StreamExt, stream::FuturesUnordered};
 use sky_orm_sqlparse::{
     db::{DbType, get_database_url},
-    query::parse_tables,
-    schema::SqlSchema,
+    query::{SqlDialect, parse_data_type, parse_schema},
+    schema::{SCHEMA_VERSION, SqlColumn, SqlSchema, schema_path},
 };
 use sqlx::Row;
 use sqlx::{Connection, SqliteConnection};
@@ -27,27 +29,16 @@ impl GenerateSchema {
             ));
         };
 
-        let Some(database_type) = DbType::from_connection_string(&database_url) else {
-            return Err(eyre::eyre!(
-                "Failed to determine database type from connection string, ensure it starts with either `postgres`, `mysql`, or `sqlite`."
-            ));
-        };
+        let schema = introspect_schema(&database_url).await?;
 
-        let schema = match database_type {
-            DbType::MySql => todo!(),
-            DbType::Postgres => todo!(),
-            DbType::Sqlite => generate_sqlite_schema(&database_url).await,
-        }?;
+        let schema_file =
+            schema_path(&std::env::current_dir().context("Failed to determine current directory")?);
 
-        let sky_orm_dir = std::env::current_dir()
-            .context("Failed to determine current directory")?
-            .join("sky_orm");
-
-        let schema_file = sky_orm_dir.join("schema.json");
-
-        tokio::fs::create_dir_all(&sky_orm_dir)
-            .await
-            .context("Failed to create sky_orm directory")?;
+        if let Some(schema_dir) = schema_file.parent() {
+            tokio::fs::create_dir_all(schema_dir)
+                .await
+                .context("Failed to create schema directory")?;
+        }
 
         tokio::fs::write(
             &schema_file,
@@ -65,23 +56,64 @@ impl GenerateSchema {
     }
 }
 
-pub async fn generate_sqlite_schema(url: &str) -> eyre::Result<SqlSchema> {
+/// Read the schema of the live database behind the given URL.
+pub async fn introspect_schema(database_url: &str) -> eyre::Result<SqlSchema> {
+    let Some(database_type) = DbType::from_connection_string(database_url) else {
+        return Err(eyre::eyre!(
+            "Failed to determine database type from connection string, ensure it starts with either `postgres`, `mysql`, or `sqlite`."
+        ));
+    };
+
+    let dialect = SqlDialect::from(&database_type);
+
+    match database_type {
+        DbType::MySql => Err(eyre::eyre!(
+            "Reading the schema of MySQL databases is not supported yet, only of SQLite ones."
+        )),
+        DbType::Postgres => Err(eyre::eyre!(
+            "Reading the schema of Postgres databases is not supported yet, only of SQLite ones."
+        )),
+        DbType::Sqlite => generate_sqlite_schema(database_url, dialect).await,
+    }
+}
+
+/// Read the checked-in schema of the project in `base_dir`, see [`schema_path`]. Fails if it was
+/// written in an older format version.
+pub async fn read_schema(base_dir: &Path) -> eyre::Result<SqlSchema> {
+    let schema: SqlSchema = serde_json::from_str(
+        &tokio::fs::read_to_string(schema_path(base_dir))
+            .await
+            .context("Failed to read schema, run `generate-schema` first")?,
+    )
+    .context("Failed to parse schema")?;
+
+    if !schema.is_current_version() {
+        return Err(eyre::eyre!(
+            "Schema was written in format version {}, but the current version is {SCHEMA_VERSION}, run `generate-schema` to update it",
+            schema.version
+        ));
+    }
+
+    Ok(schema)
+}
+
+pub async fn generate_sqlite_schema(url: &str, dialect: SqlDialect) -> eyre::Result<SqlSchema> {
     let mut conn = SqliteConnection::connect(url)
         .await
         .context("Failed to connect to database")?;
 
-    let tables = sqlx::query("SELECT type,sql FROM sqlite_schema")
+    let schemas = sqlx::query("SELECT type,sql FROM sqlite_schema")
         .fetch(&mut conn)
         .filter_map(async |e| match e {
             Ok(e) => {
                 let ty: String = e.get("type");
-                if ty.eq("table") {
+                if ty.eq("table") || ty.eq("view") {
                     let sql: String = e.get("sql");
 
-                    let table = parse_tables(&sql);
+                    let schema = parse_schema(&sql, dialect);
 
-                    match table {
-                        Ok(t) => Some(Ok(t)),
+                    match schema {
+                        Ok(s) => Some(Ok(s)),
                         Err(e) => Some(Err(eyre::eyre!("Failed to parse table SQL: {e}"))),
                     }
                 } else {
@@ -94,10 +126,36 @@ pub async fn generate_sqlite_schema(url: &str) -> eyre::Result<SqlSchema> {
         .await
         .into_iter()
         .collect::<Result<Vec<_>, _>>()
-        .context("Failed to gather tables")?
-        .into_iter()
-        .flatten()
-        .collect::<Vec<_>>();
+        .context("Failed to gather tables")?;
+
+    // Every statement is parsed on its own, so their schemas are merged back together. sqlite has
+    // no enum types, but the statements go through the same parsing as any other SQL schema.
+    let mut tables = vec![];
+    let mut enums = vec![];
+
+    for mut schema in schemas {
+        tables.append(&mut schema.tables);
+        enums.append(&mut schema.enums);
+    }
+
+    // View definitions don't carry any type information, so ask the database instead.
+    for view in tables.iter_mut().filter(|e| e.is_view) {
+        view.columns = sqlx::query("SELECT name,type FROM pragma_table_info(?)")
+            .bind(&view.name)
+            .fetch_all(&mut conn)
+            .await
+            .context("Failed to query view columns")?
+            .iter()
+            .map(|e| {
+                let name: String = e.get("name");
+                let ty: String = e.get("type");
+
+                parse_data_type(&ty, dialect)
+                    .map(|ty| SqlColumn::new_view_column(name, ty))
+                    .map_err(|e| eyre::eyre!("Failed to parse view column type: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+    }
 
-    Ok(SqlSchema { tables })
+    Ok(SqlSchema::new(tables).with_enums(enums))
 }
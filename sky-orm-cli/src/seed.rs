@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Context;
+use sky_orm::fixtures::{FixtureTable, Fixtures};
+use sky_orm_sqlparse::{
+    db::{DbType, get_database_url},
+    schema::SqlTable,
+};
+use sqlx::{Connection, MySqlConnection, PgConnection, SqliteConnection};
+use tracing::info;
+
+use crate::schema::read_schema;
+
+/// Insert the rows of JSON fixture files into the database, resolving references between them via
+/// the foreign keys of the checked-in schema
+#[derive(Parser, Debug)]
+pub struct Seed {
+    /// The fixture files to insert. Rows may refer to named rows of other files.
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+
+    /// The URL to the database to seed. If left unset, will be pulled from the `DATABASE_URL`
+    /// environment variable, or a corresponding `.env` file instead.
+    #[arg(short, long, value_name = "DATABASE_URL")]
+    database_url: Option<String>,
+}
+
+impl Seed {
+    pub async fn run(&self) -> eyre::Result<()> {
+        let Some(database_url) = self.database_url.clone().or_else(get_database_url) else {
+            return Err(eyre::eyre!(
+                "Missing database URL, either set the `DATABASE_URL` environment variable, or specify it manually via --database-url [URL]"
+            ));
+        };
+
+        let Some(database_type) = DbType::from_connection_string(&database_url) else {
+            return Err(eyre::eyre!(
+                "Failed to determine database type from connection string, ensure it starts with either `postgres`, `mysql`, or `sqlite`."
+            ));
+        };
+
+        let schema =
+            read_schema(&std::env::current_dir().context("Failed to determine current directory")?)
+                .await?;
+
+        let mut fixtures = schema
+            .tables
+            .iter()
+            .filter(|e| !e.is_view)
+            .fold(Fixtures::new(), |fixtures, table| {
+                fixtures.table(fixture_table(table))
+            });
+
+        for file in &self.files {
+            let json = tokio::fs::read_to_string(file)
+                .await
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+
+            fixtures = fixtures
+                .load(&json)
+                .map_err(|e| eyre::eyre!("Failed to load {}: {e}", file.display()))?;
+        }
+
+        // All rows are inserted in a single transaction, so that a failing row does not leave the
+        // others behind.
+        let keys = match database_type {
+            DbType::Postgres => {
+                let mut conn = PgConnection::connect(&database_url)
+                    .await
+                    .context("Failed to connect to database")?;
+                let mut transaction = conn.begin().await?;
+                let keys = fixtures.insert(&mut *transaction).await?;
+                transaction.commit().await?;
+                keys
+            }
+            DbType::MySql => {
+                let mut conn = MySqlConnection::connect(&database_url)
+                    .await
+                    .context("Failed to connect to database")?;
+                let mut transaction = conn.begin().await?;
+                let keys = fixtures.insert(&mut *transaction).await?;
+                transaction.commit().await?;
+                keys
+            }
+            DbType::Sqlite => {
+                let mut conn = SqliteConnection::connect(&database_url)
+                    .await
+                    .context("Failed to connect to database")?;
+                let mut transaction = conn.begin().await?;
+                let keys = fixtures.insert(&mut *transaction).await?;
+                transaction.commit().await?;
+                keys
+            }
+        };
+
+        info!("Database seeded, {} named rows inserted", keys.len());
+
+        Ok(())
+    }
+}
+
+/// Describe `table` for fixtures, with the foreign keys that span a single column.
+fn fixture_table(table: &SqlTable) -> FixtureTable {
+    let mut fixture_table = table
+        .columns
+        .iter()
+        .fold(FixtureTable::new(&table.name), |fixture_table, column| {
+            fixture_table.column(&column.name, column.column_type.to_string())
+        });
+
+    if let Some(schema) = &table.schema {
+        fixture_table = fixture_table.schema(schema);
+    }
+
+    if let Some(primary_key) = &table.primary_key {
+        fixture_table = fixture_table.primary_key(primary_key);
+    }
+
+    table
+        .foreign_keys
+        .iter()
+        .filter(|e| !e.is_composite())
+        .fold(fixture_table, |fixture_table, foreign_key| {
+            fixture_table.reference(
+                &foreign_key.columns[0],
+                &foreign_key.target_table,
+                foreign_key.target_columns.first().cloned(),
+            )
+        })
+}
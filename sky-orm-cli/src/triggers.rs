@@ -0,0 +1,303 @@
+use std::{fmt::Write, path::PathBuf};
+
+use clap::Parser;
+use eyre::Context;
+use sky_orm_sqlparse::{
+    db::{DbType, get_database_url},
+    schema::SqlTable,
+};
+use tracing::info;
+
+use crate::schema::read_schema;
+
+const OPERATIONS: [&str; 3] = ["INSERT", "UPDATE", "DELETE"];
+
+/// Generate SQL for triggers recording all changes to the given tables into a change table, to be
+/// consumed using `sky_orm::change::ChangeFeed`
+#[derive(Parser, Debug)]
+pub struct GenerateTriggers {
+    /// The tables for which to record changes.
+    #[arg(required = true)]
+    tables: Vec<String>,
+
+    /// The URL to the database the triggers are meant for. If left unset, will be pulled from the
+    /// `DATABASE_URL` environment variable, or a corresponding `.env` file instead.
+    #[arg(short, long, value_name = "DATABASE_URL")]
+    database_url: Option<String>,
+
+    /// The name of the table changes are recorded into.
+    #[arg(long, default_value = "sky_orm_changes")]
+    change_table: String,
+
+    /// Also send every change as a notification on this channel, to be received using
+    /// `sky_orm::notify::ChangeListener`. Only supported on Postgres.
+    #[arg(long, value_name = "CHANNEL")]
+    notify: Option<String>,
+
+    /// Where to write the generated SQL to. Defaults to `sky_orm/change_feed.sql`.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl GenerateTriggers {
+    pub async fn run(&self) -> eyre::Result<()> {
+        let Some(database_url) = self.database_url.clone().or_else(get_database_url) else {
+            return Err(eyre::eyre!(
+                "Missing database URL, either set the `DATABASE_URL` environment variable, or specify it manually via --database-url [URL]"
+            ));
+        };
+
+        let Some(database_type) = DbType::from_connection_string(&database_url) else {
+            return Err(eyre::eyre!(
+                "Failed to determine database type from connection string, ensure it starts with either `postgres`, `mysql`, or `sqlite`."
+            ));
+        };
+
+        if self.notify.is_some() && !matches!(database_type, DbType::Postgres) {
+            return Err(eyre::eyre!(
+                "Notifications are only supported on Postgres, remove --notify for this database."
+            ));
+        }
+
+        let current_dir =
+            std::env::current_dir().context("Failed to determine current directory")?;
+
+        let schema = read_schema(&current_dir).await?;
+
+        let tables = self
+            .tables
+            .iter()
+            .map(|e| {
+                schema
+                    .find_table(e)
+                    .filter(|e| !e.is_view)
+                    .ok_or_else(|| eyre::eyre!("Table `{e}` does not exist in schema"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sql = change_feed_sql(
+            database_type,
+            &self.change_table,
+            self.notify.as_deref(),
+            &tables,
+        );
+
+        let output = self
+            .output
+            .clone()
+            .unwrap_or_else(|| current_dir.join("sky_orm").join("change_feed.sql"));
+
+        tokio::fs::write(&output, sql)
+            .await
+            .context("Failed to write triggers")?;
+
+        info!("Change feed triggers written to {}", output.display());
+
+        Ok(())
+    }
+}
+
+fn quote_ident(database_type: DbType, ident: &str) -> String {
+    match database_type {
+        DbType::MySql => format!("`{}`", ident.replace('`', "``")),
+        DbType::Postgres | DbType::Sqlite => format!("\"{}\"", ident.replace('"', "\"\"")),
+    }
+}
+
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Generate the SQL creating the change table, as well as the triggers for each table.
+fn change_feed_sql(
+    database_type: DbType,
+    change_table: &str,
+    notify: Option<&str>,
+    tables: &[&SqlTable],
+) -> String {
+    let mut sql = change_table_sql(database_type, change_table, notify);
+
+    for table in tables {
+        table_triggers_sql(&mut sql, database_type, change_table, table);
+    }
+
+    sql
+}
+
+/// Generate the SQL creating the change table itself, plus the trigger function on Postgres.
+fn change_table_sql(database_type: DbType, change_table: &str, notify: Option<&str>) -> String {
+    let change_table_ident = quote_ident(database_type, change_table);
+
+    match database_type {
+        DbType::Postgres if let Some(channel) = notify => format!(
+            "CREATE TABLE IF NOT EXISTS {change_table_ident} (
+  \"id\" BIGSERIAL PRIMARY KEY,
+  \"table_name\" TEXT NOT NULL,
+  \"operation\" TEXT NOT NULL,
+  \"row_data\" JSONB NOT NULL,
+  \"changed_at\" TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE OR REPLACE FUNCTION {function}() RETURNS TRIGGER AS $$
+DECLARE
+  change_id BIGINT;
+  change_row JSONB := CASE WHEN TG_OP = 'DELETE' THEN to_jsonb(OLD) ELSE to_jsonb(NEW) END;
+  payload TEXT;
+BEGIN
+  INSERT INTO {change_table_ident} (\"table_name\", \"operation\", \"row_data\")
+    VALUES (TG_TABLE_NAME, TG_OP, change_row)
+    RETURNING \"id\" INTO change_id;
+  payload := jsonb_build_object(
+    'id', change_id, 'table_name', TG_TABLE_NAME, 'operation', TG_OP, 'row_data', change_row
+  )::text;
+  -- Payloads must be shorter than 8000 bytes, larger rows are loaded from the change table.
+  IF octet_length(payload) >= 8000 THEN
+    payload := jsonb_build_object(
+      'id', change_id, 'table_name', TG_TABLE_NAME, 'operation', TG_OP
+    )::text;
+  END IF;
+  PERFORM pg_notify('{channel}', payload);
+  IF (TG_OP = 'DELETE') THEN
+    RETURN OLD;
+  END IF;
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+",
+            function = quote_ident(database_type, &format!("{change_table}_record")),
+            channel = channel.replace('\'', "''"),
+        ),
+        DbType::Postgres => format!(
+            "CREATE TABLE IF NOT EXISTS {change_table_ident} (
+  \"id\" BIGSERIAL PRIMARY KEY,
+  \"table_name\" TEXT NOT NULL,
+  \"operation\" TEXT NOT NULL,
+  \"row_data\" JSONB NOT NULL,
+  \"changed_at\" TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE OR REPLACE FUNCTION {function}() RETURNS TRIGGER AS $$
+BEGIN
+  IF (TG_OP = 'DELETE') THEN
+    INSERT INTO {change_table_ident} (\"table_name\", \"operation\", \"row_data\")
+      VALUES (TG_TABLE_NAME, TG_OP, to_jsonb(OLD));
+    RETURN OLD;
+  END IF;
+  INSERT INTO {change_table_ident} (\"table_name\", \"operation\", \"row_data\")
+    VALUES (TG_TABLE_NAME, TG_OP, to_jsonb(NEW));
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+",
+            function = quote_ident(database_type, &format!("{change_table}_record")),
+        ),
+        DbType::MySql => format!(
+            "CREATE TABLE IF NOT EXISTS {change_table_ident} (
+  `id` BIGINT AUTO_INCREMENT PRIMARY KEY,
+  `table_name` TEXT NOT NULL,
+  `operation` TEXT NOT NULL,
+  `row_data` JSON NOT NULL,
+  `changed_at` TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+"
+        ),
+        DbType::Sqlite => format!(
+            "CREATE TABLE IF NOT EXISTS {change_table_ident} (
+  \"id\" INTEGER PRIMARY KEY AUTOINCREMENT,
+  \"table_name\" TEXT NOT NULL,
+  \"operation\" TEXT NOT NULL,
+  \"row_data\" TEXT NOT NULL,
+  \"changed_at\" TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+"
+        ),
+    }
+}
+
+/// Append the SQL creating the triggers for a single table.
+fn table_triggers_sql(
+    sql: &mut String,
+    database_type: DbType,
+    change_table: &str,
+    table: &SqlTable,
+) {
+    let q = |e: &str| quote_ident(database_type, e);
+    let qualified = |e: &str| {
+        table
+            .schema
+            .as_ref()
+            .map_or_else(|| q(e), |schema| format!("{}.{}", q(schema), q(e)))
+    };
+
+    // The table of a trigger on sqlite cannot be qualified, it is always in the trigger's schema.
+    let table_ident = if matches!(database_type, DbType::Sqlite) {
+        q(&table.name)
+    } else {
+        qualified(&table.name)
+    };
+
+    if matches!(database_type, DbType::Postgres) {
+        let trigger = q(&format!("{change_table}_{}", table.name));
+        let function = q(&format!("{change_table}_record"));
+
+        let _ = write!(
+            sql,
+            "
+DROP TRIGGER IF EXISTS {trigger} ON {table_ident};
+CREATE TRIGGER {trigger} AFTER INSERT OR UPDATE OR DELETE ON {table_ident}
+  FOR EACH ROW EXECUTE FUNCTION {function}();
+"
+        );
+
+        return;
+    }
+
+    // Neither MySQL nor SQLite can serialize an entire row, so we need to spell out every column.
+    for operation in OPERATIONS {
+        // Triggers are in the schema of their table on postgres, but need to be qualified here.
+        let trigger = qualified(&format!(
+            "{change_table}_{}_{}",
+            table.name,
+            operation.to_lowercase()
+        ));
+        let row = if operation == "DELETE" { "OLD" } else { "NEW" };
+        let row_data = table
+            .columns
+            .iter()
+            .map(|e| format!("{}, {row}.{}", quote_literal(&e.name), q(&e.name)))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // Recorded by its unqualified name, like `TG_TABLE_NAME` on postgres, which the change feed
+        // of an entity filters by.
+        let insert = format!(
+            "INSERT INTO {} ({}, {}, {}) VALUES ({}, '{operation}', json_object({row_data}))",
+            q(change_table),
+            q("table_name"),
+            q("operation"),
+            q("row_data"),
+            quote_literal(&table.name),
+        );
+
+        let _ = match database_type {
+            DbType::MySql => write!(
+                sql,
+                "
+DROP TRIGGER IF EXISTS {trigger};
+CREATE TRIGGER {trigger} AFTER {operation} ON {table_ident}
+  FOR EACH ROW {insert};
+"
+            ),
+            _ => write!(
+                sql,
+                "
+DROP TRIGGER IF EXISTS {trigger};
+CREATE TRIGGER {trigger} AFTER {operation} ON {table_ident}
+BEGIN
+  {insert};
+END;
+"
+            ),
+        };
+    }
+}
@@ -0,0 +1,63 @@
+use std::io::Write;
+
+use clap::Parser;
+use eyre::Context;
+use sky_orm_sqlparse::{db::get_database_url, diff::diff_schemas};
+use tracing::{error, info};
+
+use crate::schema::{introspect_schema, read_schema};
+
+/// Check that the checked-in schema is up to date with the live database, failing if it is not
+#[derive(Parser, Debug)]
+pub struct Validate {
+    /// The URL to the database to validate the schema against. If left unset, will be pulled
+    /// from the `DATABASE_URL` environment variable, or a corresponding `.env` file instead.
+    #[arg(short, long, value_name = "DATABASE_URL")]
+    database_url: Option<String>,
+
+    /// Print the differences as JSON to stdout, instead of logging them.
+    #[arg(long)]
+    json: bool,
+}
+
+impl Validate {
+    pub async fn run(&self) -> eyre::Result<()> {
+        let Some(database_url) = self.database_url.clone().or_else(get_database_url) else {
+            return Err(eyre::eyre!(
+                "Missing database URL, either set the `DATABASE_URL` environment variable, or specify it manually via --database-url [URL]"
+            ));
+        };
+
+        let expected =
+            read_schema(&std::env::current_dir().context("Failed to determine current directory")?)
+                .await?;
+
+        let actual = introspect_schema(&database_url).await?;
+
+        let changes = diff_schemas(&expected, &actual);
+
+        if self.json {
+            writeln!(
+                std::io::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&changes).context("Failed to serialize report")?
+            )
+            .context("Failed to write report")?;
+        } else {
+            for change in &changes {
+                error!("{change}");
+            }
+        }
+
+        if !changes.is_empty() {
+            return Err(eyre::eyre!(
+                "Schema is out of date ({} differences found), run `generate-schema` to update it",
+                changes.len()
+            ));
+        }
+
+        info!("Schema is up to date");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+use darling::{FromDeriveInput, FromMeta, FromVariant, ast::Data};
+use proc_macro_error2::abort;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Ident, parse2};
+
+use crate::rename::RenameRule;
+
+/// How an enum is stored in the database.
+#[derive(FromMeta, Clone, Copy)]
+#[darling(rename_all = "snake_case")]
+enum EnumRepr {
+    /// As the (renamed) name of the variant.
+    Text,
+    /// As the discriminant of the variant.
+    I32,
+}
+
+#[derive(FromVariant)]
+#[darling(attributes(sky_orm))]
+struct DbEnumVariant {
+    ident: Ident,
+    rename: Option<String>,
+}
+
+#[derive(FromDeriveInput)]
+#[darling(attributes(sky_orm), supports(enum_unit))]
+struct DbEnumTarget {
+    ident: Ident,
+    /// Defaults to [`EnumRepr::Text`].
+    enum_repr: Option<EnumRepr>,
+    rename_all: Option<RenameRule>,
+    /// The name of the native postgres enum type backing this enum.
+    type_name: Option<String>,
+    data: Data<DbEnumVariant, ()>,
+}
+
+pub fn derive_db_enum(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse2(input).expect("Failed to parse derive input");
+
+    let target = match DbEnumTarget::from_derive_input(&input) {
+        Ok(r) => r,
+        Err(e) => return e.write_errors(),
+    };
+
+    let Some(variants) = target.data.take_enum() else {
+        abort! {
+            input, "Target is not an enum.";
+            note = "This macro must be run on an enum.";
+        };
+    };
+
+    let enum_ident = &target.ident;
+    let variant_idents = variants.iter().map(|e| &e.ident).collect::<Vec<_>>();
+
+    let names = variants
+        .iter()
+        .map(|e| {
+            e.rename.clone().unwrap_or_else(|| {
+                target.rename_all.map_or_else(
+                    || e.ident.to_string(),
+                    |rule| rule.apply(&e.ident.to_string()),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let to_name = quote! {
+        match self {
+            #(
+                Self::#variant_idents => #names,
+            )*
+        }
+    };
+
+    let from_name = quote! {
+        match value {
+            #(
+                #names => ::std::result::Result::Ok(Self::#variant_idents),
+            )*
+            other => ::std::result::Result::Err(
+                ::std::format!("Unknown variant \"{}\" of enum `{}`", other, ::std::stringify!(#enum_ident)).into()
+            ),
+        }
+    };
+
+    match (
+        target.enum_repr.unwrap_or(EnumRepr::Text),
+        &target.type_name,
+    ) {
+        (EnumRepr::Text, None) => text_impls(enum_ident, &to_name, &from_name),
+        (EnumRepr::Text, Some(type_name)) => {
+            postgres_impls(enum_ident, type_name, &to_name, &from_name)
+        }
+        (EnumRepr::I32, None) => i32_impls(enum_ident, &variant_idents),
+        (EnumRepr::I32, Some(_)) => abort! {
+            input, "Native enum types are stored as text.";
+            note = "Remove either `type_name` or `enum_repr = \"i32\"`.";
+        },
+    }
+}
+
+/// Store the enum as text on any database.
+fn text_impls(enum_ident: &Ident, to_name: &TokenStream, from_name: &TokenStream) -> TokenStream {
+    quote! {
+        impl<DB> ::sky_orm::sqlx::Type<DB> for #enum_ident
+        where
+            DB: ::sky_orm::sqlx::Database,
+            ::std::string::String: ::sky_orm::sqlx::Type<DB>,
+        {
+            fn type_info() -> <DB as ::sky_orm::sqlx::Database>::TypeInfo {
+                <::std::string::String as ::sky_orm::sqlx::Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &<DB as ::sky_orm::sqlx::Database>::TypeInfo) -> bool {
+                <::std::string::String as ::sky_orm::sqlx::Type<DB>>::compatible(ty)
+            }
+        }
+
+        impl<'q, DB> ::sky_orm::sqlx::Encode<'q, DB> for #enum_ident
+        where
+            DB: ::sky_orm::sqlx::Database,
+            ::std::string::String: ::sky_orm::sqlx::Encode<'q, DB>,
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <DB as ::sky_orm::sqlx::Database>::ArgumentBuffer<'q>,
+            ) -> ::std::result::Result<::sky_orm::sqlx::encode::IsNull, ::sky_orm::sqlx::error::BoxDynError> {
+                let value: &str = #to_name;
+                <::std::string::String as ::sky_orm::sqlx::Encode<'q, DB>>::encode(value.to_owned(), buf)
+            }
+        }
+
+        impl<'r, DB> ::sky_orm::sqlx::Decode<'r, DB> for #enum_ident
+        where
+            DB: ::sky_orm::sqlx::Database,
+            &'r str: ::sky_orm::sqlx::Decode<'r, DB>,
+        {
+            fn decode(
+                value: <DB as ::sky_orm::sqlx::Database>::ValueRef<'r>,
+            ) -> ::std::result::Result<Self, ::sky_orm::sqlx::error::BoxDynError> {
+                let value = <&'r str as ::sky_orm::sqlx::Decode<'r, DB>>::decode(value)?;
+                #from_name
+            }
+        }
+    }
+}
+
+/// Store the enum as a native postgres enum type.
+fn postgres_impls(
+    enum_ident: &Ident,
+    type_name: &str,
+    to_name: &TokenStream,
+    from_name: &TokenStream,
+) -> TokenStream {
+    quote! {
+        impl ::sky_orm::sqlx::Type<::sky_orm::sqlx::Postgres> for #enum_ident {
+            fn type_info() -> ::sky_orm::sqlx::postgres::PgTypeInfo {
+                ::sky_orm::sqlx::postgres::PgTypeInfo::with_name(#type_name)
+            }
+        }
+
+        impl<'q> ::sky_orm::sqlx::Encode<'q, ::sky_orm::sqlx::Postgres> for #enum_ident {
+            fn encode_by_ref(
+                &self,
+                buf: &mut ::sky_orm::sqlx::postgres::PgArgumentBuffer,
+            ) -> ::std::result::Result<::sky_orm::sqlx::encode::IsNull, ::sky_orm::sqlx::error::BoxDynError> {
+                let value: &str = #to_name;
+                <&str as ::sky_orm::sqlx::Encode<'q, ::sky_orm::sqlx::Postgres>>::encode_by_ref(&value, buf)
+            }
+        }
+
+        impl<'r> ::sky_orm::sqlx::Decode<'r, ::sky_orm::sqlx::Postgres> for #enum_ident {
+            fn decode(
+                value: ::sky_orm::sqlx::postgres::PgValueRef<'r>,
+            ) -> ::std::result::Result<Self, ::sky_orm::sqlx::error::BoxDynError> {
+                let value = <&'r str as ::sky_orm::sqlx::Decode<'r, ::sky_orm::sqlx::Postgres>>::decode(value)?;
+                #from_name
+            }
+        }
+    }
+}
+
+/// Store the enum as the discriminant of its variants on any database.
+fn i32_impls(enum_ident: &Ident, variant_idents: &[&Ident]) -> TokenStream {
+    quote! {
+        impl<DB> ::sky_orm::sqlx::Type<DB> for #enum_ident
+        where
+            DB: ::sky_orm::sqlx::Database,
+            i32: ::sky_orm::sqlx::Type<DB>,
+        {
+            fn type_info() -> <DB as ::sky_orm::sqlx::Database>::TypeInfo {
+                <i32 as ::sky_orm::sqlx::Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &<DB as ::sky_orm::sqlx::Database>::TypeInfo) -> bool {
+                <i32 as ::sky_orm::sqlx::Type<DB>>::compatible(ty)
+            }
+        }
+
+        impl<'q, DB> ::sky_orm::sqlx::Encode<'q, DB> for #enum_ident
+        where
+            DB: ::sky_orm::sqlx::Database,
+            i32: ::sky_orm::sqlx::Encode<'q, DB>,
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <DB as ::sky_orm::sqlx::Database>::ArgumentBuffer<'q>,
+            ) -> ::std::result::Result<::sky_orm::sqlx::encode::IsNull, ::sky_orm::sqlx::error::BoxDynError> {
+                let value = match self {
+                    #(
+                        Self::#variant_idents => Self::#variant_idents as i32,
+                    )*
+                };
+                <i32 as ::sky_orm::sqlx::Encode<'q, DB>>::encode(value, buf)
+            }
+        }
+
+        impl<'r, DB> ::sky_orm::sqlx::Decode<'r, DB> for #enum_ident
+        where
+            DB: ::sky_orm::sqlx::Database,
+            i32: ::sky_orm::sqlx::Decode<'r, DB>,
+        {
+            fn decode(
+                value: <DB as ::sky_orm::sqlx::Database>::ValueRef<'r>,
+            ) -> ::std::result::Result<Self, ::sky_orm::sqlx::error::BoxDynError> {
+                match <i32 as ::sky_orm::sqlx::Decode<'r, DB>>::decode(value)? {
+                    #(
+                        e if e == Self::#variant_idents as i32 => ::std::result::Result::Ok(Self::#variant_idents),
+                    )*
+                    other => ::std::result::Result::Err(
+                        ::std::format!("Unknown discriminant {} of enum `{}`", other, ::std::stringify!(#enum_ident)).into()
+                    ),
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,10 @@
 #![allow(clippy::expect_used)]
+// Triggered by code generated from darling's derives.
+#![allow(clippy::needless_continue)]
+mod db_enum;
 mod model;
 mod parse;
+mod rename;
 mod schema;
 
 use model::derive_database_model;
@@ -14,7 +18,13 @@ pub fn database_model(input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_error]
-#[proc_macro_derive(FromSqlxRow)]
+#[proc_macro_derive(DbEnum, attributes(sky_orm))]
+pub fn db_enum(input: TokenStream) -> TokenStream {
+    db_enum::derive_db_enum(input.into()).into()
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(FromSqlxRow, attributes(sky_orm))]
 pub fn parse_from_row(input: TokenStream) -> TokenStream {
     parse::parse_from_row(input.into()).into()
 }
@@ -24,3 +34,9 @@ pub fn parse_from_row(input: TokenStream) -> TokenStream {
 pub fn model(input: TokenStream) -> TokenStream {
     schema::model::decl_model(input.into()).into()
 }
+
+#[proc_macro_error]
+#[proc_macro]
+pub fn models(input: TokenStream) -> TokenStream {
+    schema::model::decl_models(input.into()).into()
+}
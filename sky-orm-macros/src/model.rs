@@ -1,35 +1,198 @@
 use convert_case::{Case, Casing};
-use darling::{FromDeriveInput, FromField, ast::Data};
+use darling::{
+    FromDeriveInput, FromField, FromMeta,
+    ast::Data,
+    util::{Flag, Override, PathList},
+};
 use proc_macro_error2::{abort, emit_error};
-use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{DeriveInput, Ident, Type, Visibility, parse2};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    Attribute, DeriveInput, Expr, Ident, Path, Type, TypePath, Visibility, parse2, spanned::Spanned,
+};
+
+use crate::{
+    rename::RenameRule,
+    schema::{
+        file::SchemaFile,
+        verify::{ModelField, type_class, unwrap_option, verify_model},
+    },
+};
+
+/// A type passed as attribute value, either as a path (`String`) or as a string (`"Vec<u8>"`).
+#[derive(Debug, Clone)]
+struct AttrType(Type);
+
+impl FromMeta for AttrType {
+    fn from_expr(expr: &Expr) -> darling::Result<Self> {
+        match expr {
+            Expr::Path(e) => Ok(Self(Type::Path(TypePath {
+                qself: e.qself.clone(),
+                path: e.path.clone(),
+            }))),
+            _ => Type::from_expr(expr).map(Self),
+        }
+    }
+}
 
 #[derive(FromField, Debug, Clone)]
-#[darling(attributes(sky_orm))]
+#[darling(attributes(sky_orm), forward_attrs(doc))]
 struct DeriveModelField {
     ident: Option<Ident>,
+    /// The doc comments of the field, copied to the API model and the column struct.
+    attrs: Vec<Attribute>,
     ty: Type,
     column: Option<String>,
+    /// Exclude the field from the database, filling it with its [`Default`] value on hydration.
+    skip: Flag,
+    /// Store the field as this type in the database, e.g. `try_from = String`. The field's type
+    /// must implement `TryFrom<String>` to be decoded, and `Into<String>` to be encoded.
+    try_from: Option<AttrType>,
+    /// Store the field as this type in the database, e.g. `into = String`. The field's type must
+    /// implement `From<String>` to be decoded, and `Into<String>` to be encoded.
+    into: Option<AttrType>,
+    /// Store the field as JSON in the database, via [`sqlx::types::Json`].
+    json: Flag,
+    /// The field holds an amount of money, so it must not be stored as a floating point number,
+    /// which cannot represent most amounts exactly. Amounts are stored in minor units (e.g. cents)
+    /// as integers, or as `NUMERIC` mapped to a decimal type.
+    money: Flag,
+    /// The column is of the case-insensitive `citext` type of postgres. Values compared against it
+    /// are cast to `citext`, as they would be compared as case-sensitive `text` otherwise.
+    citext: Flag,
+    /// Leave the field out of the `From` impls generated for `dto` types.
+    dto_skip: Flag,
+    /// Leave the field out of the API model, e.g. for a password hash.
+    api_skip: Flag,
+    /// Hide the value of the field in generated [`Debug`] impls and audit records, e.g. for
+    /// a password or token. Also generates the [`Debug`] impl of the model, which must then not be
+    /// derived.
+    sensitive: Flag,
+    /// The entity this field is a foreign key to, generating the corresponding
+    /// [`Related`](sky_orm::entity::relation::Related) impl.
+    belongs_to: Option<Path>,
+    /// Whether at most one entity may refer to the same entity via `belongs_to`, i.e. the column
+    /// is unique.
+    one_to_one: Flag,
+    /// A name for the relation of `belongs_to`, re-exporting the column struct under it, e.g.
+    /// `relation = Sender` for a `sender_id` field.
+    relation: Option<Ident>,
     vis: Visibility,
 }
 
+/// A struct holding the fields of the model that may be exposed by an API.
+#[derive(FromMeta)]
+struct ApiModelTarget {
+    /// The name of the struct, `ApiModel` by default.
+    name: Option<Ident>,
+    /// Derives for the struct, e.g. `derive(serde::Serialize, utoipa::ToSchema)`.
+    derive: Option<PathList>,
+}
+
+/// The other side of a relation, whose foreign key refers to this entity's primary key.
+#[derive(FromMeta)]
+struct InverseRelationTarget {
+    /// The entity holding the foreign key.
+    entity: Path,
+    /// The column on `entity` holding the foreign key.
+    fk: Path,
+}
+
 #[derive(FromDeriveInput)]
 #[darling(attributes(sky_orm))]
 struct DeriveModelTarget {
     ident: Ident,
     table: Option<String>,
-    primary_key: Ident,
+    /// The schema (or namespace) of the table, if it's not in the default one.
+    schema: Option<String>,
+    /// The naming convention of the columns, applied to the names of all fields without a `column`,
+    /// as in serde's `rename_all`.
+    rename_all: Option<RenameRule>,
+    primary_key: Option<Ident>,
+    database: Option<Path>,
+    /// The name of the generated entity struct, `Entity` by default.
+    entity: Option<Ident>,
+    /// The name of the generated active model struct, `ActiveModel` by default.
+    active_model: Option<Ident>,
+    /// The name of the generated columns module, `columns` by default.
+    columns: Option<Ident>,
+    /// Check the model against the schema file at compile time, if one is present.
+    verify_schema: Flag,
+    /// Additional derives for the generated active model, e.g. `derive(PartialEq)`.
+    /// `Clone`, `Debug` and `Default` are always implemented.
+    derive: Option<PathList>,
+    /// Generate a copy of the model without the fields marked `api_skip`, to be exposed by an API.
+    api: Option<ApiModelTarget>,
+    /// Types to generate `From<Model>` impls for, by moving over the fields of the same name.
+    #[darling(multiple)]
+    dto: Vec<Path>,
+    /// Entities this one refers to via foreign keys that are not declared with `belongs_to`, e.g.
+    /// in hand-written `Related` impls, so that sessions write them first.
+    #[darling(multiple)]
+    references: Vec<Path>,
+    /// Entities that refer to this one via a foreign key, with many of them per entity of this one.
+    /// The relation itself is declared on their side, e.g. with `belongs_to`, this only adds it to
+    /// the metadata of this entity and checks that it's there.
+    #[darling(multiple)]
+    has_many: Vec<InverseRelationTarget>,
+    /// Entities that refer to this one via a unique foreign key, like `has_many`.
+    #[darling(multiple)]
+    has_one: Vec<InverseRelationTarget>,
+    /// Generate a patch struct holding an [`Option`] for every field, `Patch` by default.
+    patch: Option<Override<Ident>>,
+    /// Derive `Deserialize` for the patch struct, and generate `from_json` constructors for the
+    /// active model. Implies `patch`.
+    patch_json: Flag,
+    /// Generate a builder of models with default values for testing, `ModelFactory` by default.
+    /// Only generated if the `testing` feature is enabled, and requires the model to be [`Clone`].
+    factory: Option<Override<Ident>>,
+    read_only: Flag,
+    /// Implement `ActiveModelHooks` for the active model by hand, instead of generating an empty
+    /// implementation.
+    hooks: Flag,
+    /// Record the writes of this entity in the audit log, see `AuditedModel`.
+    audit: Flag,
+    /// Restrict all statements of this entity to the current tenant, see `TenantScoped`.
+    tenant_scoped: Flag,
+    /// Filter all statements of this entity by its `EntityScope`.
+    default_scope: Flag,
     data: Data<(), DeriveModelField>,
 }
 
+/// How a field is converted from the type of its column when hydrating a model.
+#[derive(Clone, Copy)]
+enum Conversion {
+    TryFrom,
+    From,
+    Json,
+}
+
 #[derive(Clone)]
 struct TargetColumn {
     field_ident: Ident,
     db_name: String,
     struct_name: String,
+    /// The type of the column, which differs from the field's type if it is converted.
     ty: Type,
+    field_ty: Type,
+    conversion: Option<Conversion>,
     field_vis: Visibility,
+    sensitive: bool,
+    citext: bool,
+    docs: Vec<Attribute>,
+}
+
+impl TargetColumn {
+    /// Convert `value`, an expression of the field's type, to the type of the column.
+    fn to_column_value(&self, value: &TokenStream) -> TokenStream {
+        match self.conversion {
+            Some(Conversion::TryFrom | Conversion::From) => {
+                quote! { ::std::convert::Into::into(#value) }
+            }
+            Some(Conversion::Json) => quote! { ::sky_orm::sqlx::types::Json(#value) },
+            None => value.clone(),
+        }
+    }
 }
 
 // TODO: Refactor this using `syn-parse-helpers` to cut down on line length.
@@ -49,9 +212,36 @@ pub fn derive_database_model(input: TokenStream) -> TokenStream {
         };
     };
 
+    // The generated entity, columns and active model are not generic, so they could not refer to
+    // the struct's parameters.
+    if !input.generics.params.is_empty() {
+        abort! {
+            input.generics, "Models cannot have generic parameters or lifetimes.";
+            note = "Define a separate model for each instantiation instead, e.g. with a macro.";
+        };
+    }
+
+    let skipped_fields = struct_data
+        .fields
+        .iter()
+        .filter(|e| e.skip.is_present())
+        .filter_map(|e| e.ident.as_ref())
+        .collect::<Vec<_>>();
+
+    if let Some(primary_key) = target
+        .primary_key
+        .as_ref()
+        .filter(|e| skipped_fields.contains(e))
+    {
+        abort! {
+            primary_key, "The primary key cannot be skipped."
+        };
+    }
+
     let columns = struct_data
         .fields
         .iter()
+        .filter(|e| !e.skip.is_present())
         .map(|e| {
             let Some(ident) = &e.ident else {
                 abort! {
@@ -60,12 +250,55 @@ pub fn derive_database_model(input: TokenStream) -> TokenStream {
                 };
             };
 
+            let (ty, conversion) = match (&e.try_from, &e.into, e.json.is_present()) {
+                (Some(ty), None, false) => (ty.0.clone(), Some(Conversion::TryFrom)),
+                (None, Some(ty), false) => (ty.0.clone(), Some(Conversion::From)),
+                (None, None, true) => {
+                    let ty = &e.ty;
+                    (
+                        parse2(quote! { ::sky_orm::sqlx::types::Json<#ty> })
+                            .expect("Failed to parse JSON type"),
+                        Some(Conversion::Json),
+                    )
+                }
+                (None, None, false) => (e.ty.clone(), None),
+                _ => abort! {
+                    ident, "Only one of `try_from`, `into` and `json` may be used on the same field."
+                },
+            };
+
+            if e.money.is_present() && type_class(unwrap_option(&ty).1) == Some("float") {
+                emit_error! {
+                    ty, "Money cannot be stored as a floating point number.";
+                    note = "Store amounts in minor units as `i64`, or as `NUMERIC` with a decimal type, e.g. `sky_orm::sqlx::types::Decimal`.";
+                };
+            }
+
+            if e.citext.is_present()
+                && type_class(unwrap_option(&ty).1).is_some_and(|class| class != "string")
+            {
+                emit_error! {
+                    ty, "Columns of type `citext` hold strings.";
+                    note = "Use `String`, or convert the field with `#[sky_orm(try_from = String)]`.";
+                };
+            }
+
             TargetColumn {
                 field_ident: ident.clone(),
-                db_name: e.column.clone().unwrap_or_else(|| ident.to_string()),
+                db_name: e.column.clone().unwrap_or_else(|| {
+                    target.rename_all.map_or_else(
+                        || ident.to_string(),
+                        |rule| rule.apply(&ident.to_string()),
+                    )
+                }),
                 struct_name: ident.to_string().to_case(Case::Pascal),
-                ty: e.ty.clone(),
+                ty,
+                field_ty: e.ty.clone(),
+                conversion,
                 field_vis: e.vis.clone(),
+                sensitive: e.sensitive.is_present(),
+                citext: e.citext.is_present(),
+                docs: e.attrs.clone(),
             }
         })
         .collect::<Vec<_>>();
@@ -89,56 +322,178 @@ pub fn derive_database_model(input: TokenStream) -> TokenStream {
         }
     }
 
+    // The column structs share their module with the `Col` enum.
+    if let Some(clashing) = columns.iter().find(|e| e.struct_name == "Col") {
+        abort! {
+            clashing.field_ident, "Fields cannot be named `col`.";
+            note = "Its column struct would clash with the `Col` enum of the columns module, rename the field and use #[sky_orm(column = \"col\")] to keep the column's name.";
+        }
+    }
+
+    // The accessors of the active model are inherent methods, which would shadow the methods of
+    // the traits it implements.
+    if !target.read_only.is_present() {
+        const TRAIT_METHODS: &[&str] = &[
+            "values",
+            "changed_values",
+            "set_from",
+            "audit_values",
+            "before_insert",
+            "after_insert",
+            "before_update",
+            "after_update",
+            "before_delete",
+            "after_delete",
+            "clone",
+            "clone_from",
+            "default",
+            "fmt",
+        ];
+
+        for e in &columns {
+            let ident = e.field_ident.to_string();
+
+            if let Some(clashing) = [
+                ident.clone(),
+                format!("set_{ident}"),
+                format!("clear_{ident}"),
+            ]
+            .into_iter()
+            .find(|name| TRAIT_METHODS.contains(&name.as_str()))
+            {
+                abort! {
+                    e.field_ident, "Fields cannot be named `{}`.", ident;
+                    note = "The active model's accessor `{}` would shadow the trait method of the same name, rename the field and use #[sky_orm(column = \"{}\")] to keep the column's name.", clashing, e.db_name;
+                }
+            }
+        }
+    }
+
     let model_ident = &target.ident;
 
-    let Some(primary_key_struct_ident) = columns.iter().find_map(|e| {
-        if e.field_ident.eq(&target.primary_key) {
-            Some(Ident::new(e.struct_name.as_str(), e.field_ident.span()))
-        } else {
-            None
+    let database = target
+        .database
+        .as_ref()
+        .map_or_else(|| quote! { ::sky_orm::sqlx::Postgres }, |e| quote! { #e });
+
+    let entity_ident = target
+        .entity
+        .clone()
+        .unwrap_or_else(|| Ident::new("Entity", Span::call_site()));
+    let active_model_ident = target
+        .active_model
+        .clone()
+        .unwrap_or_else(|| Ident::new("ActiveModel", Span::call_site()));
+    let columns_ident = target
+        .columns
+        .clone()
+        .unwrap_or_else(|| Ident::new("columns", Span::call_site()));
+
+    let primary_key_struct_ident = target.primary_key.as_ref().and_then(|primary_key| {
+        columns.iter().find_map(|e| {
+            if e.field_ident.eq(primary_key) {
+                Some(Ident::new(e.struct_name.as_str(), e.field_ident.span()))
+            } else {
+                None
+            }
+        })
+    });
+
+    // Primary keys are compared and returned as the type of their column, which converted fields
+    // do not have.
+    if let Some(primary_key) = target
+        .primary_key
+        .as_ref()
+        .and_then(|primary_key| columns.iter().find(|e| e.field_ident.eq(primary_key)))
+        .filter(|e| e.conversion.is_some())
+    {
+        abort! {
+            primary_key.field_ident, "Primary keys cannot be converted with `try_from`, `into` or `json`.";
+            note = "Use the type of the column for the field, and convert it where needed instead.";
         }
-    }) else {
+    }
+
+    // Only read-only entities (e.g. views) may be declared without a primary key.
+    if primary_key_struct_ident.is_none() && !target.read_only.is_present() {
         abort! {
             input, "Missing primary key.";
             note = "You need to specify which column is supposed to act as the primary key, using #[sky_orm(primary_key = field_name)]";
+            note = "If this model is backed by a view, mark it as #[sky_orm(read_only)] instead.";
         }
-    };
+    }
 
     let columns_module = {
+        // Types are aliased outside of the columns module, since they would otherwise resolve to
+        // column structs of the same name, or not at all if they are local to the model's module.
+        let type_alias_ident = |e: &TargetColumn| {
+            Ident::new(
+                &format!("__{model_ident}{}Type", e.struct_name),
+                Span::call_site(),
+            )
+        };
+
+        let type_aliases = columns.iter().map(|e| {
+            let alias = type_alias_ident(e);
+            let ty = &e.ty;
+
+            quote! {
+                #[doc(hidden)]
+                type #alias = #ty;
+            }
+        });
+
         let column_impls = columns.iter().map(|e| {
             let struct_name = Ident::new(e.struct_name.as_str(), e.field_ident.span());
             let db_name = &e.db_name;
-            let ty = &e.ty;
+            let alias = type_alias_ident(e);
+            let cast = e.citext.then(|| {
+                quote! {
+                    const CAST: ::std::option::Option<&'static str> = ::std::option::Option::Some("citext");
+                }
+            });
+
+            let docs = &e.docs;
 
             quote! {
+                #(#docs)*
                 pub struct #struct_name;
 
                 impl ::sky_orm::entity::column::Column for #struct_name {
-                    type Type = #ty;
-                    type Entity = super::Entity;
+                    type Type = super::#alias;
+                    type Entity = super::#entity_ident;
                     const NAME: &'static str = #db_name;
+                    #cast
                 }
             }
         });
 
-        let get_column_impls = columns.iter().map(|e| {
+        // Converted fields don't hold a value of their column's type that could be borrowed.
+        let get_column_impls = columns.iter().filter(|e| e.conversion.is_none()).map(|e| {
             let field_ident = &e.field_ident;
             let column_struct_name = Ident::new(e.struct_name.as_str(), field_ident.span());
 
             quote! {
-                impl ::sky_orm::entity::model::GetColumn<columns::#column_struct_name> for #model_ident {
-                    fn get(&self) -> &<columns::#column_struct_name as ::sky_orm::entity::column::Column>::Type {
+                impl ::sky_orm::entity::model::GetColumn<#columns_ident::#column_struct_name> for #model_ident {
+                    fn get(&self) -> &<#columns_ident::#column_struct_name as ::sky_orm::entity::column::Column>::Type {
                         &self.#field_ident
                     }
                 }
             }
         });
 
+        let column_enum = column_enum_impl(&entity_ident, &columns);
+
         quote! {
-            pub mod columns {
+            #(
+                #type_aliases
+            )*
+
+            pub mod #columns_ident {
                 #(
                     #column_impls
                 )*
+
+                #column_enum
             }
 
             #(
@@ -147,28 +502,148 @@ pub fn derive_database_model(input: TokenStream) -> TokenStream {
         }
     };
 
-    let entity_impl = {
-        let table_name = target
-            .table
-            .unwrap_or_else(|| target.ident.to_string().to_case(Case::Snake));
+    let table_name = target
+        .table
+        .clone()
+        .unwrap_or_else(|| target.ident.to_string().to_case(Case::Snake));
+
+    let qualified_table_name = target.schema.as_ref().map_or_else(
+        || format!("\"{table_name}\""),
+        |schema| format!("\"{schema}\".\"{table_name}\""),
+    );
+
+    let schema_dependency = target
+        .verify_schema
+        .is_present()
+        .then(SchemaFile::read_if_present)
+        .flatten()
+        .map(|schema_file| {
+            verify_model(
+                &schema_file.schema,
+                &target.schema.as_ref().map_or_else(
+                    || table_name.clone(),
+                    |schema| format!("{schema}.{table_name}"),
+                ),
+                target.ident.span(),
+                columns.iter().map(|e| ModelField {
+                    ident: &e.field_ident,
+                    column: &e.db_name,
+                    ty: &e.ty,
+                }),
+            );
 
+            schema_file.dependency_tokens()
+        });
+
+    let entity_impl = {
         let column_names_decl = columns.iter().map(|e| &e.db_name);
 
+        let base_select = format!(
+            "SELECT {} FROM {qualified_table_name}",
+            columns
+                .iter()
+                .enumerate()
+                .map(|(i, e)| format!("\"{table_name}\".\"{}\" AS \"__c{i}\"", e.db_name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let referenced_tables_decl = struct_data
+            .fields
+            .iter()
+            .filter(|e| !e.skip.is_present())
+            .filter_map(|e| e.belongs_to.as_ref())
+            .chain(&target.references)
+            .map(|e| {
+                quote! {
+                    <#e as ::sky_orm::entity::Entity>::TABLE_NAME
+                }
+            });
+
+        let schema_decl = target.schema.as_ref().map(|e| {
+            quote! {
+                const SCHEMA: ::std::option::Option<&'static str> = ::std::option::Option::Some(#e);
+            }
+        });
+
+        let tenant_filter = target.tenant_scoped.is_present().then(|| {
+            quote! {
+                <<Self as ::sky_orm::entity::TenantScoped>::TenantColumn as ::sky_orm::entity::column::ComparableColumn>::eq(
+                    <Self as ::sky_orm::entity::TenantScoped>::current_tenant(),
+                )
+            }
+        });
+
+        let scope_filter = target.default_scope.is_present().then(|| {
+            quote! {
+                <Self as ::sky_orm::entity::EntityScope>::default_scope()
+            }
+        });
+
+        // The scope is bracketed, so that a scope using `OR` does not swallow the tenant's
+        // condition.
+        let implicit_filter_decl = tenant_filter
+            .into_iter()
+            .chain(scope_filter)
+            .reduce(|left, right| quote! { #left.and(#right.brackets()) })
+            .map(|filter| {
+                quote! {
+                    fn implicit_filter() -> ::std::option::Option<
+                        ::std::boxed::Box<dyn ::sky_orm::query::PushToQuery<Self::Database>>,
+                    > {
+                        ::std::option::Option::Some(::std::boxed::Box::new(#filter))
+                    }
+                }
+            });
+
+        let meta_decl = meta_decl(
+            target.primary_key.as_ref(),
+            &target.has_many,
+            &target.has_one,
+            &struct_data.fields,
+            &columns,
+            &database,
+        );
+
+        let primary_key_impl = primary_key_struct_ident.map(|e| {
+            quote! {
+                impl ::sky_orm::entity::HasPrimaryKey for #entity_ident {
+                    type PrimaryKeyColumn = #columns_ident::#e;
+                }
+            }
+        });
+
         quote! {
-            pub struct Entity;
+            pub struct #entity_ident;
 
-            impl ::sky_orm::entity::Entity for Entity {
-                type PrimaryKeyColumn = columns::#primary_key_struct_ident;
+            #primary_key_impl
 
+            impl ::sky_orm::entity::Entity for #entity_ident {
                 type Model = #model_ident;
 
-                type Database = ::sky_orm::sqlx::Postgres;
+                type Database = #database;
+
+                type Col = #columns_ident::Col;
 
                 const TABLE_NAME: &'static str = #table_name;
 
+                #schema_decl
+
+                const QUALIFIED_TABLE_NAME: &'static str = #qualified_table_name;
+
                 const COLUMN_NAMES: &[&'static str] = &[
                     #(#column_names_decl),*
                 ];
+
+                const BASE_SELECT: &'static str = #base_select;
+
+                const REFERENCED_TABLES: &[&'static str] = &[
+                    #(#referenced_tables_decl),*
+                ];
+
+                #implicit_filter_decl
+
+                #meta_decl
             }
         }
     };
@@ -178,37 +653,85 @@ pub fn derive_database_model(input: TokenStream) -> TokenStream {
             let field_ident = &e.field_ident;
             let column_struct_name = Ident::new(e.struct_name.as_str(), field_ident.span());
 
+            let value = quote! {
+                #columns_ident::#column_struct_name::value_from_row(row)?
+            };
+
+            match e.conversion {
+                Some(Conversion::TryFrom) => {
+                    let db_name = &e.db_name;
+
+                    quote! {
+                        #field_ident: ::std::convert::TryFrom::try_from(#value).map_err(|e| {
+                            ::sky_orm::sqlx::Error::ColumnDecode {
+                                index: #db_name.to_string(),
+                                source: ::std::convert::Into::into(e),
+                            }
+                        })?,
+                    }
+                }
+                Some(Conversion::From) => quote! {
+                    #field_ident: ::std::convert::From::from(#value),
+                },
+                Some(Conversion::Json) => quote! {
+                    #field_ident: #value.0,
+                },
+                None => quote! {
+                    #field_ident: #value,
+                },
+            }
+        });
+
+        let skipped_field_assignments = skipped_fields.iter().map(|e| {
             quote! {
-                #field_ident: columns::#column_struct_name::value_from_row(row)?,
+                #e: ::std::default::Default::default(),
             }
         });
 
         let active_model_field_assignments = columns.iter().map(|e| {
             let ident = &e.field_ident;
+            let value = e.to_column_value(&quote! { self.#ident });
 
             quote! {
-                #ident: ::sky_orm::entity::model::ActiveModelValue::Unchanged(self.#ident),
+                #ident: ::sky_orm::entity::model::ActiveModelValue::Unchanged(#value),
             }
         });
 
-        quote! {
-            impl ::sky_orm::entity::model::Model for #model_ident {
-                type Entity = Entity;
-                type ActiveModel = ActiveModel;
+        let into_active_model_impl = (!target.read_only.is_present()).then(|| {
+            quote! {
+                impl ::sky_orm::entity::model::IntoActiveModel for #model_ident {
+                    type ActiveModel = #active_model_ident;
 
-                fn into_active(self) -> Self::ActiveModel {
-                    ActiveModel {
-                        #(
-                            #active_model_field_assignments
-                        )*
+                    fn into_active(self) -> Self::ActiveModel {
+                        #active_model_ident {
+                            #(
+                                #active_model_field_assignments
+                            )*
+                        }
                     }
                 }
             }
+        });
 
-            impl ::sky_orm::query::parse::ParseFromRow<::sky_orm::sqlx::Postgres> for #model_ident {
-                fn parse_from_row(row: &<::sky_orm::sqlx::Postgres as ::sky_orm::sqlx::Database>::Row) -> ::std::result::Result<Self, ::sky_orm::sqlx::Error>
+        let debug_impl = struct_data
+            .fields
+            .iter()
+            .any(|e| e.sensitive.is_present())
+            .then(|| model_debug_impl(model_ident, &struct_data.fields));
+
+        quote! {
+            impl ::sky_orm::entity::model::Model for #model_ident {
+                type Entity = #entity_ident;
+            }
+
+            #debug_impl
+
+            #into_active_model_impl
+
+            impl ::sky_orm::query::parse::ParseFromRow<#database> for #model_ident {
+                fn parse_from_row(row: &<#database as ::sky_orm::sqlx::Database>::Row) -> ::std::result::Result<Self, ::sky_orm::sqlx::Error>
                 where
-                    for<'a> &'a str: ::sky_orm::sqlx::ColumnIndex<<::sky_orm::sqlx::Postgres as ::sky_orm::sqlx::Database>::Row>,
+                    for<'a> &'a str: ::sky_orm::sqlx::ColumnIndex<<#database as ::sky_orm::sqlx::Database>::Row>,
                 {
                     use ::sky_orm::entity::column::Column;
 
@@ -216,43 +739,924 @@ pub fn derive_database_model(input: TokenStream) -> TokenStream {
                         #(
                             #column_field_assignments
                         )*
+                        #(
+                            #skipped_field_assignments
+                        )*
                     })
                 }
             }
         }
     };
 
-    let active_model_impl = {
+    let active_model_impl = (!target.read_only.is_present()).then(|| {
         let active_model_field_decls = columns.iter().map(|e| {
             let ident = &e.field_ident;
             let ty = &e.ty;
             let vis = &e.field_vis;
 
             quote! {
-                #vis #ident: ::sky_orm::entity::model::ActiveModelValue<#ty, ::sky_orm::sqlx::Postgres>,
+                #vis #ident: ::sky_orm::entity::model::ActiveModelValue<#ty, #database>,
+            }
+        });
+
+        let active_model_values = columns.iter().map(|e| {
+            let ident = &e.field_ident;
+            let db_name = &e.db_name;
+
+            quote! {
+                (#db_name, self.#ident.to_query_variable()),
+            }
+        });
+
+        let active_model_changed_values = columns.iter().map(|e| {
+            let ident = &e.field_ident;
+            let db_name = &e.db_name;
+
+            quote! {
+                (#db_name, self.#ident.is_set().then(|| self.#ident.to_query_variable()).flatten()),
+            }
+        });
+
+        let active_model_accessors = columns.iter().map(|e| {
+            let ident = &e.field_ident;
+            let ty = &e.ty;
+            let vis = &e.field_vis;
+            let setter = Ident::new(&format!("set_{ident}"), ident.span());
+            let clearer = Ident::new(&format!("clear_{ident}"), ident.span());
+
+            quote! {
+                /// The value of this column, if it is set.
+                #[must_use]
+                #vis fn #ident(&self) -> ::std::option::Option<&#ty> {
+                    self.#ident.get()
+                }
+
+                /// Set the value of this column.
+                #vis fn #setter(&mut self, value: #ty) {
+                    self.#ident.set(value);
+                }
+
+                /// Unset the value of this column, so that it is not written to the database.
+                #vis fn #clearer(&mut self) {
+                    self.#ident.clear();
+                }
+            }
+        });
+
+        let active_model_field_idents = columns.iter().map(|e| &e.field_ident).collect::<Vec<_>>();
+
+        let active_model_debug_fields = columns.iter().map(|e| {
+            let ident = &e.field_ident;
+
+            if e.sensitive {
+                quote! { .field(::std::stringify!(#ident), &self.#ident.redacted()) }
+            } else {
+                quote! { .field(::std::stringify!(#ident), &self.#ident) }
+            }
+        });
+        let active_model_ident_str = active_model_ident.to_string();
+
+        let hooks_impl = (!target.hooks.is_present()).then(|| {
+            quote! {
+                impl ::sky_orm::entity::model::ActiveModelHooks for #active_model_ident {}
+            }
+        });
+
+        let audited_impl = if target.audit.is_present() {
+            let audit_values = columns.iter().map(|e| {
+                let ident = &e.field_ident;
+                let db_name = &e.db_name;
+
+                if e.sensitive {
+                    quote! {
+                        (#db_name, self.#ident.get().map(|_| ::sky_orm::serde_json::to_value(::sky_orm::entity::model::Redacted))),
+                    }
+                } else {
+                    quote! {
+                        (#db_name, self.#ident.get().map(::sky_orm::serde_json::to_value)),
+                    }
+                }
+            });
+
+            quote! {
+                impl ::sky_orm::entity::model::AuditedModel for #active_model_ident {
+                    const AUDITED: bool = true;
+
+                    fn audit_values(&self) -> ::std::vec::Vec<(&'static str, ::sky_orm::serde_json::Value)> {
+                        [
+                            #(
+                                #audit_values
+                            )*
+                        ]
+                        .into_iter()
+                        .filter_map(|(name, value)| value.map(|value| (name, value.unwrap_or_default())))
+                        .collect()
+                    }
+                }
+            }
+        } else {
+            quote! {
+                impl ::sky_orm::entity::model::AuditedModel for #active_model_ident {}
+            }
+        };
+
+        let derives = target.derive.as_ref().map(|e| {
+            let paths = e.iter();
+
+            quote! {
+                #[derive(#(#paths),*)]
             }
         });
 
         quote! {
-            pub struct ActiveModel {
+            #derives
+            pub struct #active_model_ident {
                 #(
                     #active_model_field_decls
                 )*
             }
 
-            impl ::sky_orm::entity::model::ActiveModel for ActiveModel {
+            impl #active_model_ident {
+                #(
+                    #active_model_accessors
+                )*
+            }
+
+            impl ::std::clone::Clone for #active_model_ident {
+                fn clone(&self) -> Self {
+                    Self {
+                        #(
+                            #active_model_field_idents: self.#active_model_field_idents.clone(),
+                        )*
+                    }
+                }
+            }
+
+            impl ::std::default::Default for #active_model_ident {
+                fn default() -> Self {
+                    Self {
+                        #(
+                            #active_model_field_idents: ::sky_orm::entity::model::ActiveModelValue::NotSet(::std::marker::PhantomData),
+                        )*
+                    }
+                }
+            }
+
+            impl ::std::fmt::Debug for #active_model_ident {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.debug_struct(#active_model_ident_str)
+                        #(
+                            #active_model_debug_fields
+                        )*
+                        .finish()
+                }
+            }
+
+            #hooks_impl
+
+            #audited_impl
+
+            impl ::sky_orm::entity::model::ActiveModel for #active_model_ident {
                 type Model = #model_ident;
+
+                fn values(&self) -> ::std::vec::Vec<::sky_orm::entity::model::ColumnValue<#database>> {
+                    [
+                        #(
+                            #active_model_values
+                        )*
+                    ]
+                    .into_iter()
+                    .filter_map(|(name, value)| value.map(|value| (name, value)))
+                    .collect()
+                }
+
+                fn changed_values(&self) -> ::std::vec::Vec<::sky_orm::entity::model::ColumnValue<#database>> {
+                    [
+                        #(
+                            #active_model_changed_values
+                        )*
+                    ]
+                    .into_iter()
+                    .filter_map(|(name, value)| value.map(|value| (name, value)))
+                    .collect()
+                }
             }
         }
+    });
+
+    let patch_ident = match (&target.patch, target.patch_json.is_present()) {
+        (Some(Override::Explicit(ident)), _) => Some(ident.clone()),
+        (Some(Override::Inherit), _) | (None, true) => Some(Ident::new("Patch", Span::call_site())),
+        (None, false) => None,
     };
 
+    if patch_ident.is_some() && target.read_only.is_present() {
+        abort! {
+            input, "Read-only models cannot have a patch struct."
+        };
+    }
+
+    if target.factory.is_some() && target.read_only.is_present() {
+        abort! {
+            input, "Read-only models cannot have a factory, since they cannot be inserted."
+        };
+    }
+
+    if target.audit.is_present() && target.read_only.is_present() {
+        abort! {
+            input, "Read-only models cannot be audited, since they are never written."
+        };
+    }
+
+    let patch_impl = patch_ident.as_ref().map(|e| {
+        patch_impl(
+            e,
+            &active_model_ident,
+            &columns,
+            target.patch_json.is_present(),
+        )
+    });
+
+    let relation_impls = {
+        let belongs_to_impls = struct_data.fields.iter().filter(|e| !e.skip.is_present()).filter_map(|e| {
+            let target_entity = e.belongs_to.as_ref()?;
+            let ident = e.ident.as_ref()?;
+            let column_struct_name =
+                Ident::new(&ident.to_string().to_case(Case::Pascal), ident.span());
+            let relation_type = if e.one_to_one.is_present() {
+                quote! { ::sky_orm::entity::relation::OneToOne }
+            } else {
+                quote! { ::sky_orm::entity::relation::ManyToOne }
+            };
+
+            let relation_name = e.relation.as_ref().map(|name| {
+                let vis = &e.vis;
+
+                quote! {
+                    #vis use #columns_ident::#column_struct_name as #name;
+                }
+            });
+
+            Some(quote! {
+                impl ::sky_orm::entity::relation::Related<#target_entity, #columns_ident::#column_struct_name> for #entity_ident {
+                    type RelationType = #relation_type;
+                }
+
+                #relation_name
+            })
+        });
+
+        let inverse_impls = target
+            .has_many
+            .iter()
+            .map(|e| (e, quote! { ::sky_orm::entity::relation::ManyToOne }))
+            .chain(
+                target
+                    .has_one
+                    .iter()
+                    .map(|e| (e, quote! { ::sky_orm::entity::relation::OneToOne })),
+            )
+            .map(|(e, relation_type)| {
+                let other_entity = &e.entity;
+                let fk = &e.fk;
+
+                // The `Related` impl belongs to the other side, and `InverseRelated` follows from
+                // it, so implementing either here would conflict with the declaration over there.
+                quote! {
+                    const _: () = {
+                        fn assert_related()
+                        where
+                            #other_entity: ::sky_orm::entity::relation::Related<#entity_ident, #fk, RelationType = #relation_type>,
+                        {
+                        }
+                    };
+                }
+            });
+
+        belongs_to_impls.chain(inverse_impls).collect::<Vec<_>>()
+    };
+
+    let dto_impls = {
+        let dto_fields = struct_data
+            .fields
+            .iter()
+            .filter(|e| !e.dto_skip.is_present())
+            .filter_map(|e| e.ident.as_ref())
+            .collect::<Vec<_>>();
+
+        target.dto.iter().map(move |dto| {
+            quote! {
+                impl ::std::convert::From<#model_ident> for #dto {
+                    fn from(value: #model_ident) -> Self {
+                        Self {
+                            #(
+                                #dto_fields: value.#dto_fields,
+                            )*
+                        }
+                    }
+                }
+            }
+        })
+    };
+
+    let api_model_impl = target
+        .api
+        .as_ref()
+        .map(|e| api_model_impl(e, model_ident, &struct_data.fields));
+
+    let factory_impl = target
+        .factory
+        .as_ref()
+        .filter(|_| cfg!(feature = "testing"))
+        .map(|e| {
+            let factory_ident = match e {
+                Override::Explicit(ident) => ident.clone(),
+                Override::Inherit => Ident::new("ModelFactory", Span::call_site()),
+            };
+
+            factory_impl(
+                &factory_ident,
+                model_ident,
+                &entity_ident,
+                &struct_data.fields,
+                &columns,
+            )
+        });
+
+    // Spanned to the fields, so that unsupported types are reported there rather than wherever the
+    // generated code first requires the bounds of the column type. The bounds are checked as part of
+    // the signature rather than a body, so that these errors are also listed first.
+    let type_assertions = columns.iter().map(|e| {
+        let ty = &e.ty;
+
+        quote_spanned! {e.field_ty.span()=>
+            const _: fn() = {
+                fn assert_column_type() where #ty: ::sky_orm::entity::column::ColumnType<#database> {}
+                assert_column_type
+            };
+        }
+    });
+
     quote! {
+        #(
+            #type_assertions
+        )*
+
         #model_impl
 
         #active_model_impl
 
+        #patch_impl
+
+        #(
+            #dto_impls
+        )*
+
+        #api_model_impl
+
+        #factory_impl
+
+        #(
+            #relation_impls
+        )*
+
         #entity_impl
 
         #columns_module
+
+        #schema_dependency
+    }
+}
+
+/// The implementation of `Entity::meta`.
+fn meta_decl(
+    primary_key: Option<&Ident>,
+    has_many: &[InverseRelationTarget],
+    has_one: &[InverseRelationTarget],
+    fields: &[DeriveModelField],
+    columns: &[TargetColumn],
+    database: &TokenStream,
+) -> TokenStream {
+    let column_metas = columns.iter().map(|e| {
+        let db_name = &e.db_name;
+        let ty = &e.ty;
+        let nullable = unwrap_option(ty).0;
+
+        quote! {
+            ::sky_orm::entity::meta::ColumnMeta {
+                name: #db_name,
+                sql_type: ::sky_orm::sqlx::TypeInfo::name(
+                    &<#ty as ::sky_orm::sqlx::Type<#database>>::type_info(),
+                )
+                .to_string(),
+                rust_type: ::std::any::type_name::<#ty>(),
+                nullable: #nullable,
+            }
+        }
+    });
+
+    let primary_key = primary_key.map_or_else(
+        || quote! { ::std::option::Option::None },
+        |primary_key| {
+            let db_name = columns
+                .iter()
+                .find(|e| e.field_ident.eq(primary_key))
+                .map(|e| &e.db_name);
+
+            quote! { ::std::option::Option::Some(#db_name) }
+        },
+    );
+
+    let belongs_to_metas = fields
+        .iter()
+        .filter(|e| !e.skip.is_present())
+        .filter_map(|e| {
+            let target_entity = e.belongs_to.as_ref()?;
+            let db_name = columns
+                .iter()
+                .find(|c| e.ident.as_ref() == Some(&c.field_ident))
+                .map(|c| &c.db_name)?;
+            let kind = if e.one_to_one.is_present() {
+                quote! { OneToOne }
+            } else {
+                quote! { ManyToOne }
+            };
+
+            Some(quote! {
+                ::sky_orm::entity::meta::RelationMeta {
+                    kind: ::sky_orm::entity::meta::RelationKind::#kind,
+                    table: <#target_entity as ::sky_orm::entity::Entity>::TABLE_NAME,
+                    foreign_key: #db_name,
+                }
+            })
+        });
+
+    let inverse_metas = has_many
+        .iter()
+        .map(|e| (e, quote! { HasMany }))
+        .chain(has_one.iter().map(|e| (e, quote! { HasOne })))
+        .map(|(e, kind)| {
+            let other_entity = &e.entity;
+            let fk = &e.fk;
+
+            quote! {
+                ::sky_orm::entity::meta::RelationMeta {
+                    kind: ::sky_orm::entity::meta::RelationKind::#kind,
+                    table: <#other_entity as ::sky_orm::entity::Entity>::TABLE_NAME,
+                    foreign_key: <#fk as ::sky_orm::entity::column::Column>::NAME,
+                }
+            }
+        });
+
+    let relation_metas = belongs_to_metas.chain(inverse_metas);
+
+    quote! {
+        fn meta() -> ::sky_orm::entity::meta::EntityMeta {
+            ::sky_orm::entity::meta::EntityMeta {
+                table: Self::TABLE_NAME,
+                schema: Self::SCHEMA,
+                columns: ::std::vec![#(#column_metas),*],
+                primary_key: #primary_key,
+                relations: ::std::vec![#(#relation_metas),*],
+            }
+        }
+    }
+}
+
+/// The `Col` enum of the columns module, listing the columns of an entity as values.
+fn column_enum_impl(entity_ident: &Ident, columns: &[TargetColumn]) -> TokenStream {
+    let variants = columns
+        .iter()
+        .map(|e| Ident::new(e.struct_name.as_str(), e.field_ident.span()))
+        .collect::<Vec<_>>();
+    let db_names = columns.iter().map(|e| &e.db_name).collect::<Vec<_>>();
+
+    quote! {
+        /// The columns of this entity as values, e.g. to pick one by its name at runtime.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Col {
+            #(#variants),*
+        }
+
+        impl Col {
+            /// All columns, in the order they are declared in.
+            pub const ALL: &'static [Self] = &[#(Self::#variants),*];
+
+            /// The name of this column in the database.
+            #[must_use]
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #db_names),*
+                }
+            }
+
+            /// Iterate over all columns, in the order they are declared in.
+            pub fn iter() -> impl ::std::iter::Iterator<Item = Self> {
+                Self::ALL.iter().copied()
+            }
+
+            /// The fully qualified name of this column.
+            #[must_use]
+            pub fn column_name(self) -> ::sky_orm::entity::column::ColumnName {
+                match self {
+                    #(Self::#variants => <#variants as ::sky_orm::entity::column::Column>::full_column_name()),*
+                }
+            }
+
+            /// This column as an expression, e.g. to sort by it.
+            #[must_use]
+            pub fn column(self) -> ::sky_orm::entity::column::EntityConditionExpr<::sky_orm::entity::column::ColumnName, super::#entity_ident> {
+                self.column_name().into()
+            }
+        }
+
+        impl ::sky_orm::entity::column::ColumnEnum for Col {
+            type Entity = super::#entity_ident;
+
+            const ALL: &'static [Self] = Self::ALL;
+
+            fn name(self) -> &'static str {
+                self.as_str()
+            }
+
+            fn full_column_name(self) -> ::sky_orm::entity::column::ColumnName {
+                self.column_name()
+            }
+
+            fn type_id(self) -> ::std::any::TypeId {
+                match self {
+                    #(Self::#variants => ::std::any::TypeId::of::<<#variants as ::sky_orm::entity::column::Column>::Type>()),*
+                }
+            }
+
+            fn type_name(self) -> &'static str {
+                match self {
+                    #(Self::#variants => ::std::any::type_name::<<#variants as ::sky_orm::entity::column::Column>::Type>()),*
+                }
+            }
+
+            fn cast(self) -> ::std::option::Option<&'static str> {
+                match self {
+                    #(Self::#variants => <#variants as ::sky_orm::entity::column::Column>::CAST),*
+                }
+            }
+        }
+
+        impl ::std::str::FromStr for Col {
+            type Err = ::sky_orm::entity::column::UnknownColumn;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                match s {
+                    #(#db_names => ::std::result::Result::Ok(Self::#variants),)*
+                    _ => ::std::result::Result::Err(::sky_orm::entity::column::UnknownColumn {
+                        entity: <super::#entity_ident as ::sky_orm::entity::Entity>::TABLE_NAME,
+                        name: s.to_string(),
+                    }),
+                }
+            }
+        }
+
+        impl ::std::fmt::Display for Col {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl ::std::convert::From<Col> for ::sky_orm::entity::column::ColumnName {
+            fn from(value: Col) -> Self {
+                value.column_name()
+            }
+        }
+    }
+}
+
+/// The [`Debug`] impl of a model with sensitive fields, which shows all fields except for the
+/// values of the sensitive ones.
+fn model_debug_impl(model_ident: &Ident, fields: &[DeriveModelField]) -> TokenStream {
+    let model_ident_str = model_ident.to_string();
+
+    let debug_fields = fields
+        .iter()
+        .filter_map(|e| e.ident.as_ref().map(|ident| (ident, e)))
+        .map(|(ident, e)| {
+            if e.sensitive.is_present() {
+                quote! { .field(::std::stringify!(#ident), &::sky_orm::entity::model::Redacted) }
+            } else {
+                quote! { .field(::std::stringify!(#ident), &self.#ident) }
+            }
+        });
+
+    quote! {
+        impl ::std::fmt::Debug for #model_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(#model_ident_str)
+                    #(
+                        #debug_fields
+                    )*
+                    .finish()
+            }
+        }
+    }
+}
+
+/// The struct generated for `patch`, along with its `Patch` impl for the active model, and the
+/// JSON constructors of the active model for `patch_json`.
+fn patch_impl(
+    patch_ident: &Ident,
+    active_model_ident: &Ident,
+    columns: &[TargetColumn],
+    patch_json: bool,
+) -> TokenStream {
+    let patch_field_decls = columns.iter().map(|e| {
+        let ident = &e.field_ident;
+        let ty = &e.field_ty;
+        let vis = &e.field_vis;
+
+        let serde_attr = patch_json.then(|| {
+            quote! {
+                #[serde(default, deserialize_with = "::sky_orm::entity::model::deserialize_patch_field")]
+            }
+        });
+
+        quote! {
+            #serde_attr
+            #vis #ident: ::std::option::Option<#ty>,
+        }
+    });
+
+    let patch_debug_fields = columns.iter().map(|e| {
+        let ident = &e.field_ident;
+
+        if e.sensitive {
+            quote! {
+                .field(::std::stringify!(#ident), &self.#ident.as_ref().map(|_| ::sky_orm::entity::model::Redacted))
+            }
+        } else {
+            quote! { .field(::std::stringify!(#ident), &self.#ident) }
+        }
+    });
+
+    let patch_ident_str = patch_ident.to_string();
+
+    let patch_field_applications = columns.iter().map(|e| {
+        let ident = &e.field_ident;
+        let value = e.to_column_value(&quote! { value });
+
+        quote! {
+            if let ::std::option::Option::Some(value) = self.#ident {
+                model.#ident.set(#value);
+            }
+        }
+    });
+
+    let serde_derive = patch_json.then(|| {
+        quote! {
+            #[derive(::sky_orm::serde::Deserialize)]
+            #[serde(crate = "::sky_orm::serde")]
+        }
+    });
+
+    let json_impl = patch_json.then(|| {
+        quote! {
+            impl #active_model_ident {
+                /// Create an active model with only the columns present in a JSON object set.
+                ///
+                /// # Errors
+                ///
+                /// If `value` cannot be deserialized into the patch struct.
+                pub fn from_json(value: ::sky_orm::serde_json::Value) -> ::std::result::Result<Self, ::sky_orm::serde_json::Error> {
+                    let mut model = <Self as ::std::default::Default>::default();
+                    model.set_from_json(value)?;
+                    Ok(model)
+                }
+
+                /// Set the columns present in a JSON object, leaving all others untouched.
+                ///
+                /// # Errors
+                ///
+                /// If `value` cannot be deserialized into the patch struct.
+                pub fn set_from_json(&mut self, value: ::sky_orm::serde_json::Value) -> ::std::result::Result<(), ::sky_orm::serde_json::Error> {
+                    let patch = ::sky_orm::serde_json::from_value::<#patch_ident>(value)?;
+                    ::sky_orm::entity::model::ActiveModel::set_from(self, patch);
+                    Ok(())
+                }
+            }
+        }
+    });
+
+    quote! {
+        /// New values for some of the columns of the active model, leaving those that are
+        /// [`None`] untouched.
+        #[derive(Clone, Default)]
+        #serde_derive
+        pub struct #patch_ident {
+            #(
+                #patch_field_decls
+            )*
+        }
+
+        impl ::std::fmt::Debug for #patch_ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.debug_struct(#patch_ident_str)
+                    #(
+                        #patch_debug_fields
+                    )*
+                    .finish()
+            }
+        }
+
+        impl ::sky_orm::entity::model::Patch for #patch_ident {
+            type ActiveModel = #active_model_ident;
+
+            fn apply_to(self, model: &mut Self::ActiveModel) {
+                #(
+                    #patch_field_applications
+                )*
+            }
+        }
+
+        #json_impl
+    }
+}
+
+/// The struct generated for `api`, along with its `From` impl for the model.
+fn api_model_impl(
+    api: &ApiModelTarget,
+    model_ident: &Ident,
+    fields: &[DeriveModelField],
+) -> TokenStream {
+    let api_model_ident = api
+        .name
+        .clone()
+        .unwrap_or_else(|| Ident::new("ApiModel", Span::call_site()));
+
+    let derives = api.derive.as_ref().map(|e| {
+        let paths = e.iter();
+
+        quote! {
+            #[derive(#(#paths),*)]
+        }
+    });
+
+    let fields = fields
+        .iter()
+        .filter(|e| !e.api_skip.is_present())
+        .filter_map(|e| Some((e.ident.as_ref()?, e)))
+        .collect::<Vec<_>>();
+
+    let field_decls = fields.iter().map(|(ident, e)| {
+        let attrs = &e.attrs;
+        let vis = &e.vis;
+        let ty = &e.ty;
+
+        quote! {
+            #(#attrs)*
+            #vis #ident: #ty,
+        }
+    });
+
+    let field_idents = fields.iter().map(|(ident, _)| ident);
+
+    quote! {
+        /// The fields of the model that may be exposed by an API.
+        #derives
+        pub struct #api_model_ident {
+            #(
+                #field_decls
+            )*
+        }
+
+        impl ::std::convert::From<#model_ident> for #api_model_ident {
+            fn from(value: #model_ident) -> Self {
+                Self {
+                    #(
+                        #field_idents: value.#field_idents,
+                    )*
+                }
+            }
+        }
+    }
+}
+
+/// The factory generated for `factory`, along with its `Factory` impl.
+fn factory_impl(
+    factory_ident: &Ident,
+    model_ident: &Ident,
+    entity_ident: &Ident,
+    fields: &[DeriveModelField],
+    columns: &[TargetColumn],
+) -> TokenStream {
+    let field_decls = columns.iter().map(|e| {
+        let ident = &e.field_ident;
+        let ty = &e.field_ty;
+
+        quote! {
+            #ident: ::std::option::Option<#ty>,
+        }
+    });
+
+    let setters = columns.iter().map(|e| {
+        let ident = &e.field_ident;
+        let ty = &e.field_ty;
+        let vis = &e.field_vis;
+
+        quote! {
+            /// Use `value` for this field, instead of its default value.
+            #[must_use]
+            #vis fn #ident(mut self, value: #ty) -> Self {
+                self.#ident = ::std::option::Option::Some(value);
+                self
+            }
+        }
+    });
+
+    // Converted foreign keys do not hold a value of the primary key's type.
+    let relation_setters = fields
+        .iter()
+        .filter_map(|e| Some((e.ident.as_ref()?, e.belongs_to.as_ref()?, e)))
+        .filter(|(ident, _, _)| {
+            columns
+                .iter()
+                .any(|c| c.field_ident.eq(*ident) && c.conversion.is_none())
+        })
+        .map(|(ident, target_entity, e)| {
+            let name = ident.to_string();
+            let setter = Ident::new(
+                &format!("for_{}", name.strip_suffix("_id").unwrap_or(&name)),
+                ident.span(),
+            );
+            let vis = &e.vis;
+
+            quote! {
+                /// Refer to `model` via this foreign key.
+                #[must_use]
+                #vis fn #setter(mut self, model: &<#target_entity as ::sky_orm::entity::Entity>::Model) -> Self {
+                    self.#ident = ::std::option::Option::Some(::std::convert::From::from(::std::clone::Clone::clone(
+                        ::sky_orm::entity::model::GetColumn::<<#target_entity as ::sky_orm::entity::HasPrimaryKey>::PrimaryKeyColumn>::get(model),
+                    )));
+                    self
+                }
+            }
+        });
+
+    let field_assignments = columns.iter().map(|e| {
+        let ident = &e.field_ident;
+        let db_name = &e.db_name;
+
+        quote! {
+            #ident: self.#ident.clone().unwrap_or_else(|| ::sky_orm::testing::FactoryDefault::factory_default(#db_name, sequence)),
+        }
+    });
+
+    let skipped_field_assignments = fields
+        .iter()
+        .filter(|e| e.skip.is_present())
+        .filter_map(|e| e.ident.as_ref())
+        .map(|e| {
+            quote! {
+                #e: ::std::default::Default::default(),
+            }
+        });
+
+    quote! {
+        /// A builder of models with default values for all fields that are not set, for inserting
+        /// test data.
+        #[derive(Clone, Default)]
+        pub struct #factory_ident {
+            #(
+                #field_decls
+            )*
+        }
+
+        impl #factory_ident {
+            /// Create a factory using the default values of all fields.
+            #[must_use]
+            pub fn new() -> Self {
+                <Self as ::std::default::Default>::default()
+            }
+
+            #(
+                #setters
+            )*
+
+            #(
+                #relation_setters
+            )*
+        }
+
+        impl ::sky_orm::testing::Factory for #factory_ident {
+            type Entity = #entity_ident;
+
+            fn build_with(&self, sequence: u64) -> #model_ident {
+                #model_ident {
+                    #(
+                        #field_assignments
+                    )*
+                    #(
+                        #skipped_field_assignments
+                    )*
+                }
+            }
+        }
     }
 }
@@ -1,18 +1,30 @@
-use darling::{FromDeriveInput, FromField, ast::Data};
+use darling::{FromDeriveInput, FromField, ast::Data, util::Flag};
 use proc_macro_error2::abort;
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{DeriveInput, Ident, parse2};
+use syn::{DeriveInput, Ident, Path, Type, parse2};
 
 #[derive(FromField)]
+#[darling(attributes(sky_orm))]
 struct ParseFromRowFieldArgs {
     ident: Option<Ident>,
-    column_name: Option<String>,
+    ty: Type,
+    column: Option<String>,
+    /// Parse the field from the same row via its own [`ParseFromRow`] impl, e.g. to decode the
+    /// columns of a joined entity into its model.
+    flatten: Flag,
 }
 
 #[derive(FromDeriveInput)]
+#[darling(attributes(sky_orm))]
 struct ParseFromRowArgs {
     ident: Ident,
+    /// The database to parse rows of. If unset, the impl is generic over all databases that can
+    /// decode the struct's fields.
+    database: Option<Path>,
+    /// The entity whose table the struct is a projection of, to check at compile time that all of
+    /// its columns exist there.
+    entity: Option<Path>,
     data: Data<(), ParseFromRowFieldArgs>,
 }
 
@@ -33,6 +45,11 @@ pub fn parse_from_row(input: TokenStream) -> TokenStream {
         };
     };
 
+    let database = args
+        .database
+        .as_ref()
+        .map_or_else(|| quote! { DB }, |e| quote! { #e });
+
     let field_assignments = struct_args.fields.iter().map(|e| {
         let Some(field_name) = &e.ident else {
             abort! {
@@ -41,25 +58,126 @@ pub fn parse_from_row(input: TokenStream) -> TokenStream {
             };
         };
 
-        let column_name = e
-            .column_name
-            .clone()
-            .unwrap_or_else(|| field_name.to_string());
+        if e.flatten.is_present() {
+            if e.column.is_some() {
+                abort! {
+                    field_name, "Flattened fields cannot be renamed.";
+                    note = "Rename the columns on the type of the field instead.";
+                };
+            }
+
+            let ty = &e.ty;
+
+            return quote! {
+                #field_name: <#ty as ::sky_orm::query::parse::ParseFromRow<#database>>::parse_from_row(row)?,
+            };
+        }
+
+        let column_name = e.column.clone().unwrap_or_else(|| field_name.to_string());
 
         quote! {
-            #field_name: row.try_get(#column_name)?,
+            #field_name: ::sky_orm::sqlx::Row::try_get(row, #column_name)?,
         }
     });
 
-    quote! {
-        impl ::sky_orm::query::parse::ParseFromRow for #struct_name {
-            fn parse_from_row(row: &::sky_orm::sqlx::any::AnyRow) -> ::std::result::Result<Self, ::sky_orm::sqlx::Error> {
-                Ok(Self {
-                    #(
-                        #field_assignments
-                    )*
-                })
+    let projection_impl = projection_impl(&struct_name, &struct_args.fields, args.entity.as_ref());
+
+    let body = quote! {
+        fn parse_from_row(row: &<#database as ::sky_orm::sqlx::Database>::Row) -> ::std::result::Result<Self, ::sky_orm::sqlx::Error> {
+            Ok(Self {
+                #(
+                    #field_assignments
+                )*
+            })
+        }
+    };
+
+    if args.database.is_some() {
+        return quote! {
+            impl ::sky_orm::query::parse::ParseFromRow<#database> for #struct_name {
+                #body
             }
+
+            #projection_impl
+        };
+    }
+
+    let field_bounds = struct_args.fields.iter().map(|e| {
+        let ty = &e.ty;
+
+        if e.flatten.is_present() {
+            quote! { #ty: ::sky_orm::query::parse::ParseFromRow<DB> }
+        } else {
+            quote! { #ty: for<'r> ::sky_orm::sqlx::Decode<'r, DB> + ::sky_orm::sqlx::Type<DB> }
+        }
+    });
+
+    quote! {
+        impl<DB> ::sky_orm::query::parse::ParseFromRow<DB> for #struct_name
+        where
+            DB: ::sky_orm::sqlx::Database,
+            for<'a> &'a str: ::sky_orm::sqlx::ColumnIndex<<DB as ::sky_orm::sqlx::Database>::Row>,
+            #(
+                #field_bounds,
+            )*
+        {
+            #body
         }
+
+        #projection_impl
     }
 }
+
+/// The [`Projection`](sky_orm::query::parse::Projection) impl of a struct deriving `FromSqlxRow`,
+/// unless it has flattened fields, along with checks that its columns exist on `entity`.
+fn projection_impl(
+    struct_name: &Ident,
+    fields: &[ParseFromRowFieldArgs],
+    entity: Option<&Path>,
+) -> Option<TokenStream> {
+    let has_flattened_fields = fields.iter().any(|e| e.flatten.is_present());
+
+    if has_flattened_fields {
+        if let Some(entity) = entity {
+            abort! {
+                entity, "Structs with flattened fields cannot be projections of an entity.";
+                note = "Flattened fields are parsed from columns named after their own tables.";
+            };
+        }
+
+        return None;
+    }
+
+    let column_names = fields
+        .iter()
+        .filter_map(|e| {
+            e.column
+                .clone()
+                .or_else(|| e.ident.as_ref().map(ToString::to_string))
+        })
+        .collect::<Vec<_>>();
+
+    let column_checks = entity.iter().flat_map(|entity| {
+        column_names.iter().map(move |e| {
+            let message = format!("column \"{e}\" does not exist on the entity");
+
+            quote! {
+                const _: () = ::std::assert!(
+                    ::sky_orm::query::parse::contains_column(
+                        <#entity as ::sky_orm::entity::Entity>::COLUMN_NAMES,
+                        #e,
+                    ),
+                    #message,
+                );
+            }
+        })
+    });
+
+    Some(quote! {
+        impl ::sky_orm::query::parse::Projection for #struct_name {
+            const COLUMN_NAMES: &'static [&'static str] = &[#(#column_names),*];
+        }
+
+        #(#column_checks)*
+    })
+}
@@ -0,0 +1,32 @@
+use convert_case::{Case, Casing};
+use darling::FromMeta;
+
+/// A naming convention to rename identifiers by, as in serde's `rename_all`.
+#[derive(Debug, Clone, Copy)]
+pub struct RenameRule(Case<'static>);
+
+impl RenameRule {
+    pub fn apply(self, name: &str) -> String {
+        name.to_case(self.0)
+    }
+}
+
+impl FromMeta for RenameRule {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        let case = match value {
+            "lowercase" => Case::Flat,
+            "UPPERCASE" => Case::UpperFlat,
+            "PascalCase" => Case::Pascal,
+            "camelCase" => Case::Camel,
+            "snake_case" => Case::Snake,
+            "SCREAMING_SNAKE_CASE" => Case::UpperSnake,
+            "kebab-case" => Case::Kebab,
+            "SCREAMING-KEBAB-CASE" => Case::UpperKebab,
+            _ => {
+                return Err(darling::Error::unknown_value(value));
+            }
+        };
+
+        Ok(Self(case))
+    }
+}
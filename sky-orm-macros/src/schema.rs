@@ -1,3 +1,5 @@
-mod file;
+mod enums;
+pub mod file;
 pub mod model;
 mod type_conversion;
+pub mod verify;
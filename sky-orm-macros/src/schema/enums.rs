@@ -0,0 +1,145 @@
+use convert_case::{Case, Casing};
+use proc_macro_error2::abort;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use sky_orm_sqlparse::schema::{SqlColumn, SqlSchema};
+use sqlparser::ast::{DataType, EnumMember, ObjectNamePart};
+use syn::Ident;
+
+use crate::schema::type_conversion::CustomTypes;
+
+/// A rust enum generated by `model!` for the values of an enum column.
+pub struct GeneratedEnum {
+    pub ident: Ident,
+    /// The name of the native postgres enum type, or [`None`] for enum columns of mysql, whose
+    /// values are sent as text.
+    type_name: Option<String>,
+    variants: Vec<String>,
+    /// The column the enum was generated for, for error messages.
+    column: String,
+}
+
+impl GeneratedEnum {
+    /// The enum to generate for `column`, if it is of a postgres enum type in `schema`, or an enum
+    /// column of mysql. Postgres enums are named after their type, while mysql enums are named
+    /// after their field, e.g. `Status` for a `status` column. Types mapped with the `types`
+    /// directive are never generated.
+    pub fn for_column(
+        column: &SqlColumn,
+        field_name: &str,
+        schema: &SqlSchema,
+        custom_types: &CustomTypes,
+    ) -> Option<Self> {
+        if custom_types.get(&column.column_type).is_some() {
+            return None;
+        }
+
+        let (name, type_name, variants) = match &column.column_type {
+            DataType::Custom(name, args) if args.is_empty() => {
+                let qualified_name = name
+                    .0
+                    .iter()
+                    .map(|e| {
+                        let ObjectNamePart::Identifier(ident) = e;
+
+                        ident.value.clone()
+                    })
+                    .collect::<Vec<_>>()
+                    .join(".");
+
+                let sql_enum = schema.find_enum(&qualified_name)?;
+
+                (
+                    sql_enum.name.clone(),
+                    Some(sql_enum.qualified_name()),
+                    sql_enum.variants.clone(),
+                )
+            }
+            DataType::Enum(members, _) => (
+                field_name.to_string(),
+                None,
+                members
+                    .iter()
+                    .map(|e| match e {
+                        EnumMember::Name(name) | EnumMember::NamedValue(name, _) => name.clone(),
+                    })
+                    .collect(),
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            ident: rust_ident(&name, &column.name),
+            type_name,
+            variants,
+            column: column.name.clone(),
+        })
+    }
+
+    /// Abort if the enum would be named like one of `items`, the other items generated for the
+    /// model.
+    pub fn reject_clash(&self, items: &[Ident]) {
+        if let Some(item) = items.iter().find(|e| self.ident.eq(*e)) {
+            abort! {
+                Span::call_site(), "The enum generated for column \"{}\" would be named `{}`, like an item of the model.", self.column, item;
+                note = "Map the enum to a hand-written `DbEnum` instead, e.g. `types: { my_enum: MyEnum }`, or rename the item with `names: { .. }`."
+            };
+        }
+    }
+
+    pub fn to_tokens(&self) -> TokenStream {
+        let ident = &self.ident;
+
+        let variant_idents = self
+            .variants
+            .iter()
+            .map(|e| rust_ident(e, &self.column))
+            .collect::<Vec<_>>();
+
+        if let Some(duplicate) = variant_idents
+            .iter()
+            .find(|e| variant_idents.iter().filter(|o| e.eq(o)).count() > 1)
+        {
+            abort! {
+                Span::call_site(), "Values of enum `{}` map to the same variant `{}`.", ident, duplicate;
+                note = "Map the enum to a hand-written `DbEnum` instead, e.g. `types: { my_enum: MyEnum }`."
+            };
+        }
+
+        let variants = &self.variants;
+        let type_name_attr = self
+            .type_name
+            .as_ref()
+            .map(|e| quote! { #[sky_orm(type_name = #e)] });
+
+        let graphql_attrs = cfg!(feature = "async-graphql").then(|| {
+            quote! {
+                #[derive(::sky_orm::async_graphql::Enum)]
+                #[graphql(crate = "::sky_orm::async_graphql")]
+            }
+        });
+
+        quote! {
+            #[derive(::sky_orm::DbEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #graphql_attrs
+            #type_name_attr
+            pub enum #ident {
+                #(
+                    #[sky_orm(rename = #variants)]
+                    #variant_idents,
+                )*
+            }
+        }
+    }
+}
+
+/// The rust identifier for `name` (the name or a value of the enum of `column`) in `PascalCase`,
+/// aborting if there is none, e.g. for a value starting with a digit.
+fn rust_ident(name: &str, column: &str) -> Ident {
+    syn::parse_str::<Ident>(&name.to_case(Case::Pascal)).unwrap_or_else(|_| {
+        abort! {
+            Span::call_site(), "\"{}\" of the enum of column \"{}\" is not a valid rust identifier.", name, column;
+            note = "Map the enum to a hand-written `DbEnum` instead, e.g. `types: { my_enum: MyEnum }`."
+        };
+    })
+}
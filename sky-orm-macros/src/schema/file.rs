@@ -1 +1,91 @@
+use std::{fs, path::PathBuf};
 
+use proc_macro_error2::abort;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use sky_orm_sqlparse::schema::{SCHEMA_PATH_VAR, SCHEMA_VERSION, SqlSchema, schema_path};
+use syn::LitStr;
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(
+        std::env::var_os("CARGO_MANIFEST_DIR").expect("Missing env var CARGO_MANIFEST_DIR"),
+    )
+}
+
+/// The schema file of the crate being compiled.
+pub struct SchemaFile {
+    path: PathBuf,
+    pub schema: SqlSchema,
+}
+
+impl SchemaFile {
+    /// Read the schema file. `path` is resolved against the crate's manifest directory, and
+    /// defaults to [`schema_path`] if unset.
+    pub fn read(path: Option<&LitStr>) -> Self {
+        let manifest_dir = manifest_dir();
+
+        let schema_file = path.map_or_else(
+            || schema_path(&manifest_dir),
+            |e| manifest_dir.join(e.value()),
+        );
+
+        Self::read_from(schema_file, path.map_or_else(Span::call_site, LitStr::span))
+    }
+
+    /// Read the schema file from its default location (see [`schema_path`]), if it exists.
+    pub fn read_if_present() -> Option<Self> {
+        let schema_file = schema_path(&manifest_dir());
+
+        schema_file
+            .exists()
+            .then(|| Self::read_from(schema_file, Span::call_site()))
+    }
+
+    fn read_from(schema_file: PathBuf, span: Span) -> Self {
+        let contents = fs::read_to_string(&schema_file).unwrap_or_else(|e| {
+            abort!(
+                span,
+                "Failed to read schema file {}: {}",
+                schema_file.display(),
+                e
+            )
+        });
+
+        let schema = serde_json::from_str::<SqlSchema>(&contents).unwrap_or_else(|e| {
+            abort!(
+                span,
+                "Failed to parse schema file {}: {}",
+                schema_file.display(),
+                e
+            )
+        });
+
+        if !schema.is_current_version() {
+            abort! {
+                span,
+                "Schema file {} was written in format version {}, but the current version is {}.",
+                schema_file.display(),
+                schema.version,
+                SCHEMA_VERSION;
+                help = "Run `sky-orm-cli generate-schema` to update it."
+            };
+        }
+
+        Self {
+            path: schema_file,
+            schema,
+        }
+    }
+
+    /// Items that register the schema file and its override as dependencies of the calling crate,
+    /// so that it is rebuilt whenever either changes. Proc macros cannot declare dependencies on
+    /// stable, but the compiler tracks the ones of `include_str!` and `option_env!`.
+    pub fn dependency_tokens(&self) -> TokenStream {
+        let path = self.path.to_string_lossy();
+
+        quote! {
+            const _: &str = ::std::include_str!(#path);
+            const _: ::std::option::Option<&str> = ::std::option_env!(#SCHEMA_PATH_VAR);
+        }
+    }
+}
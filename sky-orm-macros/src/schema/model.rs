@@ -1,22 +1,29 @@
-use std::{
-    fs,
-    ops::{Deref, DerefMut},
-    path::PathBuf,
-};
+use std::ops::{Deref, DerefMut};
 
 use convert_case::{Case, Casing};
+use darling::FromMeta;
 use proc_macro_error2::abort;
 use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::quote;
-use sky_orm_sqlparse::schema::{SqlColumn, SqlSchema};
+use sky_orm_sqlparse::schema::SqlColumn;
+use sqlparser::ast::DataType;
 use syn::{
-    Attribute, Ident, LitStr, Path, Token,
-    parse::{Parse, ParseStream},
+    Attribute, Ident, LitStr, Meta, Path, Token, Type, braced,
+    parse::{Parse, ParseStream, Parser},
     parse2,
-    token::Colon,
+    punctuated::Punctuated,
+    token::{Brace, Colon},
 };
 
-use crate::schema::type_conversion::sql_to_rust_type;
+use crate::{
+    rename::RenameRule,
+    schema::{
+        enums::GeneratedEnum,
+        file::SchemaFile,
+        type_conversion::{CustomTypes, implements_default, is_citext, sql_to_rust_type},
+        verify::{type_class, unwrap_option},
+    },
+};
 
 #[derive(Clone)]
 struct FieldAddition {
@@ -89,10 +96,124 @@ impl Parse for FieldAdditions {
     }
 }
 
+/// Parse a comma-separated list of `key: Ident` pairs, aborting on keys not in `allowed`.
+fn parse_named_idents(input: ParseStream, allowed: &[&str]) -> syn::Result<Vec<(String, Ident)>> {
+    let pairs = Punctuated::<(Ident, Ident), Token![,]>::parse_terminated_with(input, |input| {
+        let key = input.parse::<Ident>()?;
+        input.parse::<Colon>()?;
+        Ok((key, input.parse::<Ident>()?))
+    })?;
+
+    pairs
+        .into_iter()
+        .map(|(key, value)| {
+            if allowed.contains(&key.to_string().as_str()) {
+                Ok((key.to_string(), value))
+            } else {
+                Err(syn::Error::new(
+                    key.span(),
+                    format!("Unknown name, expected one of: {}", allowed.join(", ")),
+                ))
+            }
+        })
+        .collect()
+}
+
+/// The names of the items generated for a model. Unset names fall back to the derive's defaults.
+#[derive(Default)]
+struct ItemNames {
+    model: Option<Ident>,
+    entity: Option<Ident>,
+    active_model: Option<Ident>,
+    columns: Option<Ident>,
+}
+
+impl Parse for ItemNames {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut this = Self::default();
+
+        for (key, value) in
+            parse_named_idents(input, &["model", "entity", "active_model", "columns"])?
+        {
+            match key.as_str() {
+                "model" => this.model = Some(value),
+                "entity" => this.entity = Some(value),
+                "active_model" => this.active_model = Some(value),
+                _ => this.columns = Some(value),
+            }
+        }
+
+        Ok(this)
+    }
+}
+
+/// Where to find the entity on the other side of a relation, by its table name.
+#[derive(Clone)]
+struct RelationTarget {
+    table_name: Ident,
+    module: Path,
+    entity: Option<Ident>,
+    columns: Option<Ident>,
+}
+
+impl Parse for RelationTarget {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let table_name = input.parse::<Ident>()?;
+        input.parse::<Colon>()?;
+        let module = Path::parse_mod_style(input)?;
+
+        let mut this = Self {
+            table_name,
+            module,
+            entity: None,
+            columns: None,
+        };
+
+        if input.peek(Brace) {
+            let content;
+            braced!(content in input);
+
+            for (key, value) in parse_named_idents(&content, &["entity", "columns"])? {
+                if key == "entity" {
+                    this.entity = Some(value);
+                } else {
+                    this.columns = Some(value);
+                }
+            }
+        }
+
+        Ok(this)
+    }
+}
+
+/// A mapping of a custom SQL type to a rust type, e.g. `citext: String`.
+struct TypeMapping {
+    sql_name: Ident,
+    ty: Path,
+}
+
+impl Parse for TypeMapping {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let sql_name = input.parse::<Ident>()?;
+        input.parse::<Colon>()?;
+
+        Ok(Self {
+            sql_name,
+            ty: input.parse()?,
+        })
+    }
+}
+
 struct DeclModelArgs {
     table_name: LitStr,
+    schema_file: Option<LitStr>,
     struct_attrs: Vec<Attribute>,
     field_additions: FieldAdditions,
+    excluded_columns: Vec<Ident>,
+    money_columns: Vec<Ident>,
+    names: ItemNames,
+    relation_targets: Vec<RelationTarget>,
+    custom_types: CustomTypes,
 }
 
 impl Parse for DeclModelArgs {
@@ -100,7 +221,13 @@ impl Parse for DeclModelArgs {
         let mut this = Self {
             struct_attrs: input.call(Attribute::parse_outer)?,
             table_name: input.parse::<LitStr>()?,
+            schema_file: None,
             field_additions: FieldAdditions::default(),
+            excluded_columns: vec![],
+            money_columns: vec![],
+            names: ItemNames::default(),
+            relation_targets: vec![],
+            custom_types: CustomTypes::default(),
         };
 
         input.parse::<Token![,]>()?;
@@ -108,19 +235,53 @@ impl Parse for DeclModelArgs {
         while let Ok(ident) = input.parse::<Ident>() {
             input.parse::<Colon>()?;
 
-            let TokenTree::Group(group) = input.parse()? else {
-                abort!(input.span(), "Unexpected continuation (expected block)");
-            };
+            if ident == "schema" {
+                this.schema_file = Some(input.parse::<LitStr>()?);
+            } else {
+                let TokenTree::Group(group) = input.parse()? else {
+                    abort!(input.span(), "Unexpected continuation (expected block)");
+                };
 
-            let group_stream = group.stream();
+                let group_stream = group.stream();
 
-            match ident.to_string().as_str() {
-                "fields" => {
-                    this.field_additions = parse2::<FieldAdditions>(group_stream)?;
+                match ident.to_string().as_str() {
+                    "fields" => {
+                        this.field_additions = parse2::<FieldAdditions>(group_stream)?;
+                    }
+                    "exclude" => {
+                        this.excluded_columns = Punctuated::<Ident, Token![,]>::parse_terminated
+                            .parse2(group_stream)?
+                            .into_iter()
+                            .collect();
+                    }
+                    "money" => {
+                        this.money_columns = Punctuated::<Ident, Token![,]>::parse_terminated
+                            .parse2(group_stream)?
+                            .into_iter()
+                            .collect();
+                    }
+                    "names" => {
+                        this.names = parse2::<ItemNames>(group_stream)?;
+                    }
+                    "relations" => {
+                        this.relation_targets =
+                            Punctuated::<RelationTarget, Token![,]>::parse_terminated
+                                .parse2(group_stream)?
+                                .into_iter()
+                                .collect();
+                    }
+                    "types" => {
+                        Punctuated::<TypeMapping, Token![,]>::parse_terminated
+                            .parse2(group_stream)?
+                            .into_iter()
+                            .for_each(|e| {
+                                this.custom_types.insert(&e.sql_name.to_string(), e.ty);
+                            });
+                    }
+                    _ => abort! {
+                        ident, "Unknown directive"
+                    },
                 }
-                _ => abort! {
-                    ident, "Unknown directive"
-                },
             }
 
             if !input.peek(Token![,]) {
@@ -136,26 +297,32 @@ impl Parse for DeclModelArgs {
 
 struct ColumnFieldPairing(SqlColumn, Option<FieldAddition>);
 
-// TODO: refactor with `syn-parse-helpers` to cut down on line length
-#[allow(clippy::too_many_lines)]
-pub fn decl_model(input: TokenStream) -> TokenStream {
-    let arg = match parse2::<DeclModelArgs>(input) {
-        Ok(e) => e,
-        Err(e) => return e.to_compile_error(),
-    };
-
-    let sky_orm_dir: PathBuf = [
-        std::env::var("CARGO_MANIFEST_DIR").expect("Missing env var CARGO_MANIFEST_DIR"),
-        "sky_orm".to_owned(),
-    ]
-    .iter()
-    .collect();
+/// The `rename_all` rule given among the `sky_orm` attributes of the model, if any.
+fn rename_rule(attrs: &[Attribute]) -> Option<RenameRule> {
+    attrs
+        .iter()
+        .filter(|e| e.path().is_ident("sky_orm"))
+        .filter_map(|e| {
+            e.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                .ok()
+        })
+        .flatten()
+        .find(|e| e.path().is_ident("rename_all"))
+        .and_then(|e| RenameRule::from_meta(&e).ok())
+}
 
-    let schema_file = fs::read_to_string(sky_orm_dir.join("schema.json"))
-        .expect("Failed to read schema.json file");
+pub fn decl_model(input: TokenStream) -> TokenStream {
+    match parse2::<DeclModelArgs>(input) {
+        Ok(arg) => expand_model(arg),
+        Err(e) => e.to_compile_error(),
+    }
+}
 
-    let schema: SqlSchema =
-        serde_json::from_str(&schema_file).expect("Failed to read schema.json file");
+// TODO: refactor with `syn-parse-helpers` to cut down on line length
+#[allow(clippy::too_many_lines)]
+fn expand_model(arg: DeclModelArgs) -> TokenStream {
+    let schema_file = SchemaFile::read(arg.schema_file.as_ref());
+    let schema = &schema_file.schema;
 
     let table_name = arg.table_name.value();
 
@@ -166,8 +333,48 @@ pub fn decl_model(input: TokenStream) -> TokenStream {
         );
     };
 
-    let field_names = table
+    // Excluded and money columns may be referred to by either their database or their field name.
+    let refers_to = |e: &Ident, column: &str| {
+        let e = e.to_string();
+        column.eq(&e) || column.to_case(Case::Snake).eq(&e)
+    };
+    let is_excluded = |column: &str| arg.excluded_columns.iter().any(|e| refers_to(e, column));
+    let is_money = |column: &str| arg.money_columns.iter().any(|e| refers_to(e, column));
+
+    arg.money_columns.iter().for_each(|e| {
+        if !table.columns.iter().any(|c| refers_to(e, &c.name)) {
+            abort! {
+                e.span(), "Column does not exist in schema."
+            };
+        }
+    });
+
+    arg.excluded_columns.iter().for_each(|e| {
+        let Some(column) = table.columns.iter().find(|c| refers_to(e, &c.name)) else {
+            abort! {
+                e.span(), "Column does not exist in schema."
+            };
+        };
+
+        if table
+            .primary_key
+            .as_ref()
+            .is_some_and(|p| p.eq(&column.name))
+        {
+            abort! {
+                e.span(), "Cannot exclude the primary key.";
+                note = "The primary key is required to identify rows, e.g. when loading relations."
+            };
+        }
+    });
+
+    let columns = table
         .columns
+        .iter()
+        .filter(|e| !is_excluded(&e.name))
+        .collect::<Vec<_>>();
+
+    let field_names = columns
         .iter()
         .map(|e| e.name.to_case(Case::Snake))
         .collect::<Vec<_>>();
@@ -175,7 +382,7 @@ pub fn decl_model(input: TokenStream) -> TokenStream {
     // Ensure that the specified fields exist, or that a corresponding database column exists for
     // each rename instruction.
     arg.field_additions.iter().for_each(|e| {
-        if e.rename_to.is_some() && !table.columns.iter().any(|c| c.name.eq(&e.field_name.to_string())) {
+        if e.rename_to.is_some() && !columns.iter().any(|c| c.name.eq(&e.field_name.to_string())) {
             abort! {
                 e.field_name.span(), "Column does not exist in schema.";
                 note = "When renaming, make sure to use the exact column name as it appears in the database."
@@ -188,11 +395,9 @@ pub fn decl_model(input: TokenStream) -> TokenStream {
         }
     });
 
-    let column_field_pairings = table
-        .columns
+    let column_field_pairings = columns
         .iter()
-        .cloned()
-        .map(|c| {
+        .map(|&c| {
             let field_addition = arg.field_additions.iter().find(|e| {
                 if e.rename_to.is_some() {
                     c.name.eq(&e.field_name.to_string())
@@ -201,11 +406,41 @@ pub fn decl_model(input: TokenStream) -> TokenStream {
                 }
             });
 
-            ColumnFieldPairing(c, field_addition.cloned())
+            ColumnFieldPairing(c.clone(), field_addition.cloned())
         })
         .collect::<Vec<_>>();
 
-    let field_quotes = column_field_pairings.iter().map(|e| {
+    // Enum columns without a user-supplied type get a rust enum generated for their values.
+    let generated_enums = column_field_pairings
+        .iter()
+        .map(|e| {
+            if e.1.as_ref().is_some_and(|f| f.ty_override.is_some()) {
+                return None;
+            }
+
+            let field_name =
+                e.1.as_ref()
+                    .and_then(|f| f.rename_to.as_ref())
+                    .map_or_else(|| e.0.name.to_case(Case::Snake), ToString::to_string);
+
+            GeneratedEnum::for_column(&e.0, &field_name, schema, &arg.custom_types)
+        })
+        .collect::<Vec<_>>();
+
+    // Fields with a user-supplied type are assumed to implement `Default`, as before.
+    let derive_default =
+        column_field_pairings
+            .iter()
+            .zip(&generated_enums)
+            .all(|(e, generated_enum)| {
+                e.1.as_ref().is_some_and(|f| f.ty_override.is_some())
+                    || (generated_enum.is_none()
+                        && implements_default(&e.0.column_type, &arg.custom_types))
+            });
+
+    let rename_rule = rename_rule(&arg.struct_attrs);
+
+    let field_quotes = column_field_pairings.iter().zip(&generated_enums).map(|(e, generated_enum)| {
         let (c, field_addition) = (&e.0, e.1.as_ref());
 
         let field_name = field_addition
@@ -217,7 +452,7 @@ pub fn decl_model(input: TokenStream) -> TokenStream {
             field_addition.map_or_else(Span::call_site, |e| {
                 e.rename_to
                     .as_ref()
-                    .map_or(e.field_name.span(), proc_macro2::Ident::span)
+                    .map_or_else(|| e.field_name.span(), proc_macro2::Ident::span)
             }),
         );
 
@@ -231,61 +466,223 @@ pub fn decl_model(input: TokenStream) -> TokenStream {
                     }
                 })
             })
-            .unwrap_or_else(|| sql_to_rust_type(&c.column_type));
+            .or_else(|| {
+                generated_enum.as_ref().map(|e| {
+                    let ident = &e.ident;
+                    quote! {
+                        #ident
+                    }
+                })
+            })
+            .unwrap_or_else(|| {
+                // View columns computed from expressions have no type we could derive.
+                if c.column_type == DataType::Unspecified {
+                    abort! {
+                        arg.table_name.span(), "Type of column \"{}\" is unknown.", c.name;
+                        note = "Specify the type manually, e.g. `fields: {{ {}: String }}`", c.name.to_case(Case::Snake)
+                    };
+                }
+
+                sql_to_rust_type(&c.column_type, &arg.custom_types).unwrap_or_else(|| {
+                    abort! {
+                        arg.table_name.span(), "Type `{}` of column \"{}\" is not supported.", c.column_type, c.name;
+                        note = "Specify the type manually, e.g. `fields: {{ {}: MyType }}`, or map the SQL type via `types: {{ my_sql_type: MyType }}`", c.name.to_case(Case::Snake)
+                    };
+                })
+            });
+
+        // Money columns must hold exact amounts, see the `money` attribute of the derive.
+        let money_attr = is_money(&c.name).then(|| {
+            let has_exact_type = parse2::<Type>(ty_quote.clone())
+                .ok()
+                .and_then(|e| type_class(unwrap_option(&e).1))
+                .is_some_and(|e| matches!(e, "integer" | "decimal"));
+
+            if field_addition.is_none_or(|e| e.ty_override.is_none()) && !has_exact_type {
+                abort! {
+                    arg.table_name.span(), "Column \"{}\" of type `{}` cannot hold money.", c.name, c.column_type;
+                    note = "Money columns must be integers holding minor units, or `NUMERIC` with the `rust_decimal` or `bigdecimal` feature of sky-orm enabled."
+                };
+            }
+
+            quote! {
+                #[sky_orm(money)]
+            }
+        });
+
+        let citext_attr = is_citext(&c.column_type).then(|| {
+            quote! {
+                #[sky_orm(citext)]
+            }
+        });
 
+        // Columns following the model's naming convention are left to the derive to rename.
         let column_name = &c.name;
+        let column_attr = rename_rule
+            .is_none_or(|rule| rule.apply(&field_name.to_string()).ne(column_name))
+            .then(|| {
+                quote! {
+                    #[sky_orm(column = #column_name)]
+                }
+            });
+
+        let doc_attr = c.comment.as_ref().map(|e| quote! { #[doc = #e] });
 
         quote! {
+            #doc_attr
             #(
                 #attrs
             )*
-            #[sky_orm(column = #column_name)]
+            #column_attr
+            #money_attr
+            #citext_attr
             #field_name: #ty_quote,
         }
     });
 
-    let relation_impls = column_field_pairings.iter().filter_map(|e| {
-        e.0.foreign_key.as_ref().map(|foreign_key| {
-            let module_name = Ident::new(&foreign_key.target_table, Span::call_site());
-            let column_struct_name =
-                e.1.as_ref()
-                    // need to do this weirdness, because this is how the struct name is computed
-                    // inside the derive macro. directly going to PascalCase might have unintended
-                    // consequences
-                    .map_or_else(|| e.0.name.to_case(Case::Snake).to_case(Case::Pascal),|e| {
-                        e.rename_to
-                            .as_ref().map_or_else(|| e.field_name.to_string().to_case(Case::Pascal), std::string::ToString::to_string)
-                    });
+    // need to do this weirdness, because this is how the struct name is computed inside the
+    // derive macro. directly going to PascalCase might have unintended consequences
+    let column_struct_name = |column: &str| {
+        let name = column_field_pairings
+            .iter()
+            .find(|e| e.0.name.eq(column))
+            .and_then(|e| e.1.as_ref())
+            .and_then(|e| e.rename_to.as_ref())
+            .map_or_else(
+                || column.to_case(Case::Snake),
+                std::string::ToString::to_string,
+            )
+            .to_case(Case::Pascal);
+
+        Ident::new(&name, Span::call_site())
+    };
 
-            let column_struct_name = Ident::new(&column_struct_name, Span::call_site());
+    let entity = arg
+        .names
+        .entity
+        .clone()
+        .unwrap_or_else(|| Ident::new("Entity", Span::call_site()));
+    let columns_module = arg
+        .names
+        .columns
+        .clone()
+        .unwrap_or_else(|| Ident::new("columns", Span::call_site()));
 
-            let relation_type = if e.0.unique {
-                quote! {
-                    ::sky_orm::entity::relation::OneToOne
-                }
-            } else {
+    let relation_impls = table
+        .foreign_keys
+        .iter()
+        .filter(|e| !e.columns.iter().any(|c| is_excluded(c)))
+        .map(|foreign_key| {
+        let relation_target = arg
+            .relation_targets
+            .iter()
+            .find(|e| e.table_name.to_string().eq(&foreign_key.target_table));
+
+        let target_module = relation_target.map_or_else(
+            || {
+                let module_name = Ident::new(&foreign_key.target_table, Span::call_site());
+                quote! { super::#module_name }
+            },
+            |e| {
+                let module = &e.module;
+                quote! { #module }
+            },
+        );
+        let target_entity = relation_target
+            .and_then(|e| e.entity.clone())
+            .unwrap_or_else(|| Ident::new("Entity", Span::call_site()));
+        let target_columns_module = relation_target
+            .and_then(|e| e.columns.clone())
+            .unwrap_or_else(|| Ident::new("columns", Span::call_site()));
+        let target_primary_key = schema
+            .find_table(&foreign_key.qualified_target_table())
+            .and_then(|e| e.primary_key.as_ref());
+
+        let references_primary_key = foreign_key.target_columns.is_empty()
+            || (foreign_key.target_columns.len() == 1
+                && target_primary_key.is_some_and(|p| foreign_key.target_columns[0].eq(p)));
+
+        // This is the owning side, so many entries may refer to the same target, unless the foreign
+        // key is unique. The target gets the inverse side, `OneToMany` or `OneToOne`, implemented
+        // automatically.
+        let relation_type = if !foreign_key.is_composite()
+            && table
+                .find_column(&foreign_key.columns[0])
+                .is_some_and(|e| e.unique)
+        {
+            quote! {
+                ::sky_orm::entity::relation::OneToOne
+            }
+        } else {
+            quote! {
+                ::sky_orm::entity::relation::ManyToOne
+            }
+        };
+
+        if !foreign_key.is_composite() && references_primary_key {
+            let column_struct_name = column_struct_name(&foreign_key.columns[0]);
+
+            return (
+                quote! { #target_module::#target_entity },
                 quote! {
-                    ::sky_orm::entity::relation::OneToMany
-                }
+                    impl ::sky_orm::entity::relation::Related<#target_module::#target_entity, #columns_module::#column_struct_name> for #entity {
+                        type RelationType = #relation_type;
+                    }
+                },
+            );
+        }
+
+        // Composite keys must name their referenced columns explicitly, we cannot resolve them
+        // otherwise.
+        if foreign_key.target_columns.len() != foreign_key.columns.len() {
+            abort! {
+                arg.table_name.span(), "The referenced columns of foreign key {} are unknown.", foreign_key;
+                note = "Name the referenced columns in the schema, e.g. `REFERENCES {} (a, b)`, or exclude the columns of the foreign key.", foreign_key.target_table
             };
+        }
 
-            Some(quote! {
-                impl ::sky_orm::relation::Related<super::#module_name::Entity, columns::#column_struct_name, ::sky_orm::sqlx::Postgres> for Entity {
+        let own_columns = foreign_key.columns.iter().map(|e| column_struct_name(e));
+        let target_columns = foreign_key.target_columns.iter().map(|e| {
+            Ident::new(
+                &e.to_case(Case::Snake).to_case(Case::Pascal),
+                Span::call_site(),
+            )
+        });
+
+        (
+            quote! { #target_module::#target_entity },
+            quote! {
+                impl ::sky_orm::entity::relation::CompositeRelated<#target_module::#target_entity, (#(#columns_module::#own_columns,)*)> for #entity {
                     type RelationType = #relation_type;
+                    type ReferencedColumns = (#(#target_module::#target_columns_module::#target_columns,)*);
                 }
-            })
-        })
+            },
+        )
+    })
+    .collect::<Vec<_>>();
+
+    // The derive only knows about the foreign keys of `belongs_to`, so tell it which entities the
+    // relations refer to, for sessions to write them first.
+    let references_attrs = relation_impls.iter().map(|(target, _)| {
+        quote! {
+            #[sky_orm(references = #target)]
+        }
     });
+    let relation_impls = relation_impls.iter().map(|(_, e)| e);
+
+    // The table may have been given qualified by its schema, e.g. `"analytics.events"`.
+    let schema_attr = table.schema.as_ref().map(|e| quote! { schema = #e, });
+    let table_name = &table.name;
 
     let sky_orm_attr = if let Some(e) = &table.primary_key {
         let primary_key_field_name = arg
             .field_additions
             .iter()
             .find_map(|f| {
-                if f.field_name.to_string().eq(e) {
-                    if let Some(r) = &f.rename_to {
-                        return Some(r.to_string());
-                    }
+                if f.field_name.to_string().eq(e)
+                    && let Some(r) = &f.rename_to
+                {
+                    return Some(r.to_string());
                 }
 
                 None
@@ -293,23 +690,100 @@ pub fn decl_model(input: TokenStream) -> TokenStream {
             .unwrap_or_else(|| e.to_case(Case::Snake));
 
         quote! {
-            #[sky_orm(primary_key = #primary_key_field_name, table = #table_name)]
+            #[sky_orm(primary_key = #primary_key_field_name, #schema_attr table = #table_name)]
         }
     } else {
         quote! {
-            #[sky_orm(table = #table_name)]
+            #[sky_orm(#schema_attr table = #table_name)]
         }
     };
 
+    let read_only_attr = table.is_view.then(|| {
+        quote! {
+            #[sky_orm(read_only)]
+        }
+    });
+
     let struct_attrs = arg.struct_attrs;
 
+    let schema_dependency = schema_file.dependency_tokens();
+
+    let model_ident = arg
+        .names
+        .model
+        .clone()
+        .unwrap_or_else(|| Ident::new("Model", Span::call_site()));
+
+    let names_attr = {
+        let entity_attr = arg.names.entity.as_ref().map(|e| quote! { entity = #e, });
+        let active_model_attr = arg
+            .names
+            .active_model
+            .as_ref()
+            .map(|e| quote! { active_model = #e, });
+        let columns_attr = arg.names.columns.as_ref().map(|e| quote! { columns = #e, });
+
+        quote! {
+            #[sky_orm(#entity_attr #active_model_attr #columns_attr)]
+        }
+    };
+
+    let default_derive = derive_default.then(|| quote! { ::std::default::Default });
+
+    // Every model is called `Model`, so the GraphQL types are named after their tables instead,
+    // unless the model is given a `#[graphql(..)]` attribute of its own.
+    let graphql_attrs = cfg!(feature = "async-graphql").then(|| {
+        let graphql_name =
+            (!struct_attrs.iter().any(|e| e.path().is_ident("graphql"))).then(|| {
+                let name = table_name.to_case(Case::Pascal);
+                quote! { #[graphql(name = #name)] }
+            });
+
+        quote! {
+            #[derive(::sky_orm::async_graphql::SimpleObject)]
+            #[graphql(crate = "::sky_orm::async_graphql")]
+            #graphql_name
+        }
+    });
+
+    // The generated enums share their module with the model's items.
+    let item_idents = [
+        model_ident.clone(),
+        entity,
+        arg.names
+            .active_model
+            .clone()
+            .unwrap_or_else(|| Ident::new("ActiveModel", Span::call_site())),
+        columns_module,
+    ];
+
+    // Columns of the same enum type share a single generated enum.
+    let mut enum_quotes = Vec::<(String, TokenStream)>::new();
+    for e in generated_enums.iter().flatten() {
+        e.reject_clash(&item_idents);
+
+        if !enum_quotes.iter().any(|(ident, _)| e.ident.eq(ident)) {
+            enum_quotes.push((e.ident.to_string(), e.to_tokens()));
+        }
+    }
+    let enum_quotes = enum_quotes.into_iter().map(|(_, e)| e);
+
+    let doc_attr = table.comment.as_ref().map(|e| quote! { #[doc = #e] });
+
     quote! {
-        #[derive(::sky_orm::DatabaseModel, ::std::default::Default)]
+        #doc_attr
+        #[derive(::sky_orm::DatabaseModel, #default_derive)]
+        #graphql_attrs
         #(
             #struct_attrs
         )*
         #sky_orm_attr
-        pub struct Model {
+        #read_only_attr
+        #names_attr
+        #(
+            #references_attrs
+        )*
+        pub struct #model_ident {
             #(
                 #field_quotes
             )*
@@ -318,5 +792,130 @@ pub fn decl_model(input: TokenStream) -> TokenStream {
         #(
             #relation_impls
         )*
+
+        #(
+            #enum_quotes
+        )*
+
+        #schema_dependency
+    }
+}
+
+/// A table declared by `models!`, with the directives of `model!` for it, if any, e.g.
+/// `"posts" { exclude: { draft } }`.
+struct ModelsEntry {
+    attrs: Vec<Attribute>,
+    table_name: LitStr,
+    directives: TokenStream,
+}
+
+impl Parse for ModelsEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let table_name = input.parse::<LitStr>()?;
+
+        let directives = if input.peek(Brace) {
+            let content;
+            braced!(content in input);
+            content.parse::<TokenStream>()?
+        } else {
+            TokenStream::new()
+        };
+
+        Ok(Self {
+            attrs,
+            table_name,
+            directives,
+        })
+    }
+}
+
+/// Declare the models of several tables at once, each in a module named after its table, e.g.
+/// `users` for `"users"`. Relations between the tables are wired to these modules, unless given
+/// explicitly with the `relations` directive.
+pub fn decl_models(input: TokenStream) -> TokenStream {
+    let entries = match Punctuated::<ModelsEntry, Token![,]>::parse_terminated.parse2(input) {
+        Ok(e) => e,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let mut args = vec![];
+    for e in &entries {
+        let (attrs, table_name, directives) = (&e.attrs, &e.table_name, &e.directives);
+
+        match parse2::<DeclModelArgs>(quote! { #(#attrs)* #table_name, #directives }) {
+            Ok(arg) => args.push(arg),
+            Err(e) => return e.to_compile_error(),
+        }
+    }
+
+    // Tables qualified by their schema are referred to by foreign keys with their name only.
+    let names = args
+        .iter()
+        .map(|e| {
+            let table_name = e.table_name.value();
+            let name = table_name
+                .rsplit_once('.')
+                .map_or(table_name.as_str(), |(_, name)| name)
+                .to_string();
+
+            let Ok(module) = syn::parse_str::<Ident>(&name.to_case(Case::Snake)) else {
+                abort! {
+                    e.table_name.span(), "Table \"{}\" has no valid module name.", name;
+                    note = "Declare it with `model!` in a module of your own instead."
+                };
+            };
+
+            (name, module)
+        })
+        .collect::<Vec<_>>();
+
+    if let Some((_, duplicate)) = names
+        .iter()
+        .find(|e| names.iter().filter(|o| e.1.eq(&o.1)).count() > 1)
+    {
+        abort! {
+            duplicate.span(), "More than one table would be declared in module `{}`.", duplicate;
+            note = "Declare one of them with `model!` in a module of your own instead."
+        };
+    }
+
+    let targets = args
+        .iter()
+        .zip(&names)
+        .filter_map(|(arg, (name, module))| {
+            Some(RelationTarget {
+                table_name: syn::parse_str::<Ident>(name).ok()?,
+                module: parse2(quote! { super::#module }).ok()?,
+                entity: arg.names.entity.clone(),
+                columns: arg.names.columns.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let modules = args.into_iter().zip(&names).map(|(mut arg, (_, module))| {
+        for target in &targets {
+            if !arg
+                .relation_targets
+                .iter()
+                .any(|e| e.table_name.eq(&target.table_name))
+            {
+                arg.relation_targets.push(target.clone());
+            }
+        }
+
+        let model = expand_model(arg);
+
+        quote! {
+            pub mod #module {
+                #model
+            }
+        }
+    });
+
+    quote! {
+        #(
+            #modules
+        )*
     }
 }
@@ -1,18 +1,49 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use sqlparser::ast::DataType;
+use sqlparser::ast::{ArrayElemTypeDef, DataType, ObjectName, ObjectNamePart};
+use syn::Path;
 
+/// Rust types for SQL types that sky-orm does not know about, e.g. domains, enums or types of
+/// extensions, by the name of the SQL type.
+#[derive(Default)]
+pub struct CustomTypes(Vec<(String, Path)>);
+
+impl CustomTypes {
+    /// Map the SQL type called `sql_name` (case-insensitive) to `ty`.
+    pub fn insert(&mut self, sql_name: &str, ty: Path) {
+        self.0.push((sql_name.to_lowercase(), ty));
+    }
+
+    pub fn get(&self, sql_type: &DataType) -> Option<&Path> {
+        let DataType::Custom(name, _) = sql_type else {
+            return None;
+        };
+
+        let name = name.to_string().to_lowercase();
+
+        self.0.iter().find(|(e, _)| e.eq(&name)).map(|(_, ty)| ty)
+    }
+}
+
+/// The rust type that a column of the given SQL type is decoded into, or [`None`] if the SQL type
+/// is not supported. Custom SQL types are looked up in `custom_types` first.
+///
+/// Unsupported types are not an error here, since verifying a derived model only checks the
+/// columns whose types are known and skips the others. `model!` reports them instead.
 #[allow(clippy::match_same_arms, clippy::too_many_lines)]
-pub fn sql_to_rust_type(sql_type: &DataType) -> TokenStream {
-    match sql_type {
-        DataType::Table(_) => todo!(),
+pub fn sql_to_rust_type(sql_type: &DataType, custom_types: &CustomTypes) -> Option<TokenStream> {
+    if let Some(ty) = custom_types.get(sql_type) {
+        return Some(quote! { #ty });
+    }
+
+    let ty = match sql_type {
+        DataType::Table(_) => return None,
         DataType::TinyText
         | DataType::MediumText
         | DataType::LongText
         | DataType::String(_)
         | DataType::FixedString(_)
         | DataType::Text
-        | DataType::Uuid
         | DataType::Nvarchar(_)
         | DataType::Varchar(_)
         | DataType::CharVarying(_)
@@ -21,22 +52,34 @@ pub fn sql_to_rust_type(sql_type: &DataType) -> TokenStream {
         | DataType::Character(_) => quote! {
             ::std::string::String
         },
-        DataType::CharacterLargeObject(_) => todo!(),
-        DataType::CharLargeObject(_) => todo!(),
-        DataType::Clob(_) => todo!(),
-        DataType::Binary(_) => todo!(),
-        DataType::Varbinary(_) => todo!(),
-        DataType::Blob(_) => todo!(),
-        DataType::TinyBlob => todo!(),
-        DataType::MediumBlob => todo!(),
-        DataType::LongBlob => todo!(),
-        DataType::Bytes(_) => todo!(),
+        DataType::Uuid => {
+            if cfg!(feature = "uuid") {
+                quote! {
+                    ::sky_orm::sqlx::types::Uuid
+                }
+            } else {
+                quote! {
+                    ::std::string::String
+                }
+            }
+        }
+        DataType::CharacterLargeObject(_) => return None,
+        DataType::CharLargeObject(_) => return None,
+        DataType::Clob(_) => return None,
+        DataType::Binary(_)
+        | DataType::Varbinary(_)
+        | DataType::Blob(_)
+        | DataType::TinyBlob
+        | DataType::MediumBlob
+        | DataType::LongBlob
+        | DataType::Bytes(_)
+        | DataType::Bytea => quote! {
+            ::std::vec::Vec<u8>
+        },
         DataType::Decimal(_)
         | DataType::BigNumeric(_)
         | DataType::Numeric(_)
-        | DataType::BigDecimal(_) => quote! {
-            f64
-        },
+        | DataType::BigDecimal(_) => decimal_type(),
         DataType::TinyInt(_) | DataType::Int2(_) | DataType::SmallInt(_) | DataType::Int8(_) => {
             quote! {
                 i8
@@ -51,7 +94,7 @@ pub fn sql_to_rust_type(sql_type: &DataType) -> TokenStream {
         DataType::Int64 => quote! {
             i64
         },
-        DataType::Int256 => todo!(),
+        DataType::Int256 => return None,
         DataType::TinyIntUnsigned(_)
         | DataType::UTinyInt
         | DataType::Int2Unsigned(_)
@@ -69,7 +112,7 @@ pub fn sql_to_rust_type(sql_type: &DataType) -> TokenStream {
         DataType::UInt64 => quote! {
             u64
         },
-        DataType::UInt256 => todo!(),
+        DataType::UInt256 => return None,
         DataType::Int128 | DataType::HugeInt | DataType::BigInt(_) => quote! {
             i128
         },
@@ -124,35 +167,128 @@ pub fn sql_to_rust_type(sql_type: &DataType) -> TokenStream {
                 }
             }
         },
-        DataType::Interval => todo!(),
+        DataType::Interval => quote! {
+            ::sky_orm::entity::column::interval::Interval
+        },
         DataType::JSONB | DataType::JSON => quote! {
             ::sky_orm::sqlx::types::JsonRawValue
         },
-        DataType::Regclass => todo!(),
-        DataType::Bytea => todo!(),
-        DataType::Bit(_) => todo!(),
-        DataType::BitVarying(_) => todo!(),
-        DataType::VarBit(_) => todo!(),
-        DataType::Custom(_, _) => todo!(),
-        DataType::Array(_) => todo!(),
-        DataType::Map(_, _) => todo!(),
-        DataType::Tuple(_) => todo!(),
-        DataType::Nested(_) => todo!(),
-        DataType::Enum(_, _) => todo!(),
-        DataType::Set(_) => todo!(),
-        DataType::Struct(_, _) => todo!(),
-        DataType::Union(_) => todo!(),
+        DataType::Regclass => return None,
+        DataType::Bit(_) => return None,
+        DataType::BitVarying(_) => return None,
+        DataType::VarBit(_) => return None,
+        DataType::Custom(name, _) => match extension_type_name(name).as_str() {
+            // pgvector
+            "vector" => quote! {
+                ::sky_orm::pgvector::Vector
+            },
+            "sparsevec" => quote! {
+                ::sky_orm::pgvector::SparseVector
+            },
+            "inet" | "cidr" if cfg!(feature = "ipnetwork") => quote! {
+                ::sky_orm::sqlx::types::ipnetwork::IpNetwork
+            },
+            "macaddr" if cfg!(feature = "mac_address") => quote! {
+                ::sky_orm::sqlx::types::mac_address::MacAddress
+            },
+            // Comparisons are made case-insensitive by the `citext` attribute of the derive.
+            "citext" => quote! {
+                ::std::string::String
+            },
+            // PostGIS
+            "geometry" | "geography" => quote! {
+                ::sky_orm::entity::column::geo::Wkb
+            },
+            _ => return None,
+        },
+        DataType::Array(
+            ArrayElemTypeDef::AngleBracket(inner)
+            | ArrayElemTypeDef::SquareBracket(inner, _)
+            | ArrayElemTypeDef::Parenthesis(inner),
+        ) => {
+            let inner_type = sql_to_rust_type(inner, custom_types)?;
+
+            quote! {
+                ::std::vec::Vec<#inner_type>
+            }
+        }
+        DataType::Array(ArrayElemTypeDef::None) => return None,
+        DataType::Map(_, _) => return None,
+        DataType::Tuple(_) => return None,
+        DataType::Nested(_) => return None,
+        DataType::Enum(_, _) => return None,
+        DataType::Set(_) => return None,
+        DataType::Struct(_, _) => return None,
+        DataType::Union(_) => return None,
         DataType::Nullable(data_type) => {
-            let inner_type = sql_to_rust_type(data_type);
+            let inner_type = sql_to_rust_type(data_type, custom_types)?;
 
             quote! {
                 ::std::option::Option<#inner_type>
             }
         }
-        DataType::LowCardinality(_) => todo!(),
-        DataType::Unspecified => todo!(),
-        DataType::Trigger => todo!(),
-        DataType::AnyType => todo!(),
-        DataType::GeometricType(_) => todo!(),
+        DataType::LowCardinality(_) => return None,
+        DataType::Unspecified => return None,
+        DataType::Trigger => return None,
+        DataType::AnyType => return None,
+        DataType::GeometricType(_) => return None,
+    };
+
+    Some(ty)
+}
+
+/// Whether the rust type of the given SQL type implements [`Default`], so that generated models
+/// can derive it. This is unknown for custom types, so they are assumed not to.
+pub fn implements_default(sql_type: &DataType, custom_types: &CustomTypes) -> bool {
+    if custom_types.get(sql_type).is_some() {
+        return false;
+    }
+
+    match sql_type {
+        DataType::Custom(name, _) => !matches!(
+            extension_type_name(name).as_str(),
+            "vector" | "sparsevec" | "inet" | "cidr" | "geometry" | "geography"
+        ),
+        _ => true,
+    }
+}
+
+/// Whether the given SQL type is the case-insensitive `citext` of postgres.
+pub fn is_citext(sql_type: &DataType) -> bool {
+    match sql_type {
+        DataType::Custom(name, _) => extension_type_name(name) == "citext",
+        DataType::Nullable(inner) => is_citext(inner),
+        _ => false,
+    }
+}
+
+/// The lowercase, unqualified name of a custom type, as the types of extensions are matched by.
+/// Whether they are qualified with the schema the extension is installed in (e.g. `public.citext`)
+/// depends on the search path the schema was introspected with.
+fn extension_type_name(name: &ObjectName) -> String {
+    name.0
+        .last()
+        .map(|e| {
+            let ObjectNamePart::Identifier(ident) = e;
+
+            ident.value.to_lowercase()
+        })
+        .unwrap_or_default()
+}
+
+/// The rust type of `DECIMAL`/`NUMERIC` columns, depending on which decimal crate is enabled.
+fn decimal_type() -> TokenStream {
+    if cfg!(feature = "rust_decimal") {
+        quote! {
+            ::sky_orm::sqlx::types::Decimal
+        }
+    } else if cfg!(feature = "bigdecimal") {
+        quote! {
+            ::sky_orm::sqlx::types::BigDecimal
+        }
+    } else {
+        quote! {
+            f64
+        }
     }
 }
@@ -0,0 +1,107 @@
+use proc_macro_error2::emit_error;
+use proc_macro2::Span;
+use sky_orm_sqlparse::schema::SqlSchema;
+use syn::{GenericArgument, Ident, PathArguments, Type, parse2};
+
+use crate::schema::type_conversion::{CustomTypes, sql_to_rust_type};
+
+/// A field of a hand-written model, mapped to a column of its table.
+pub struct ModelField<'a> {
+    pub ident: &'a Ident,
+    pub column: &'a str,
+    pub ty: &'a Type,
+}
+
+/// Check the fields of a model against its table in the schema, emitting an error for every field
+/// whose column does not exist, or whose type is incompatible with the column's.
+pub fn verify_model<'a>(
+    schema: &SqlSchema,
+    table_name: &str,
+    span: Span,
+    fields: impl IntoIterator<Item = ModelField<'a>>,
+) {
+    let Some(table) = schema.find_table(table_name) else {
+        emit_error! {
+            span, "Table \"{}\" does not exist in schema.", table_name;
+            note = "If the schema is out of date, run `generate-schema` to update it.";
+        };
+        return;
+    };
+
+    for field in fields {
+        let Some(column) = table.find_column(field.column) else {
+            emit_error! {
+                field.ident, "Column \"{}\" does not exist in table \"{}\".", field.column, table_name;
+                note = "Use #[sky_orm(column = \"my_column_name\")] if the column is named differently.";
+            };
+            continue;
+        };
+
+        let (is_option, ty) = unwrap_option(field.ty);
+
+        // Views don't carry any constraints, so their columns can't be checked for nullability.
+        if column.nullable && !is_option && !table.is_view {
+            emit_error! {
+                field.ty, "Column \"{}\" is nullable, but this field is not an `Option`.", column.name
+            };
+        }
+
+        let Some(expected) = sql_to_rust_type(&column.column_type, &CustomTypes::default())
+            .and_then(|e| parse2::<Type>(e).ok())
+            .and_then(|e| type_class(&e))
+        else {
+            continue;
+        };
+
+        if type_class(ty).is_some_and(|actual| actual != expected) {
+            emit_error! {
+                field.ty, "Type of this field is incompatible with column \"{}\" of type `{}`.", column.name, column.column_type
+            };
+        }
+    }
+}
+
+/// Split `Option<T>` into `(true, T)`, and any other type `T` into `(false, T)`.
+pub fn unwrap_option(ty: &Type) -> (bool, &Type) {
+    if let Type::Path(path) = ty
+        && let Some(segment) = path.path.segments.last()
+        && segment.ident == "Option"
+        && let PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(GenericArgument::Type(inner)) = args.args.first()
+    {
+        return (true, inner);
+    }
+
+    (false, ty)
+}
+
+/// A name for the kind of values a type holds, unifying types that the database can decode into
+/// one another (e.g. all integers). [`None`] for types unknown to sky-orm, which may decode from
+/// anything and are therefore not checked.
+pub fn type_class(ty: &Type) -> Option<&'static str> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let class = match path.path.segments.last()?.ident.to_string().as_str() {
+        "i8" | "i16" | "i32" | "i64" | "i128" | "u8" | "u16" | "u32" | "u64" | "u128" => "integer",
+        "f32" | "f64" => "float",
+        "Decimal" | "BigDecimal" => "decimal",
+        "String" | "PgCiText" => "string",
+        "Uuid" => "uuid",
+        "bool" => "bool",
+        "NaiveDate" => "date",
+        "NaiveTime" => "time",
+        "NaiveDateTime" | "DateTime" => "timestamp",
+        "Interval" | "PgInterval" => "interval",
+        "JsonRawValue" | "Json" | "Value" => "json",
+        "IpNetwork" | "IpAddr" => "network address",
+        "MacAddress" => "mac address",
+        "Vector" => "vector",
+        "SparseVector" => "sparse vector",
+        "Wkb" => "spatial value",
+        _ => return None,
+    };
+
+    Some(class)
+}
@@ -1,5 +1,6 @@
 use dotenvy::dotenv;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DbType {
     MySql,
     Postgres,
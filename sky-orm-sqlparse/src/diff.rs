@@ -0,0 +1,424 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+use sqlparser::ast::DataType;
+
+use crate::schema::{SqlColumn, SqlSchema, SqlTable};
+
+/// A single difference between an expected schema (e.g. the checked-in `schema.json`) and the
+/// actual schema of a live database.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SchemaChange {
+    /// The table exists in the database, but not in the expected schema.
+    TableAdded {
+        table: String,
+    },
+    /// The table exists in the expected schema, but not in the database.
+    TableRemoved {
+        table: String,
+    },
+    /// The table has turned into a view, or vice versa.
+    TableKindChanged {
+        table: String,
+        is_view: bool,
+    },
+    ColumnAdded {
+        table: String,
+        column: String,
+    },
+    ColumnRemoved {
+        table: String,
+        column: String,
+    },
+    /// A property of a column (its type, nullability, etc.) differs.
+    ColumnChanged {
+        table: String,
+        column: String,
+        property: &'static str,
+        expected: String,
+        actual: String,
+    },
+    PrimaryKeyChanged {
+        table: String,
+        expected: Option<String>,
+        actual: Option<String>,
+    },
+    ForeignKeyAdded {
+        table: String,
+        foreign_key: String,
+    },
+    ForeignKeyRemoved {
+        table: String,
+        foreign_key: String,
+    },
+    /// The enum type exists in the database, but not in the expected schema.
+    EnumAdded {
+        name: String,
+    },
+    /// The enum type exists in the expected schema, but not in the database.
+    EnumRemoved {
+        name: String,
+    },
+    /// The values of the enum type differ, or are declared in a different order, which also
+    /// changes how they are sorted.
+    EnumChanged {
+        name: String,
+        expected: Vec<String>,
+        actual: Vec<String>,
+    },
+}
+
+impl Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TableAdded { table } => write!(f, "table `{table}` is missing from the schema"),
+            Self::TableRemoved { table } => {
+                write!(f, "table `{table}` no longer exists in the database")
+            }
+            Self::TableKindChanged { table, is_view } => write!(
+                f,
+                "`{table}` is now a {}",
+                if *is_view { "view" } else { "table" }
+            ),
+            Self::ColumnAdded { table, column } => {
+                write!(f, "column `{table}.{column}` is missing from the schema")
+            }
+            Self::ColumnRemoved { table, column } => {
+                write!(
+                    f,
+                    "column `{table}.{column}` no longer exists in the database"
+                )
+            }
+            Self::ColumnChanged {
+                table,
+                column,
+                property,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{property} of column `{table}.{column}` changed from {expected} to {actual}"
+            ),
+            Self::PrimaryKeyChanged {
+                table,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "primary key of table `{table}` changed from {} to {}",
+                expected.as_deref().unwrap_or("none"),
+                actual.as_deref().unwrap_or("none")
+            ),
+            Self::ForeignKeyAdded { table, foreign_key } => write!(
+                f,
+                "foreign key {foreign_key} of table `{table}` is missing from the schema"
+            ),
+            Self::ForeignKeyRemoved { table, foreign_key } => write!(
+                f,
+                "foreign key {foreign_key} of table `{table}` no longer exists in the database"
+            ),
+            Self::EnumAdded { name } => write!(f, "enum `{name}` is missing from the schema"),
+            Self::EnumRemoved { name } => {
+                write!(f, "enum `{name}` no longer exists in the database")
+            }
+            Self::EnumChanged {
+                name,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "values of enum `{name}` changed from ({}) to ({})",
+                expected.join(", "),
+                actual.join(", ")
+            ),
+        }
+    }
+}
+
+/// Compare the expected schema against the actual one, returning all differences.
+#[must_use]
+pub fn diff_schemas(expected: &SqlSchema, actual: &SqlSchema) -> Vec<SchemaChange> {
+    let mut changes = vec![];
+
+    for table in &expected.tables {
+        match actual.find_table(&table.qualified_name()) {
+            Some(actual_table) => diff_tables(&mut changes, table, actual_table),
+            None => changes.push(SchemaChange::TableRemoved {
+                table: table.qualified_name(),
+            }),
+        }
+    }
+
+    changes.extend(
+        actual
+            .tables
+            .iter()
+            .filter(|e| expected.find_table(&e.qualified_name()).is_none())
+            .map(|e| SchemaChange::TableAdded {
+                table: e.qualified_name(),
+            }),
+    );
+
+    for e in &expected.enums {
+        match actual.find_enum(&e.qualified_name()) {
+            Some(actual_enum) if actual_enum.variants != e.variants => {
+                changes.push(SchemaChange::EnumChanged {
+                    name: e.qualified_name(),
+                    expected: e.variants.clone(),
+                    actual: actual_enum.variants.clone(),
+                });
+            }
+            Some(_) => {}
+            None => changes.push(SchemaChange::EnumRemoved {
+                name: e.qualified_name(),
+            }),
+        }
+    }
+
+    changes.extend(
+        actual
+            .enums
+            .iter()
+            .filter(|e| expected.find_enum(&e.qualified_name()).is_none())
+            .map(|e| SchemaChange::EnumAdded {
+                name: e.qualified_name(),
+            }),
+    );
+
+    changes
+}
+
+fn diff_tables(changes: &mut Vec<SchemaChange>, expected: &SqlTable, actual: &SqlTable) {
+    let table = &expected.qualified_name();
+
+    if expected.is_view != actual.is_view {
+        changes.push(SchemaChange::TableKindChanged {
+            table: table.clone(),
+            is_view: actual.is_view,
+        });
+    }
+
+    for column in &expected.columns {
+        match actual.find_column(&column.name) {
+            Some(actual_column) => diff_columns(changes, table, column, actual_column),
+            None => changes.push(SchemaChange::ColumnRemoved {
+                table: table.clone(),
+                column: column.name.clone(),
+            }),
+        }
+    }
+
+    changes.extend(
+        actual
+            .columns
+            .iter()
+            .filter(|e| expected.find_column(&e.name).is_none())
+            .map(|e| SchemaChange::ColumnAdded {
+                table: table.clone(),
+                column: e.name.clone(),
+            }),
+    );
+
+    if expected.primary_key != actual.primary_key {
+        changes.push(SchemaChange::PrimaryKeyChanged {
+            table: table.clone(),
+            expected: expected.primary_key.clone(),
+            actual: actual.primary_key.clone(),
+        });
+    }
+
+    changes.extend(
+        expected
+            .foreign_keys
+            .iter()
+            .filter(|e| !actual.foreign_keys.contains(e))
+            .map(|e| SchemaChange::ForeignKeyRemoved {
+                table: table.clone(),
+                foreign_key: e.to_string(),
+            }),
+    );
+
+    changes.extend(
+        actual
+            .foreign_keys
+            .iter()
+            .filter(|e| !expected.foreign_keys.contains(e))
+            .map(|e| SchemaChange::ForeignKeyAdded {
+                table: table.clone(),
+                foreign_key: e.to_string(),
+            }),
+    );
+}
+
+fn diff_columns(
+    changes: &mut Vec<SchemaChange>,
+    table: &str,
+    expected: &SqlColumn,
+    actual: &SqlColumn,
+) {
+    let column = &expected.name;
+
+    let properties = [
+        ("type", column_type(expected), column_type(actual)),
+        (
+            "nullability",
+            expected.nullable.to_string(),
+            actual.nullable.to_string(),
+        ),
+        (
+            "uniqueness",
+            expected.unique.to_string(),
+            actual.unique.to_string(),
+        ),
+    ];
+
+    changes.extend(
+        properties
+            .into_iter()
+            .filter(|(_, expected, actual)| expected != actual)
+            .map(|(property, expected, actual)| SchemaChange::ColumnChanged {
+                table: table.to_string(),
+                column: column.clone(),
+                property,
+                expected,
+                actual,
+            }),
+    );
+}
+
+/// The type of `column`, as compared between schemas. `citext` is compared by its unqualified
+/// name, since whether it is qualified with the schema its extension is installed in (e.g.
+/// `public.citext`) depends on the search path it was introspected with.
+fn column_type(column: &SqlColumn) -> String {
+    match &column.column_type {
+        DataType::Custom(name, args)
+            if args.is_empty()
+                && name
+                    .0
+                    .last()
+                    .is_some_and(|e| e.to_string().eq_ignore_ascii_case("citext")) =>
+        {
+            "citext".to_string()
+        }
+        e => e.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod test {
+    use crate::{
+        query::{SqlDialect, parse_enums, parse_tables},
+        schema::SqlSchema,
+    };
+
+    use super::{SchemaChange, diff_schemas};
+
+    #[test]
+    fn test_diff_schemas() {
+        let expected = SqlSchema::new(
+            parse_tables(
+                "CREATE TABLE `a`(`id` INTEGER NOT NULL PRIMARY KEY, `name` TEXT);
+                CREATE TABLE `b`(`id` INTEGER NOT NULL PRIMARY KEY)",
+                SqlDialect::Sqlite,
+            )
+            .expect("Failed to parse query"),
+        );
+
+        let actual = SqlSchema::new(
+            parse_tables(
+                "CREATE TABLE `a`(`id` INTEGER NOT NULL PRIMARY KEY, `name` TEXT NOT NULL, `b_id` INTEGER REFERENCES `b`(`id`));
+                CREATE TABLE `c`(`id` INTEGER NOT NULL PRIMARY KEY)",
+                SqlDialect::Sqlite,
+            )
+            .expect("Failed to parse query"),
+        );
+
+        let changes = diff_schemas(&expected, &actual);
+
+        assert_eq!(
+            changes,
+            vec![
+                SchemaChange::ColumnChanged {
+                    table: "a".to_string(),
+                    column: "name".to_string(),
+                    property: "nullability",
+                    expected: "true".to_string(),
+                    actual: "false".to_string(),
+                },
+                SchemaChange::ColumnAdded {
+                    table: "a".to_string(),
+                    column: "b_id".to_string(),
+                },
+                SchemaChange::ForeignKeyAdded {
+                    table: "a".to_string(),
+                    foreign_key: "(b_id) -> b(id)".to_string(),
+                },
+                SchemaChange::TableRemoved {
+                    table: "b".to_string(),
+                },
+                SchemaChange::TableAdded {
+                    table: "c".to_string(),
+                },
+            ]
+        );
+
+        assert!(diff_schemas(&actual, &actual).is_empty());
+    }
+
+    #[test]
+    fn test_diff_citext() {
+        let schema = |sql| {
+            SqlSchema::new(parse_tables(sql, SqlDialect::Postgres).expect("Failed to parse query"))
+        };
+
+        let expected = schema(r#"CREATE TABLE "a"("id" INTEGER PRIMARY KEY, "email" citext)"#);
+        let qualified =
+            schema(r#"CREATE TABLE "a"("id" INTEGER PRIMARY KEY, "email" public.CITEXT)"#);
+        let text = schema(r#"CREATE TABLE "a"("id" INTEGER PRIMARY KEY, "email" TEXT)"#);
+
+        assert!(diff_schemas(&expected, &qualified).is_empty());
+        assert_eq!(
+            diff_schemas(&expected, &text),
+            vec![SchemaChange::ColumnChanged {
+                table: "a".to_string(),
+                column: "email".to_string(),
+                property: "type",
+                expected: "citext".to_string(),
+                actual: "TEXT".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_enums() {
+        let schema = |sql| {
+            SqlSchema::new(vec![])
+                .with_enums(parse_enums(sql, SqlDialect::Postgres).expect("Failed to parse query"))
+        };
+
+        let expected =
+            schema("CREATE TYPE mood AS ENUM ('sad', 'happy'); CREATE TYPE color AS ENUM ('red')");
+        let actual = schema(
+            "CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy'); CREATE TYPE size AS ENUM ('s')",
+        );
+
+        assert_eq!(
+            diff_schemas(&expected, &actual),
+            vec![
+                SchemaChange::EnumChanged {
+                    name: "mood".to_string(),
+                    expected: vec!["sad".to_string(), "happy".to_string()],
+                    actual: vec!["sad".to_string(), "ok".to_string(), "happy".to_string()],
+                },
+                SchemaChange::EnumRemoved {
+                    name: "color".to_string(),
+                },
+                SchemaChange::EnumAdded {
+                    name: "size".to_string(),
+                },
+            ]
+        );
+    }
+}
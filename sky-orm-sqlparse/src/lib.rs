@@ -1,3 +1,4 @@
 pub mod db;
+pub mod diff;
 pub mod query;
 pub mod schema;
@@ -1,36 +1,248 @@
 use sqlparser::{
-    ast::Statement,
-    dialect::SQLiteDialect,
+    ast::{
+        CommentObject, DataType, Expr, ObjectName, ObjectNamePart, Query, SelectItem, SetExpr,
+        Statement, UserDefinedTypeRepresentation, ViewColumnDef,
+    },
+    dialect::{Dialect, GenericDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect},
     parser::{Parser, ParserError},
 };
 
-use crate::schema::SqlTable;
+use crate::{
+    db::DbType,
+    schema::{SqlColumn, SqlEnum, SqlSchema, SqlTable, object_name_schema, object_name_to_string},
+};
+
+/// The SQL dialect to use when parsing statements. Dialect-specific syntax, like backtick-quoted
+/// identifiers or `::type` casts, is only understood by the matching dialect.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SqlDialect {
+    #[default]
+    Generic,
+    MySql,
+    Postgres,
+    Sqlite,
+}
+
+impl SqlDialect {
+    fn parser_dialect(self) -> &'static dyn Dialect {
+        match self {
+            Self::Generic => &GenericDialect {},
+            Self::MySql => &MySqlDialect {},
+            Self::Postgres => &PostgreSqlDialect {},
+            Self::Sqlite => &SQLiteDialect {},
+        }
+    }
+}
+
+impl From<&DbType> for SqlDialect {
+    fn from(value: &DbType) -> Self {
+        match value {
+            DbType::MySql => Self::MySql,
+            DbType::Postgres => Self::Postgres,
+            DbType::Sqlite => Self::Sqlite,
+        }
+    }
+}
+
+/// Parses SQL text containing one or more `CREATE TABLE` or `CREATE VIEW` statements and returns a
+/// list of [`SqlTable`] for each parsed statement. Views are returned as read-only tables.
+///
+/// `COMMENT ON TABLE` and `COMMENT ON COLUMN` statements are applied to the tables they are on.
+///
+/// # Errors
+///
+/// If the query cannot be parsed correctly. See [`ParserError`] for more information.
+pub fn parse_tables(query: &str, dialect: SqlDialect) -> Result<Vec<SqlTable>, ParserError> {
+    Ok(tables_of(&Parser::parse_sql(
+        dialect.parser_dialect(),
+        query,
+    )?))
+}
 
-/// Parses SQL text containing one or more `CREATE TABLE` statements and returns a list of
-/// [`SqlTable`] for each parsed statement.
+/// Parses SQL text containing `CREATE TABLE`, `CREATE VIEW` and `CREATE TYPE .. AS ENUM`
+/// statements into a schema, see [`parse_tables`] and [`parse_enums`].
 ///
 /// # Errors
 ///
 /// If the query cannot be parsed correctly. See [`ParserError`] for more information.
-pub fn parse_tables(query: &str) -> Result<Vec<SqlTable>, ParserError> {
-    let ast = Parser::parse_sql(&SQLiteDialect {}, query)?;
+pub fn parse_schema(query: &str, dialect: SqlDialect) -> Result<SqlSchema, ParserError> {
+    let ast = Parser::parse_sql(dialect.parser_dialect(), query)?;
+
+    Ok(SqlSchema::new(tables_of(&ast)).with_enums(enums_of(&ast)))
+}
 
-    Ok(ast
+/// The tables and views created by `ast`, with the comments of `ast` applied to them.
+fn tables_of(ast: &[Statement]) -> Vec<SqlTable> {
+    let mut tables = ast
         .iter()
-        .filter_map(|e| {
-            if let Statement::CreateTable(statement) = e {
-                Some(statement.into())
-            } else {
-                None
+        .filter_map(|e| match e {
+            Statement::CreateTable(statement) => Some(statement.into()),
+            Statement::CreateView {
+                name,
+                columns,
+                query,
+                ..
+            } => Some(SqlTable {
+                schema: object_name_schema(name),
+                ..SqlTable::new_view(object_name_to_string(name)?, view_columns(columns, query))
+            }),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    for e in ast {
+        if let Statement::Comment {
+            object_type,
+            object_name,
+            comment,
+            ..
+        } = e
+        {
+            apply_comment(&mut tables, *object_type, object_name, comment.clone());
+        }
+    }
+
+    tables
+}
+
+/// Set the comment of a `COMMENT ON TABLE` or `COMMENT ON COLUMN` statement on the table or column
+/// of `tables` it is on, if any. `COMMENT .. IS NULL` removes the comment.
+fn apply_comment(
+    tables: &mut [SqlTable],
+    object_type: CommentObject,
+    name: &ObjectName,
+    comment: Option<String>,
+) {
+    let parts = name
+        .0
+        .iter()
+        .map(|e| {
+            let ObjectNamePart::Identifier(ident) = e;
+
+            ident.value.as_str()
+        })
+        .collect::<Vec<_>>();
+
+    let (column, table_parts) = match object_type {
+        CommentObject::Table => (None, parts.as_slice()),
+        CommentObject::Column => match parts.split_last() {
+            Some((column, table_parts)) => (Some(*column), table_parts),
+            None => return,
+        },
+        _ => return,
+    };
+
+    let Some((table_name, schema)) = table_parts.split_last() else {
+        return;
+    };
+
+    // Unqualified tables are in the default schema, so they may be commented on with a qualified
+    // name, and the other way around.
+    let Some(table) = tables.iter_mut().find(|e| {
+        e.name.eq(table_name)
+            && schema
+                .last()
+                .is_none_or(|schema| e.schema.as_ref().is_none_or(|e| e.eq(schema)))
+    }) else {
+        return;
+    };
+
+    match column {
+        Some(column) => {
+            if let Some(column) = table.columns.iter_mut().find(|e| e.name.eq(column)) {
+                column.comment = comment;
             }
+        }
+        None => table.comment = comment,
+    }
+}
+
+/// Parses SQL text containing one or more `CREATE TYPE .. AS ENUM` statements and returns the enum
+/// types they create. All other statements are ignored.
+///
+/// # Errors
+///
+/// If the query cannot be parsed correctly. See [`ParserError`] for more information.
+pub fn parse_enums(query: &str, dialect: SqlDialect) -> Result<Vec<SqlEnum>, ParserError> {
+    Ok(enums_of(&Parser::parse_sql(
+        dialect.parser_dialect(),
+        query,
+    )?))
+}
+
+/// The enum types created by `ast`.
+fn enums_of(ast: &[Statement]) -> Vec<SqlEnum> {
+    ast.iter()
+        .filter_map(|e| match e {
+            Statement::CreateType {
+                name,
+                representation: UserDefinedTypeRepresentation::Enum { labels },
+            } => Some(SqlEnum {
+                name: object_name_to_string(name)?,
+                schema: object_name_schema(name),
+                variants: labels.iter().map(|e| e.value.clone()).collect(),
+            }),
+            _ => None,
         })
-        .collect())
+        .collect()
+}
+
+/// Parses a single SQL data type, e.g. `VARCHAR(255)`. An empty string results in
+/// [`DataType::Unspecified`].
+///
+/// # Errors
+///
+/// If the data type cannot be parsed correctly. See [`ParserError`] for more information.
+pub fn parse_data_type(data_type: &str, dialect: SqlDialect) -> Result<DataType, ParserError> {
+    if data_type.trim().is_empty() {
+        return Ok(DataType::Unspecified);
+    }
+
+    Parser::new(dialect.parser_dialect())
+        .try_with_sql(data_type)?
+        .parse_data_type()
+}
+
+/// Determine the columns of a view, either from its explicit column list, or from the projection
+/// of its query. Columns whose type cannot be determined are [`DataType::Unspecified`].
+fn view_columns(columns: &[ViewColumnDef], query: &Query) -> Vec<SqlColumn> {
+    if !columns.is_empty() {
+        return columns
+            .iter()
+            .map(|e| {
+                SqlColumn::new_view_column(
+                    e.name.value.clone(),
+                    e.data_type.clone().unwrap_or(DataType::Unspecified),
+                )
+            })
+            .collect();
+    }
+
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return vec![];
+    };
+
+    select
+        .projection
+        .iter()
+        .filter_map(|e| match e {
+            SelectItem::ExprWithAlias { expr: _, alias } => Some(alias.value.clone()),
+            SelectItem::UnnamedExpr(Expr::Identifier(ident)) => Some(ident.value.clone()),
+            SelectItem::UnnamedExpr(Expr::CompoundIdentifier(idents)) => {
+                idents.last().map(|e| e.value.clone())
+            }
+            _ => None,
+        })
+        .map(|e| SqlColumn::new_view_column(e, DataType::Unspecified))
+        .collect()
 }
 
 #[cfg(test)]
 #[allow(clippy::expect_used)]
 mod test {
-    use super::parse_tables;
+    use sqlparser::ast::DataType;
+
+    use super::{SqlDialect, parse_data_type, parse_enums, parse_schema, parse_tables};
 
     #[test]
     fn test_create_table() {
@@ -40,7 +252,7 @@ mod test {
           `something_nullable` TEXT
         )";
 
-        let tables = parse_tables(query).expect("Failed to parse query");
+        let tables = parse_tables(query, SqlDialect::Sqlite).expect("Failed to parse query");
 
         let parsed = tables.first().expect("Failed to get first table");
 
@@ -64,4 +276,195 @@ mod test {
                 .any(|e| e.name.eq("something_nullable") && e.nullable)
         );
     }
+
+    #[test]
+    fn test_schema_qualified_table() {
+        let query = r#"CREATE TABLE "analytics"."events"(
+          "id" BIGINT PRIMARY KEY,
+          "account_id" BIGINT NOT NULL REFERENCES "public"."account"("id")
+        )"#;
+
+        let tables = parse_tables(query, SqlDialect::Postgres).expect("Failed to parse query");
+
+        let parsed = tables.first().expect("Failed to get first table");
+
+        assert_eq!(parsed.name, "events");
+        assert_eq!(parsed.schema.as_deref(), Some("analytics"));
+        assert_eq!(parsed.qualified_name(), "analytics.events");
+        assert!(
+            parsed
+                .foreign_keys
+                .iter()
+                .any(|e| e.target_table == "account"
+                    && e.target_schema.as_deref() == Some("public")
+                    && e.qualified_target_table() == "public.account")
+        );
+    }
+
+    #[test]
+    fn test_foreign_keys() {
+        let query = "CREATE TABLE `membership`(
+          `tenant_id` INTEGER NOT NULL,
+          `user_id` INTEGER NOT NULL,
+          `group_id` INTEGER NOT NULL REFERENCES `group`(`id`),
+          FOREIGN KEY (`tenant_id`, `user_id`) REFERENCES `user`(`tenant_id`, `id`)
+        )";
+
+        let tables = parse_tables(query, SqlDialect::Sqlite).expect("Failed to parse query");
+
+        let parsed = tables.first().expect("Failed to get first table");
+
+        assert_eq!(parsed.foreign_keys.len(), 2);
+        assert!(parsed.foreign_keys.iter().any(|e| !e.is_composite()
+            && e.columns == ["group_id"]
+            && e.target_table == "group"
+            && e.target_columns == ["id"]));
+        assert!(parsed.foreign_keys.iter().any(|e| e.is_composite()
+            && e.columns == ["tenant_id", "user_id"]
+            && e.target_table == "user"
+            && e.target_columns == ["tenant_id", "id"]));
+        assert_eq!(parsed.foreign_keys_of("user_id").count(), 1);
+    }
+
+    #[test]
+    fn test_create_view() {
+        let query = "CREATE VIEW `active_users` AS
+          SELECT `u`.`id`, `name` AS `display_name` FROM `user` `u` WHERE `active`";
+
+        let tables = parse_tables(query, SqlDialect::Sqlite).expect("Failed to parse query");
+
+        let parsed = tables.first().expect("Failed to get first table");
+
+        assert_eq!(parsed.name, "active_users");
+        assert!(parsed.is_view);
+        assert!(parsed.primary_key.is_none());
+        assert_eq!(
+            parsed
+                .columns
+                .iter()
+                .map(|e| e.name.as_str())
+                .collect::<Vec<_>>(),
+            ["id", "display_name"]
+        );
+    }
+
+    #[test]
+    fn test_postgres_dialect() {
+        let query = r#"CREATE TABLE "account"(
+          "id" SERIAL PRIMARY KEY,
+          "created_at" TIMESTAMP NOT NULL DEFAULT '2000-01-01'::timestamp
+        )"#;
+
+        let tables = parse_tables(query, SqlDialect::Postgres).expect("Failed to parse query");
+
+        let parsed = tables.first().expect("Failed to get first table");
+
+        assert_eq!(parsed.name, "account");
+        assert_eq!(parsed.primary_key.as_deref(), Some("id"));
+        assert!(
+            parsed
+                .columns
+                .iter()
+                .any(|e| e.name.eq("created_at") && !e.nullable)
+        );
+    }
+
+    #[test]
+    fn test_extension_types() {
+        let parsed =
+            parse_data_type("vector(3)", SqlDialect::Postgres).expect("Failed to parse data type");
+
+        assert!(
+            matches!(&parsed, DataType::Custom(name, modifiers) if name.to_string() == "vector" && modifiers == &["3"])
+        );
+
+        let parsed = parse_data_type("geometry(Point,4326)", SqlDialect::Postgres)
+            .expect("Failed to parse data type");
+
+        assert!(
+            matches!(&parsed, DataType::Custom(name, modifiers) if name.to_string() == "geometry" && modifiers == &["Point", "4326"])
+        );
+    }
+
+    #[test]
+    fn test_enums() {
+        let query = r#"CREATE TYPE "analytics"."mood" AS ENUM ('sad', 'ok', 'happy');
+          CREATE TABLE "person"("id" SERIAL PRIMARY KEY, "mood" "analytics"."mood")"#;
+
+        let enums = parse_enums(query, SqlDialect::Postgres).expect("Failed to parse query");
+
+        let parsed = enums.first().expect("Failed to get first enum");
+
+        assert_eq!(enums.len(), 1);
+        assert_eq!(parsed.qualified_name(), "analytics.mood");
+        assert_eq!(parsed.variants, ["sad", "ok", "happy"]);
+
+        let query = "CREATE TABLE `ticket`(
+          `id` INTEGER NOT NULL PRIMARY KEY,
+          `status` ENUM('open', 'closed') NOT NULL
+        )";
+
+        let tables = parse_tables(query, SqlDialect::MySql).expect("Failed to parse query");
+
+        let parsed = tables.first().expect("Failed to get first table");
+
+        assert!(parsed.columns.iter().any(|e| e.name.eq("status")
+            && matches!(&e.column_type, DataType::Enum(members, _) if members.len() == 2)));
+    }
+
+    #[test]
+    fn test_schema() {
+        let query = r#"CREATE TYPE "mood" AS ENUM ('sad', 'happy');
+          CREATE TABLE "person"("id" SERIAL PRIMARY KEY, "mood" "mood" NOT NULL)"#;
+
+        let schema = parse_schema(query, SqlDialect::Postgres).expect("Failed to parse query");
+
+        assert!(schema.find_table("person").is_some());
+        assert!(
+            schema
+                .find_enum("mood")
+                .is_some_and(|e| e.variants == ["sad", "happy"])
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let query = r#"CREATE TABLE "analytics"."event"("id" SERIAL PRIMARY KEY, "kind" TEXT NOT NULL);
+          COMMENT ON TABLE "analytics"."event" IS 'Something that happened.';
+          COMMENT ON COLUMN "event"."kind" IS 'What happened.';
+          COMMENT ON COLUMN "event"."id" IS NULL"#;
+
+        let tables = parse_tables(query, SqlDialect::Postgres).expect("Failed to parse query");
+
+        let parsed = tables.first().expect("Failed to get first table");
+
+        assert_eq!(parsed.comment.as_deref(), Some("Something that happened."));
+        assert!(
+            parsed
+                .columns
+                .iter()
+                .any(|e| e.name.eq("kind") && e.comment.as_deref() == Some("What happened."))
+        );
+        assert!(
+            parsed
+                .columns
+                .iter()
+                .any(|e| e.name.eq("id") && e.comment.is_none())
+        );
+
+        let query = "CREATE TABLE `ticket`(
+          `id` INTEGER NOT NULL PRIMARY KEY,
+          `title` TEXT NOT NULL COMMENT 'Shown in the list of tickets.'
+        ) COMMENT = 'A request for support.'";
+
+        let tables = parse_tables(query, SqlDialect::MySql).expect("Failed to parse query");
+
+        let parsed = tables.first().expect("Failed to get first table");
+
+        assert_eq!(parsed.comment.as_deref(), Some("A request for support."));
+        assert!(
+            parsed.columns.iter().any(|e| e.name.eq("title")
+                && e.comment.as_deref() == Some("Shown in the list of tickets."))
+        );
+    }
 }
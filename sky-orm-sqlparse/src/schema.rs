@@ -1,10 +1,107 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
 use serde::{Deserialize, Serialize};
-use sqlparser::ast::{ColumnDef, ColumnOption, CreateTable, DataType, ObjectNamePart};
+use sqlparser::ast::{
+    ColumnDef, ColumnOption, CommentDef, CreateTable, DataType, Ident, ObjectName, ObjectNamePart,
+    TableConstraint,
+};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// The current version of the schema file format. Bumped whenever the format changes in a way that
+/// requires existing schema files to be regenerated.
+///
+/// - `0`: files predating versioning, with a single-column `foreign_key` per column. They're not
+///   read, since those would be lost.
+/// - `1`: composite and table-level foreign keys, and views.
+/// - `2`: named enum types, and the comments of tables and columns.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// The environment variable that overrides the location of the schema file.
+pub const SCHEMA_PATH_VAR: &str = "SKY_ORM_SCHEMA";
+
+/// The location of the schema file of the project in `base_dir`.
+///
+/// This is `sky_orm/schema.json`, unless overridden via the [`SCHEMA_PATH_VAR`] environment
+/// variable, in which case relative paths are resolved against `base_dir`.
+#[must_use]
+pub fn schema_path(base_dir: &Path) -> PathBuf {
+    std::env::var_os(SCHEMA_PATH_VAR).map_or_else(
+        || base_dir.join("sky_orm").join("schema.json"),
+        |e| base_dir.join(e),
+    )
+}
+
+/// A (possibly composite) foreign key, declared either inline on a column or as a table-level
+/// `FOREIGN KEY (...) REFERENCES ...` constraint.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct SqlForeignKey {
+    /// The columns on the owning table making up the key.
+    pub columns: Vec<String>,
     pub target_table: String,
-    pub target_column: String,
+    /// The schema the target table is in, if it is referenced by a qualified name, e.g.
+    /// `public` for `REFERENCES public.account`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_schema: Option<String>,
+    /// The referenced columns on the target table, in the same order as `columns`. Empty if the
+    /// key implicitly references the target table's primary key.
+    pub target_columns: Vec<String>,
+}
+
+impl SqlForeignKey {
+    /// Whether this foreign key spans more than one column.
+    #[must_use]
+    pub const fn is_composite(&self) -> bool {
+        self.columns.len() > 1
+    }
+
+    /// The name of the target table, qualified by its schema if it has one, see
+    /// [`SqlTable::qualified_name`].
+    #[must_use]
+    pub fn qualified_target_table(&self) -> String {
+        self.target_schema.as_ref().map_or_else(
+            || self.target_table.clone(),
+            |schema| format!("{schema}.{}", self.target_table),
+        )
+    }
+}
+
+impl Display for SqlForeignKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({}) -> {}({})",
+            self.columns.join(", "),
+            self.qualified_target_table(),
+            self.target_columns.join(", ")
+        )
+    }
+}
+
+pub(crate) fn object_name_to_string(name: &ObjectName) -> Option<String> {
+    name.0.last().map(|e| {
+        let ObjectNamePart::Identifier(ident) = e;
+
+        ident.value.clone()
+    })
+}
+
+/// The schema a qualified name like `analytics.events` is in, or [`None`] if it is unqualified.
+pub(crate) fn object_name_schema(name: &ObjectName) -> Option<String> {
+    name.0
+        .len()
+        .checked_sub(2)
+        .and_then(|i| name.0.get(i))
+        .map(|e| {
+            let ObjectNamePart::Identifier(ident) = e;
+
+            ident.value.clone()
+        })
+}
+
+fn idents_to_strings(idents: &[Ident]) -> Vec<String> {
+    idents.iter().map(|e| e.value.clone()).collect()
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -14,7 +111,26 @@ pub struct SqlColumn {
     pub nullable: bool,
     pub unique: bool,
     pub primary_key: bool,
-    pub foreign_key: Option<SqlForeignKey>,
+    /// The comment on the column, set with `COMMENT ON COLUMN` on postgres, or the `COMMENT`
+    /// column option on mysql.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl SqlColumn {
+    /// Create a new column belonging to a view. Since views don't carry any constraints, the
+    /// column is assumed to be nullable.
+    #[must_use]
+    pub const fn new_view_column(name: String, column_type: DataType) -> Self {
+        Self {
+            name,
+            column_type,
+            nullable: true,
+            unique: false,
+            primary_key: false,
+            comment: None,
+        }
+    }
 }
 
 impl From<&ColumnDef> for SqlColumn {
@@ -65,42 +181,121 @@ impl From<&ColumnDef> for SqlColumn {
                     }
                 })
                 .unwrap_or(false),
-            foreign_key: value.options.iter().find_map(|e| {
-                if let ColumnOption::ForeignKey {
-                    foreign_table,
-                    referred_columns,
-                    on_delete: _,
-                    on_update: _,
-                    characteristics: _,
-                } = &e.option
-                {
-                    #[allow(clippy::unwrap_used)]
-                    if referred_columns.len() == 1 {
-                        return Some(SqlForeignKey {
-                            target_table: foreign_table.0.last().unwrap().to_string(),
-                            target_column: referred_columns.first().unwrap().to_string(),
-                        });
-                    }
+            comment: value.options.iter().find_map(|e| {
+                if let ColumnOption::Comment(comment) = &e.option {
+                    Some(comment.clone())
+                } else {
+                    None
                 }
-
-                None
             }),
         }
     }
 }
 
+/// Extract the foreign key declared inline on a column definition, if any.
+fn column_foreign_key(column: &ColumnDef) -> Option<SqlForeignKey> {
+    column.options.iter().find_map(|e| {
+        if let ColumnOption::ForeignKey {
+            foreign_table,
+            referred_columns,
+            on_delete: _,
+            on_update: _,
+            characteristics: _,
+        } = &e.option
+        {
+            Some(SqlForeignKey {
+                columns: vec![column.name.value.clone()],
+                target_table: object_name_to_string(foreign_table)?,
+                target_schema: object_name_schema(foreign_table),
+                target_columns: idents_to_strings(referred_columns),
+            })
+        } else {
+            None
+        }
+    })
+}
+
+/// Extract the foreign key declared by a table-level constraint, if it is one.
+fn constraint_foreign_key(constraint: &TableConstraint) -> Option<SqlForeignKey> {
+    if let TableConstraint::ForeignKey {
+        name: _,
+        columns,
+        foreign_table,
+        referred_columns,
+        on_delete: _,
+        on_update: _,
+        characteristics: _,
+    } = constraint
+    {
+        Some(SqlForeignKey {
+            columns: idents_to_strings(columns),
+            target_table: object_name_to_string(foreign_table)?,
+            target_schema: object_name_schema(foreign_table),
+            target_columns: idents_to_strings(referred_columns),
+        })
+    } else {
+        None
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SqlTable {
     pub name: String,
+    /// The schema (or namespace) the table is in, if it was created with a qualified name, e.g.
+    /// `analytics` for `analytics.events`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
     pub columns: Vec<SqlColumn>,
     pub primary_key: Option<String>,
+    #[serde(default)]
+    pub foreign_keys: Vec<SqlForeignKey>,
+    /// Whether this is a view. Views are read-only, and do not need to have a primary key.
+    #[serde(default)]
+    pub is_view: bool,
+    /// The comment on the table, set with `COMMENT ON TABLE` on postgres, or the `COMMENT` table
+    /// option on mysql.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
 }
 
 impl SqlTable {
+    /// Create a new read-only table representing a view.
+    #[must_use]
+    pub const fn new_view(name: String, columns: Vec<SqlColumn>) -> Self {
+        Self {
+            name,
+            schema: None,
+            columns,
+            primary_key: None,
+            foreign_keys: vec![],
+            is_view: true,
+            comment: None,
+        }
+    }
+
+    /// The name of the table, qualified by its schema if it has one, e.g. `analytics.events`.
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        self.schema.as_ref().map_or_else(
+            || self.name.clone(),
+            |schema| format!("{schema}.{}", self.name),
+        )
+    }
+
     #[must_use]
     pub fn find_column(&self, name: &str) -> Option<&SqlColumn> {
         self.columns.iter().find(|e| e.name.eq(name))
     }
+
+    /// Find all foreign keys that the given column is part of.
+    pub fn foreign_keys_of<'a>(
+        &'a self,
+        column: &'a str,
+    ) -> impl Iterator<Item = &'a SqlForeignKey> {
+        self.foreign_keys
+            .iter()
+            .filter(move |e| e.columns.iter().any(|c| c.eq(column)))
+    }
 }
 
 #[allow(clippy::fallible_impl_from, clippy::unwrap_used)]
@@ -109,17 +304,25 @@ impl From<&CreateTable> for SqlTable {
         let columns: Vec<SqlColumn> = create_table.columns.iter().map(SqlColumn::from).collect();
 
         Self {
-            name: create_table
-                .name
-                .0
+            name: object_name_to_string(&create_table.name).unwrap(),
+            schema: object_name_schema(&create_table.name),
+            foreign_keys: create_table
+                .columns
                 .iter()
-                .map(|e| {
-                    let ObjectNamePart::Identifier(ident) = e;
-
-                    ident.value.clone()
-                })
-                .next()
-                .unwrap(),
+                .filter_map(column_foreign_key)
+                .chain(
+                    create_table
+                        .constraints
+                        .iter()
+                        .filter_map(constraint_foreign_key),
+                )
+                .collect(),
+            is_view: false,
+            comment: create_table.comment.as_ref().map(|e| match e {
+                CommentDef::WithEq(comment)
+                | CommentDef::WithoutEq(comment)
+                | CommentDef::AfterColumnDefsWithoutEq(comment) => comment.clone(),
+            }),
             primary_key: columns
                 .iter()
                 .find_map(|e| {
@@ -140,14 +343,86 @@ impl From<&CreateTable> for SqlTable {
     }
 }
 
+/// A named enum type, e.g. one created with `CREATE TYPE mood AS ENUM ('sad', 'happy')` on
+/// postgres.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SqlEnum {
+    pub name: String,
+    /// The schema (or namespace) the type is in, if it was created with a qualified name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// The values of the enum, in the order they are declared (and sorted) in.
+    pub variants: Vec<String>,
+}
+
+impl SqlEnum {
+    /// The name of the type, qualified by its schema if it has one, e.g. `analytics.mood`.
+    #[must_use]
+    pub fn qualified_name(&self) -> String {
+        self.schema.as_ref().map_or_else(
+            || self.name.clone(),
+            |schema| format!("{schema}.{}", self.name),
+        )
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct SqlSchema {
+    /// The version of the format this schema was written in. Schema files predating versioning
+    /// are read as version `0`.
+    #[serde(default)]
+    pub version: u32,
     pub tables: Vec<SqlTable>,
+    /// The named enum types of the database. Enum columns of mysql are not named, their values
+    /// are part of the type of the column instead, see [`DataType::Enum`].
+    ///
+    /// Filled in from `CREATE TYPE .. AS ENUM` statements by
+    /// [`parse_schema`](crate::query::parse_schema). Only sqlite databases can be introspected so
+    /// far, which have no enum types, so the enums of a postgres database have to be added to the
+    /// schema file by hand for now.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enums: Vec<SqlEnum>,
 }
 
 impl SqlSchema {
+    /// Create a new schema in the current format version.
+    #[must_use]
+    pub const fn new(tables: Vec<SqlTable>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            tables,
+            enums: vec![],
+        }
+    }
+
+    /// Add the named enum types of the database to this schema.
+    #[must_use]
+    pub fn with_enums(mut self, enums: Vec<SqlEnum>) -> Self {
+        self.enums = enums;
+        self
+    }
+
+    /// Whether this schema was written in the current format version.
+    #[must_use]
+    pub const fn is_current_version(&self) -> bool {
+        self.version == SCHEMA_VERSION
+    }
+
+    /// Find a table by its name, which may be qualified by its schema, e.g. `analytics.events`.
     #[must_use]
     pub fn find_table(&self, name: &str) -> Option<&SqlTable> {
-        self.tables.iter().find(|e| e.name.eq(name))
+        self.tables
+            .iter()
+            .find(|e| e.qualified_name().eq(name))
+            .or_else(|| self.tables.iter().find(|e| e.name.eq(name)))
+    }
+
+    /// Find an enum type by its name, which may be qualified by its schema.
+    #[must_use]
+    pub fn find_enum(&self, name: &str) -> Option<&SqlEnum> {
+        self.enums
+            .iter()
+            .find(|e| e.qualified_name().eq(name))
+            .or_else(|| self.enums.iter().find(|e| e.name.eq(name)))
     }
 }
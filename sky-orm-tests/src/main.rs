@@ -1,16 +1,106 @@
-#![allow(clippy::print_stdout)]
+#![allow(clippy::print_stdout, clippy::too_many_lines)]
 
-use sky_orm::entity::{
-    Entity,
-    column::{ComparableColumn, OrderableColumn},
+use std::net::{IpAddr, Ipv4Addr};
+
+use sky_orm::{
+    entity::{
+        Entity, HasPrimaryKey,
+        column::{
+            Aliased, Column, ColumnExt, ColumnTuple, ComparableColumn, EntityConditionExpr,
+            NumericColumn, OrderableColumn, TableAlias,
+            array::{AnyColumn, ArrayColumn},
+            geo::{GeoColumn, Wkb},
+            interval::Interval,
+            json::JsonColumn,
+            network::NetworkColumn,
+            vector::VectorColumn,
+        },
+        model::IntoActiveModel,
+        relation::Inverse,
+    },
+    pgvector::Vector,
+    query::{
+        BinaryExpr, BinaryExprOperand, PushToQuery, QueryVariable,
+        aggregate::{Aggregate, AggregateColumn},
+        cond::Cond,
+        hint::{IndexHint, PlannerMethod, QueryHint},
+        parse::ParseFromRow,
+        select::{Order, Select},
+    },
+    session::Session,
+    sqlx::Postgres,
+    sqlx::types::{Decimal, Uuid, ipnetwork::IpNetwork, mac_address::MacAddress},
+    testing::Factory,
 };
 
+/// An operator pack as a third-party crate would define it.
+trait SimilarityColumn: ColumnExt
+where
+    Self::Type: 'static,
+{
+    /// `column % $1`
+    fn similar_to(
+        other: Self::Type,
+    ) -> EntityConditionExpr<impl PushToQuery<<Self::Entity as Entity>::Database>, Self::Entity>
+    {
+        Self::binary_op(BinaryExprOperand::Custom("%"), other)
+    }
+
+    /// `column <-> $1 < $2`
+    fn distance_lt(
+        other: Self::Type,
+        distance: f64,
+    ) -> EntityConditionExpr<impl PushToQuery<<Self::Entity as Entity>::Database>, Self::Entity>
+    where
+        f64: for<'a> sky_orm::sqlx::Encode<'a, <Self::Entity as Entity>::Database>
+            + sky_orm::sqlx::Type<<Self::Entity as Entity>::Database>,
+    {
+        BinaryExpr::new(
+            Self::binary_op(BinaryExprOperand::Custom("<->"), other),
+            QueryVariable::new(distance),
+            BinaryExprOperand::Lt,
+        )
+        .into()
+    }
+}
+
+impl<T> SimilarityColumn for T where T: Column<Type = String> {}
+
+struct SenderAccount;
+
+impl TableAlias for SenderAccount {
+    const ALIAS: &'static str = "sender";
+}
+
+struct RecipientAccount;
+
+impl TableAlias for RecipientAccount {
+    const ALIAS: &'static str = "recipient";
+}
+
+/// A condition borrowing its SQL from elsewhere, e.g. a local.
+struct Fragment<'s>(&'s str);
+
+impl<DB> PushToQuery<DB> for Fragment<'_>
+where
+    DB: sky_orm::sqlx::Database + Sync,
+{
+    fn push_to(&self, builder: &mut sky_orm::sqlx::QueryBuilder<'_, DB>) {
+        builder.push(self.0);
+    }
+}
+
 mod my_entity {
     use sky_orm::entity::relation::{OneToOne, Related};
     use sky_orm_macros::DatabaseModel;
 
     #[derive(DatabaseModel)]
-    #[sky_orm(primary_key = id, table = "entity")]
+    #[sky_orm(
+        primary_key = id,
+        table = "entity",
+        patch_json,
+        references = super::my_other_entity::Entity
+    )]
     pub struct Model {
         id: String,
         name: Option<String>,
@@ -30,19 +120,592 @@ mod my_parsed_entity {
         fields: {
             uuid -> identifier
         },
+        exclude: { tp_targets, original_stop_loss },
     }
 }
 
-mod my_other_entity {
+mod my_parsed_strategy {
+    use sky_orm_macros::model;
+
+    model! {
+        "strategy",
+        names: {
+            model: Strategy,
+            entity: StrategyEntity,
+            active_model: NewStrategy,
+            columns: strategy_columns,
+        },
+    }
+}
+
+mod my_parsed_strategy_config {
+    use sky_orm_macros::model;
+
+    model! {
+        "strategy_config",
+        relations: {
+            strategy: super::my_parsed_strategy { entity: StrategyEntity, columns: strategy_columns },
+        },
+    }
+}
+
+mod my_parsed_view {
+    use sky_orm_macros::model;
+
+    model! {
+        "active_strategy",
+        schema: "sky_orm/schema.json",
+        fields: {
+            open_trades: i64
+        },
+    }
+}
+
+mod my_profile_entity {
+    use sky_orm::sqlx::types::Uuid;
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, table = "profile", rename_all = "camelCase")]
+    pub struct Model {
+        pub id: Uuid,
+        pub display_name: String,
+        pub is_public: bool,
+        #[sky_orm(column = "URL")]
+        pub url: Option<String>,
+    }
+}
+
+mod my_parsed_profile {
+    use sky_orm_macros::model;
+
+    model! {
+        #[sky_orm(rename_all = "camelCase")]
+        "profile",
+    }
+}
+
+mod my_verified_entity {
     use sky_orm_macros::DatabaseModel;
 
+    /// A strategy name, which must not be empty.
+    pub struct StrategyName(String);
+
+    impl TryFrom<String> for StrategyName {
+        type Error = &'static str;
+
+        fn try_from(value: String) -> Result<Self, Self::Error> {
+            if value.is_empty() {
+                Err("Strategy name must not be empty")
+            } else {
+                Ok(Self(value))
+            }
+        }
+    }
+
+    impl From<StrategyName> for String {
+        fn from(value: StrategyName) -> Self {
+            value.0
+        }
+    }
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, table = "strategy", verify_schema)]
+    pub struct Model {
+        pub id: i64,
+        #[sky_orm(try_from = String)]
+        pub name: StrategyName,
+        #[sky_orm(column = "last_error_msg")]
+        pub error: Option<String>,
+        pub next_update_timestamp: chrono::NaiveDateTime,
+        #[sky_orm(skip)]
+        #[allow(dead_code)]
+        pub is_stale: bool,
+    }
+}
+
+mod my_enum_entity {
+    use serde::{Deserialize, Serialize};
+    use sky_orm::DbEnum;
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+    #[sky_orm(rename_all = "snake_case")]
+    pub enum Status {
+        Open,
+        OnHold,
+        #[sky_orm(rename = "done")]
+        Closed,
+    }
+
+    #[derive(DbEnum, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+    #[sky_orm(enum_repr = "i32")]
+    pub enum Priority {
+        Low = 1,
+        High = 10,
+    }
+
+    #[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+    #[sky_orm(type_name = "mood", rename_all = "lowercase")]
+    pub enum Mood {
+        Happy,
+        Grumpy,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+    pub struct TicketSettings {
+        pub notify: bool,
+        pub labels: Vec<String>,
+    }
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, table = "ticket")]
+    pub struct Model {
+        pub id: i64,
+        pub status: Status,
+        pub priority: Priority,
+        pub mood: Option<Mood>,
+        #[sky_orm(json)]
+        pub settings: TicketSettings,
+        pub tags: Vec<String>,
+    }
+}
+
+mod my_ledger_entity {
+    use sky_orm_macros::model;
+
+    model! {
+        #[derive(Debug, Clone)]
+        #[sky_orm(derive(PartialEq))]
+        "ledger_entry",
+        money: { amount },
+    }
+}
+
+mod my_account_entity {
+    use sky_orm_macros::model;
+
+    model! {
+        #[derive(Debug, Clone)]
+        #[sky_orm(
+            factory,
+            has_many(
+                entity = super::my_transfer_entity::Entity,
+                fk = super::my_transfer_entity::columns::AccountId,
+            )
+        )]
+        "account",
+    }
+}
+
+mod my_transfer_entity {
+    use sky_orm::{
+        entity::relation::{ManyToOne, Related},
+        sqlx::types::Uuid,
+    };
+    use sky_orm_macros::DatabaseModel;
+
+    /// A transfer as exposed to API consumers, without its checksum.
+    pub struct ApiTransfer {
+        pub id: Uuid,
+        pub account_id: Uuid,
+        pub amount: i64,
+    }
+
     #[derive(DatabaseModel, Default)]
-    #[sky_orm(primary_key = id, table = "other_entity")]
+    #[sky_orm(primary_key = id, table = "transfer", dto = ApiTransfer)]
+    pub struct Model {
+        pub id: Uuid,
+        pub account_id: Uuid,
+        #[sky_orm(money)]
+        pub amount: i64,
+        #[sky_orm(dto_skip)]
+        pub hash: Vec<u8>,
+    }
+
+    impl Related<super::my_account_entity::Entity, columns::AccountId> for Entity {
+        type RelationType = ManyToOne;
+    }
+}
+
+mod my_host_entity {
+    use sky_orm::DbEnum;
+    use sky_orm_macros::model;
+
+    #[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+    #[sky_orm(type_name = "host_kind", rename_all = "lowercase")]
+    pub enum HostKind {
+        Server,
+        Switch,
+    }
+
+    model! {
+        "host",
+        types: { host_kind: HostKind },
+    }
+}
+
+mod my_job_entity {
+    use sky_orm_macros::model;
+
+    model! { "job", }
+}
+
+mod my_place_entity {
+    use sky_orm_macros::model;
+
+    model! { "place", }
+}
+
+mod my_ticket_entity {
+    use sky_orm_macros::model;
+
+    model! { "ticket", }
+}
+
+mod my_author_entity {
+    use sky_orm_macros::model;
+
+    model! {
+        #[derive(Debug, Clone)]
+        "author",
+    }
+}
+
+mod my_article_entity {
+    use sky_orm_macros::model;
+
+    model! {
+        #[derive(Debug, Clone)]
+        "article",
+        relations: {
+            author: super::my_author_entity,
+        },
+    }
+}
+
+mod my_blog {
+    use sky_orm_macros::models;
+
+    models! {
+        #[derive(Debug, Clone)]
+        "author" {
+            names: { entity: Author },
+        },
+        #[derive(Debug, Clone)]
+        "article",
+    }
+}
+
+mod my_event_entity {
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, schema = "analytics", table = "events")]
+    pub struct Model {
+        pub id: i64,
+        pub name: String,
+    }
+}
+
+mod my_invoice_entity {
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    use sky_orm::{
+        entity::{
+            EntityScope, TenantScoped, column::ComparableColumn, column::EntityConditionExpr,
+        },
+        query::PushToQuery,
+    };
+    use sky_orm_macros::DatabaseModel;
+
+    pub static CURRENT_TENANT: AtomicI64 = AtomicI64::new(0);
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, table = "invoice", tenant_scoped, default_scope)]
+    pub struct Model {
+        pub id: i64,
+        pub tenant_id: i64,
+        pub total: i64,
+        pub voided: bool,
+    }
+
+    impl TenantScoped for Entity {
+        type TenantColumn = columns::TenantId;
+
+        fn current_tenant() -> i64 {
+            CURRENT_TENANT.load(Ordering::Relaxed)
+        }
+    }
+
+    impl EntityScope for Entity {
+        fn default_scope() -> EntityConditionExpr<impl PushToQuery<Self::Database> + 'static, Self>
+        {
+            columns::Voided::eq(false)
+        }
+    }
+}
+
+mod my_user_entity {
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(
+        primary_key = id,
+        table = "user",
+        api(name = UserResponse, derive(serde::Serialize))
+    )]
+    pub struct Model {
+        pub id: i64,
+        /// The address the user signs in with.
+        pub email: String,
+        #[sky_orm(api_skip, sensitive)]
+        pub password_hash: String,
+    }
+}
+
+mod my_post_entity {
+    use sky_orm::{
+        entity::{EntityScope, column::ComparableColumn, column::EntityConditionExpr},
+        query::PushToQuery,
+    };
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, table = "post", default_scope)]
+    pub struct Model {
+        pub id: i64,
+        pub title: String,
+        pub published: bool,
+    }
+
+    impl EntityScope for Entity {
+        fn default_scope() -> EntityConditionExpr<impl PushToQuery<Self::Database> + 'static, Self>
+        {
+            columns::Published::eq(true)
+        }
+    }
+}
+
+mod my_message_entity {
+    use sky_orm::sqlx::types::Uuid;
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel, Debug, Clone)]
+    #[sky_orm(primary_key = id, table = "message", factory)]
+    pub struct Model {
+        pub id: i64,
+        #[sky_orm(belongs_to = super::my_account_entity::Entity, relation = Sender)]
+        pub sender_id: Uuid,
+        #[sky_orm(belongs_to = super::my_account_entity::Entity, relation = Recipient)]
+        pub recipient_id: Uuid,
+        #[sky_orm(belongs_to = super::my_account_entity::Entity, relation = Moderator)]
+        pub moderator_id: Option<Uuid>,
+        pub body: String,
+    }
+}
+
+mod my_category_entity {
+    use sky_orm::entity::relation::tree::SelfRelated;
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, table = "category")]
+    pub struct Model {
+        pub id: i64,
+        pub parent_id: Option<i64>,
+        pub name: String,
+    }
+
+    impl SelfRelated<columns::ParentId> for Entity {}
+}
+
+/// Only checks that the tree helpers can be called on self-related entities.
+#[allow(dead_code)]
+async fn load_category_tree(
+    connection: &mut sky_orm::sqlx::PgConnection,
+    category: &my_category_entity::Model,
+) -> sky_orm::Result<usize> {
+    use sky_orm::entity::relation::tree::LoadTree;
+
+    let children = category.load_children(connection).await?;
+    let ancestors = category.load_ancestors(connection).await?;
+
+    Ok(children.len() + ancestors.len())
+}
+
+/// Only checks that relations can be loaded through nullable foreign keys, where messages without
+/// a moderator have no related account.
+#[allow(dead_code)]
+async fn load_message_moderators(
+    connection: &mut sky_orm::sqlx::PgConnection,
+    messages: Vec<my_message_entity::Model>,
+) -> sky_orm::Result<Vec<(my_message_entity::Model, Option<my_account_entity::Model>)>> {
+    use sky_orm::entity::relation::LoadRelation;
+
+    LoadRelation::<
+        my_account_entity::Model,
+        my_message_entity::Moderator,
+        my_message_entity::Entity,
+        _,
+    >::load_relation(messages, connection)
+    .await
+}
+
+/// Only checks that the relations generated by `model!` can be loaded in both directions, with
+/// every author having many articles, and every article one author.
+#[allow(dead_code)]
+async fn load_article_authors(
+    connection: &mut sky_orm::sqlx::PgConnection,
+    authors: &[my_author_entity::Model],
+    articles: &[my_article_entity::Model],
+) -> sky_orm::Result<(
+    Vec<Vec<my_article_entity::Model>>,
+    Vec<Option<my_author_entity::Model>>,
+)> {
+    use sky_orm::entity::relation::{LoadInverse, LoadRelation};
+
+    let by_author = LoadInverse::<
+        my_author_entity::Model,
+        my_article_entity::columns::AuthorId,
+        my_article_entity::Entity,
+        _,
+    >::load_inverse(authors, &mut *connection)
+    .await?;
+
+    let authors = LoadRelation::<
+        my_author_entity::Model,
+        my_article_entity::columns::AuthorId,
+        my_article_entity::Entity,
+        _,
+    >::load_relation(articles, connection)
+    .await?;
+
+    Ok((by_author, authors))
+}
+
+/// Only checks that cached queries can be run.
+#[allow(dead_code)]
+async fn find_category_cached(
+    connection: &mut sky_orm::sqlx::PgConnection,
+    cache: &sky_orm::query::cache::QueryCache<&'static str>,
+    name: &str,
+) -> sky_orm::Result<my_category_entity::Model> {
+    my_category_entity::Entity::find()
+        .filter(my_category_entity::columns::Name::eq(name.to_string()))
+        .cached(cache, "category_by_name")
+        .one(connection)
+        .await
+}
+
+/// Only checks that queries can be routed between a primary and a replica, and be given a timeout.
+#[allow(dead_code)]
+async fn rename_category_routed(
+    router: &sky_orm::router::DatabaseRouter<Postgres>,
+    id: i64,
+    name: &str,
+) -> sky_orm::Result<my_category_entity::Model> {
+    my_category_entity::Entity::update_many()
+        .set(my_category_entity::columns::Name::value(name.to_string()))
+        .filter(my_category_entity::columns::Id::eq(id))
+        .timeout(std::time::Duration::from_secs(5))
+        .exec_routed(router)
+        .await?;
+
+    my_category_entity::Entity::find_by_id(id)
+        .on_primary()
+        .one_routed(router)
+        .await
+}
+
+mod my_other_entity {
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel, Default, Debug, Clone)]
+    #[sky_orm(primary_key = id, table = "other_entity", derive(PartialEq), hooks, audit)]
     pub struct Model {
         pub id: String,
         pub amount_killed: i32,
         pub other_amount_killed: i32,
     }
+
+    impl sky_orm::entity::model::ActiveModelHooks for ActiveModel {
+        fn before_insert(&mut self) -> Result<(), sky_orm::sqlx::Error> {
+            if let Some(id) = self.id() {
+                let id = id.trim().to_lowercase();
+                self.set_id(id);
+            }
+            Ok(())
+        }
+    }
+}
+
+mod my_query_only_entity {
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, table = "query_only", database = sky_orm::sqlx::Any)]
+    pub struct Model {
+        pub id: i64,
+        pub name: String,
+    }
+}
+
+mod my_embedding_entity {
+    use sky_orm_macros::DatabaseModel;
+
+    #[derive(DatabaseModel)]
+    #[sky_orm(primary_key = id, table = "document")]
+    pub struct Model {
+        pub id: i64,
+        pub title: String,
+        pub embedding: sky_orm::pgvector::Vector,
+    }
+}
+
+#[allow(dead_code)]
+mod my_row_types {
+    use sky_orm::FromSqlxRow;
+
+    /// A row of an ad-hoc query, usable with any database.
+    #[derive(FromSqlxRow)]
+    pub struct StrategySummary {
+        #[sky_orm(column = "strategy_name")]
+        pub name: String,
+        pub open_trades: i64,
+    }
+
+    /// A trade joined with its strategy.
+    #[derive(FromSqlxRow)]
+    pub struct TradeWithStrategy {
+        #[sky_orm(flatten)]
+        pub trade: super::my_parsed_entity::Model,
+        #[sky_orm(flatten)]
+        pub strategy: super::my_parsed_strategy::Strategy,
+    }
+
+    /// Only the columns of a post needed to list it.
+    #[derive(FromSqlxRow)]
+    #[sky_orm(entity = super::my_post_entity::Entity)]
+    pub struct PostListItem {
+        pub id: i64,
+        pub title: String,
+    }
+
+    /// A row of an ad-hoc query, only usable with postgres.
+    #[derive(FromSqlxRow)]
+    #[sky_orm(database = sky_orm::sqlx::Postgres)]
+    pub struct NearestDocument {
+        pub id: i64,
+        pub distance: f64,
+    }
+
+    /// An entity aggregated into the row of the entity it belongs to.
+    #[derive(serde::Deserialize)]
+    pub struct EntityChild {
+        pub id: String,
+        pub name: Option<String>,
+    }
 }
 
 fn main() {
@@ -67,7 +730,539 @@ fn main() {
 
     let pq = my_parsed_entity::Entity::find();
 
+    let rq = my_parsed_strategy::StrategyEntity::find().where_relation(
+        my_parsed_strategy_config::columns::Settings::eq(String::new()),
+    );
+
+    let vq = my_parsed_view::Entity::find();
+
+    let renq = my_profile_entity::Entity::find()
+        .filter(my_profile_entity::columns::IsPublic::eq(true))
+        .order_by(
+            my_profile_entity::columns::DisplayName::column(),
+            Order::Asc,
+        );
+    let prenq = my_parsed_profile::Entity::find().filter(
+        my_parsed_profile::columns::DisplayName::eq("someone".to_string()),
+    );
+
+    let verq = my_verified_entity::Entity::find()
+        .filter(my_verified_entity::columns::Error::eq(None))
+        .filter(my_verified_entity::columns::Name::eq("August".to_string()))
+        .limit(1);
+
+    let mut other_active = my_other_entity::Model::default().into_active();
+    other_active.set_amount_killed(5);
+    other_active.clear_other_amount_killed();
+
+    let mut other_default = my_other_entity::ActiveModel::default();
+    other_default.set_amount_killed(other_active.amount_killed().copied().unwrap_or_default());
+
+    let mut hooked = my_other_entity::ActiveModel::default();
+    hooked.set_id(" Hooked ".to_string());
+    let hooked_result = sky_orm::entity::model::ActiveModelHooks::before_insert(&mut hooked)
+        .map(|()| hooked.id().cloned());
+
+    let iq = my_other_entity::Entity::insert()
+        .row(&other_active.clone())
+        .row(&other_active);
+
+    let sq = my_query_only_entity::Entity::find()
+        .filter(my_query_only_entity::columns::Id::gt(5))
+        .filter(my_query_only_entity::columns::Name::eq(
+            "August".to_string(),
+        ));
+
+    let eq = my_other_entity::Entity::find()
+        .filter(my_other_entity::columns::Id::similar_to(
+            "August".to_string(),
+        ))
+        .filter(my_other_entity::columns::Id::distance_lt(
+            "Gustav".to_string(),
+            0.5,
+        ));
+
+    let nq = my_embedding_entity::Entity::find()
+        .filter(my_embedding_entity::columns::Id::gt(5))
+        .order_by(
+            my_embedding_entity::columns::Embedding::cosine_distance(Vector::from(vec![
+                1.0, 0.0, 0.5,
+            ])),
+            Order::Asc,
+        )
+        .order_by(my_embedding_entity::columns::Title::column(), Order::Desc)
+        .limit(5);
+
+    let enq = my_enum_entity::Entity::find()
+        .filter(my_enum_entity::columns::Status::not_eq(
+            my_enum_entity::Status::Closed,
+        ))
+        .filter(my_enum_entity::columns::Priority::geq(
+            my_enum_entity::Priority::High,
+        ))
+        .filter(my_enum_entity::columns::Mood::eq(Some(
+            my_enum_entity::Mood::Grumpy,
+        )))
+        .filter(my_enum_entity::columns::Settings::eq(
+            sky_orm::sqlx::types::Json(my_enum_entity::TicketSettings {
+                notify: true,
+                labels: vec![],
+            }),
+        ))
+        .filter(
+            my_enum_entity::columns::Tags::contains(vec!["urgent".to_string()]).or(
+                my_enum_entity::columns::Tags::overlaps(vec![
+                    "bug".to_string(),
+                    "regression".to_string(),
+                ]),
+            ),
+        )
+        .filter(my_enum_entity::columns::Settings::get_text_eq(
+            "notify", "true",
+        ))
+        .filter(my_enum_entity::columns::Settings::has_key("labels").and(
+            my_enum_entity::columns::Settings::path_exists("$.labels[*] ? (@ == \"bug\")"),
+        ));
+
+    let lq = my_ledger_entity::Entity::find()
+        .filter(my_ledger_entity::columns::Amount::between(
+            Decimal::new(10_000, 2),
+            Decimal::new(250_050, 2),
+        ))
+        .filter(my_ledger_entity::columns::Checksum::eq(vec![
+            0xde, 0xad, 0xbe, 0xef,
+        ]))
+        .order_by(my_ledger_entity::columns::Amount::column(), Order::Desc);
+
+    let account_id = Uuid::from_u128(0x936d_a01f_9abd_4d9d_80c7_02af_85c8_22a8);
+
+    let aq = my_account_entity::Entity::find_by_id(account_id)
+        .where_relation(my_transfer_entity::columns::Amount::gt(1_000));
+
+    // Accounts with more than 5 transfers, one of which is over 1000.
+    let agq = my_account_entity::Entity::find()
+        .where_aggregate(Aggregate::<my_transfer_entity::Entity>::count().gt(5))
+        .where_aggregate(my_transfer_entity::columns::Amount::max().gt(1_000));
+
+    let miq = my_account_entity::Entity::find()
+        .where_missing_relation::<my_transfer_entity::Entity, _>()
+        .where_missing_relation_via::<my_message_entity::Entity, _>(my_message_entity::Sender);
+
+    let asq = my_account_entity::Entity::find()
+        .limit(10)
+        .append_sql(|builder| {
+            builder.push(" OFFSET ");
+            builder.push_bind(20_i64);
+            builder.push(" FOR UPDATE SKIP LOCKED");
+        });
+
+    // Hints for other backends are not rendered.
+    let qhq = my_account_entity::Entity::find()
+        .hint(QueryHint::Disable(PlannerMethod::SeqScan))
+        .hint(QueryHint::Index(IndexHint::Force, "my_account_idx"))
+        .hint(QueryHint::Optimizer("MAX_EXECUTION_TIME(1000)"));
+
+    let tiq = my_transfer_entity::Entity::insert().row(
+        &my_transfer_entity::Model {
+            account_id,
+            ..Default::default()
+        }
+        .into_active(),
+    );
+
+    let hq = my_host_entity::Entity::find()
+        .filter(my_host_entity::columns::Address::contains_or_eq(
+            IpNetwork::from(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))),
+        ))
+        .filter(my_host_entity::columns::Mac::not_eq(MacAddress::new(
+            [0; 6],
+        )))
+        .filter(my_host_entity::columns::Kind::eq(
+            my_host_entity::HostKind::Server,
+        ))
+        .filter(my_host_entity::columns::Hostname::eq("DB-1".to_string()));
+
+    let jq = my_job_entity::Entity::find()
+        .filter(my_job_entity::columns::RunEvery::between(
+            Interval::new(0, 1, 0),
+            Interval::new(1, 0, 0),
+        ))
+        .filter(my_job_entity::columns::Timeout::lt(Interval::new(
+            0, 0, 30_000_000,
+        )))
+        .order_by(my_job_entity::columns::RunEvery::column(), Order::Asc);
+
+    let tkq = my_ticket_entity::Entity::find()
+        .filter(my_ticket_entity::columns::Status::not_eq(
+            my_ticket_entity::TicketStatus::Closed,
+        ))
+        .filter(my_ticket_entity::columns::Priority::eq(
+            my_ticket_entity::Priority::High,
+        ));
+
+    let auq = my_author_entity::Entity::find().where_relation_via(
+        my_article_entity::columns::AuthorId,
+        my_article_entity::columns::Id::gt(100),
+    );
+
+    let arq = my_article_entity::Entity::find().where_inverse_relation(
+        my_author_entity::columns::Email::eq("ann@example.com".to_string()),
+    );
+
+    let blog_authors = my_blog::author::Author::find().where_relation_via(
+        my_blog::article::columns::AuthorId,
+        my_blog::article::columns::Id::gt(100),
+    );
+
+    let blog_articles = my_blog::article::Entity::find().where_inverse_relation(
+        my_blog::author::columns::Email::eq("ann@example.com".to_string()),
+    );
+
+    // `POINT(13.4 52.5)` as WKB.
+    let here = Wkb([
+        &[1, 1, 0, 0, 0][..],
+        &13.4_f64.to_le_bytes(),
+        &52.5_f64.to_le_bytes(),
+    ]
+    .concat());
+    let geoq = my_place_entity::Entity::find()
+        .filter(my_place_entity::columns::Location::within_distance(
+            here.clone(),
+            500.0,
+        ))
+        .order_by(
+            my_place_entity::columns::Location::knn_distance(here),
+            Order::Asc,
+        )
+        .limit(10);
+
+    let api_transfer = my_transfer_entity::ApiTransfer::from(my_transfer_entity::Model {
+        account_id,
+        amount: 42,
+        ..Default::default()
+    });
+
+    // Only checks that the row types can be parsed from the expected databases.
+    let _ = <my_row_types::StrategySummary as ParseFromRow<Postgres>>::parse_from_row;
+    let _ = <my_row_types::StrategySummary as ParseFromRow<sky_orm::sqlx::Any>>::parse_from_row;
+    let _ = <my_row_types::NearestDocument as ParseFromRow<Postgres>>::parse_from_row;
+    let _ = <my_row_types::TradeWithStrategy as ParseFromRow<Postgres>>::parse_from_row;
+
+    // Joins the account table twice, once for each side of the message.
+    let alq = my_message_entity::Entity::find()
+        .where_inverse_relation_as::<SenderAccount, my_message_entity::Sender, _, _>(Aliased::<
+            my_account_entity::columns::Owner,
+            SenderAccount,
+        >::eq(
+            "alice".to_string(),
+        ))
+        .where_inverse_relation_as::<RecipientAccount, my_message_entity::Recipient, _, _>(
+            Aliased::<my_account_entity::columns::Owner, RecipientAccount>::eq("bob".to_string()),
+        );
+
+    let mq = my_account_entity::Entity::find().where_relation_via(
+        my_message_entity::Recipient,
+        my_message_entity::columns::Body::eq("hello".to_string()),
+    );
+
+    let nmq = my_message_entity::Entity::find()
+        .where_inverse_relation_via(
+            my_message_entity::Moderator,
+            my_account_entity::columns::Owner::eq("carol".to_string()),
+        )
+        .query();
+
+    // `name` is nullable, so an explicit `null` sets it, while the missing `other_entity_id` stays
+    // untouched.
+    let patched = my_entity::ActiveModel::from_json(sky_orm::serde_json::json!({
+        "id": "abc",
+        "name": null,
+    }));
+
+    let uq = my_other_entity::Entity::update_many()
+        .set(my_other_entity::columns::AmountKilled::value(0))
+        .set(my_other_entity::columns::OtherAmountKilled::increment(1))
+        .filter(my_other_entity::columns::OtherAmountKilled::gt(5));
+
+    let min_killed: Option<i32> = Some(2);
+    let name_filter: Option<String> = None;
+    let cq = my_other_entity::Entity::find()
+        .apply_if(min_killed, |q, e| {
+            q.filter(my_other_entity::columns::AmountKilled::gt(e))
+        })
+        .filter_opt(name_filter.map(my_other_entity::columns::Id::eq))
+        .apply_if(Some(10), Select::limit);
+
+    let killed = [1, 2, 3]
+        .into_iter()
+        .fold(Cond::any(), |acc, e| {
+            acc.add_or(my_other_entity::columns::AmountKilled::eq(e))
+        })
+        .add_and(my_other_entity::columns::OtherAmountKilled::lt(10));
+    let dq = my_other_entity::Entity::find().filter(killed.into_expr());
+
+    let inq = my_other_entity::Entity::find()
+        .filter(my_other_entity::columns::AmountKilled::is_in_chunks(
+            &[1, 2, 3, 4, 5],
+            2,
+        ))
+        .filter(my_other_entity::columns::OtherAmountKilled::is_not_in(&[]))
+        .filter(my_other_entity::columns::Id::eq_any(vec![
+            "a".to_string(),
+            "b".to_string(),
+        ]))
+        .filter(my_other_entity::columns::Id::is_not_in_values(
+            ["c", "d"].map(ToString::to_string),
+        ));
+
+    let tq = my_other_entity::Entity::find().filter(<(
+        my_other_entity::columns::Id,
+        my_other_entity::columns::AmountKilled,
+    ) as ColumnTuple>::is_in(&[
+        ("a".to_string(), 1),
+        ("b".to_string(), 2),
+    ]));
+
+    let fragment = format!("\"other_entity\".\"amount_killed\" > {}", 3);
+    let bq = my_other_entity::Entity::find().filter(Fragment(&fragment).into());
+
+    let scq = my_event_entity::Entity::find_by_id(1);
+    let event_insert =
+        my_event_entity::Entity::insert_values([my_event_entity::columns::Name::value(
+            "signup".to_string(),
+        )]);
+
+    my_invoice_entity::CURRENT_TENANT.store(7, std::sync::atomic::Ordering::Relaxed);
+    let tnq = my_invoice_entity::Entity::find_by_id(1);
+    let tenant_update = my_invoice_entity::Entity::update_many()
+        .set(my_invoice_entity::columns::Total::value(0))
+        .filter(my_invoice_entity::columns::Total::lt(0));
+    let all_tenants_delete = my_invoice_entity::Entity::delete_many().unscoped();
+
+    let dsq = my_post_entity::Entity::find()
+        .filter(my_post_entity::columns::Title::eq("hello".to_string()));
+    let drafts = my_post_entity::Entity::find_unscoped()
+        .filter(my_post_entity::columns::Published::eq(false));
+    let prq = my_post_entity::Entity::find()
+        .limit(10)
+        .into_model::<my_row_types::PostListItem>();
+    let with_children = my_other_entity::Entity::find()
+        .filter(my_other_entity::columns::AmountKilled::gt(5))
+        .with_children::<my_entity::columns::OtherEntityId, my_row_types::EntityChild>();
+    let dynamic_sort = "title"
+        .parse::<my_post_entity::columns::Col>()
+        .map(|e| my_post_entity::Entity::find().order_by(e.column(), Order::Desc));
+    let by_name = my_post_entity::Entity::find_unscoped()
+        .filter_by_name("published", BinaryExprOperand::Equals, true)
+        .and_then(|e| Ok(e.order_by_name("id", Order::Asc)?));
+    let mistyped =
+        my_post_entity::Entity::find().filter_by_name("title", BinaryExprOperand::Equals, 5_i64);
+    let by_str =
+        my_post_entity::Entity::find().filter_by_name("title", BinaryExprOperand::Equals, "Hello");
+    let by_nullable = my_profile_entity::Entity::find().filter_by_name(
+        "URL",
+        BinaryExprOperand::Equals,
+        "https://example.com".to_string(),
+    );
+    let post_ids = my_post_entity::Entity::find().pluck::<my_post_entity::columns::Id>();
+    let post_titles = my_post_entity::Entity::find()
+        .pluck2::<(my_post_entity::columns::Id, my_post_entity::columns::Title)>();
+
+    let ivq = my_other_entity::Entity::insert_values([
+        my_other_entity::columns::Id::value("abc".to_string()),
+        my_other_entity::columns::AmountKilled::value(3),
+    ]);
+
+    // Rows without any value are inserted by a statement each.
+    let default_other = my_other_entity::ActiveModel::default();
+    let dfq = my_other_entity::Entity::insert().rows([&default_other, &default_other]);
+
+    // The entity is added first, but refers to the other entity, so that one is written first.
+    let mut session = Session::<Postgres>::new();
+    if let Ok(entity) = my_entity::ActiveModel::from_json(sky_orm::serde_json::json!({
+        "id": "abc",
+        "other_entity_id": "def",
+    })) {
+        session.add(entity);
+    }
+    let other = session.add(my_other_entity::ActiveModel::default());
+    session.get_mut(other).set_id("def".to_string());
+    session.get_mut(other).set_amount_killed(1);
+    let loaded = session.track(my_other_entity::Model {
+        id: "ghi".to_string(),
+        amount_killed: 2,
+        other_amount_killed: 3,
+    });
+    session.get_mut(loaded).set_amount_killed(4);
+    let removed = session.track(my_other_entity::Model::default());
+    session.delete(removed);
+
+    // `model!` declares the relation of articles to their authors, so the author is written first.
+    let mut blog_session = Session::<Postgres>::new();
+    blog_session.add(my_article_entity::ActiveModel::default());
+    blog_session.add(my_author_entity::ActiveModel::default());
+
+    // The first two models change the same column, so they are updated by the same statement.
+    let batch = (1..=3)
+        .map(|i| {
+            let mut active = my_other_entity::Model {
+                id: format!("batch-{i}"),
+                amount_killed: 0,
+                other_amount_killed: 0,
+            }
+            .into_active();
+            active.set_amount_killed(i);
+            if i == 3 {
+                active.set_other_amount_killed(i);
+            }
+            active
+        })
+        .collect::<Vec<_>>();
+    let umf = my_other_entity::Entity::update_many_from(&batch);
+
+    let tmq = my_transfer_entity::Entity::find()
+        .where_related_through::<my_account_entity::Entity, _, Inverse<my_message_entity::Recipient>, _, _>(
+            my_message_entity::columns::Body::eq("hello".to_string()),
+        );
+    // `account` is listed already, so the joined chain falls back to conditions.
+    let listed_tmq = my_transfer_entity::Entity::find()
+        .where_inverse_relation::<my_transfer_entity::columns::AccountId, _, _>(
+            my_account_entity::columns::Id::is_not_in(&[]),
+        )
+        .where_related_through::<my_account_entity::Entity, _, Inverse<my_message_entity::Recipient>, _, _>(
+            my_message_entity::columns::Body::eq("hello".to_string()),
+        );
+
     println!("Q: {}", q.query());
     println!("OQ: {}", oq.query());
     println!("PQ: {}", pq.query());
+    println!("RQ: {}", rq.query());
+    println!("VQ: {}", vq.query());
+    println!("VERQ: {}", verq.query());
+    println!("RENQ: {}", renq.query());
+    println!("PRENQ: {}", prenq.query());
+    println!("IQ: {}", iq.query());
+    println!("IQA: {other_active:?}");
+    println!("IQD: {other_default:?} {}", other_default == other_active);
+    println!("SQ: {:?}", sq.statement());
+    println!("EQ: {}", eq.query());
+    println!("NQ: {}", nq.query());
+    println!("ENQ: {}", enq.query());
+    println!("LQ: {}", lq.query());
+    println!("AQ: {}", aq.query());
+    println!("AGQ: {}", agq.query());
+    println!("MIQ: {}", miq.query());
+    println!("ASQ: {}", asq.query());
+    println!("QHQ: {}", qhq.query());
+    println!("UMF: {}", umf.query());
+    println!("TIQ: {}", tiq.query());
+    println!(
+        "DTO: {} {} {}",
+        api_transfer.id, api_transfer.account_id, api_transfer.amount
+    );
+    println!("HQ: {}", hq.query());
+    println!("JQ: {}", jq.query());
+    println!("GEOQ: {}", geoq.query());
+    println!("TKQ: {}", tkq.query());
+    println!("AUQ: {}", auq.query());
+    println!("ARQ: {}", arq.query());
+    println!("BLAUQ: {}", blog_authors.query());
+    println!("BLARQ: {}", blog_articles.query());
+    println!("MQ: {}", mq.query());
+    println!("NMQ: {nmq}");
+    println!("ALQ: {}", alq.query());
+    println!("TMQ: {}", tmq.query());
+    println!("LTMQ: {}", listed_tmq.query());
+    println!("PATCH: {patched:?}");
+    println!("UQ: {}", uq.query());
+    println!("CQ: {}", cq.query());
+    println!("DQ: {}", dq.query());
+    println!("INQ: {}", inq.query());
+    println!("TQ: {}", tq.query());
+    println!("BQ: {}", bq.query());
+    println!("SCQ: {}", scq.query());
+    println!("SCIQ: {}", event_insert.query());
+    println!("TNQ: {}", tnq.query());
+    println!("TNUQ: {}", tenant_update.query());
+    println!("TNAQ: {}", all_tenants_delete.query());
+    println!("DSQ: {}", dsq.query());
+    println!("DSUQ: {}", drafts.query());
+    println!("PRQ: {}", prq.query());
+    println!("WCQ: {}", with_children.query());
+    println!("DYQ: {:?}", dynamic_sort.map(|e| e.query()));
+    println!(
+        "COLS: {:?} {:?}",
+        my_post_entity::columns::Col::iter()
+            .map(my_post_entity::columns::Col::as_str)
+            .collect::<Vec<_>>(),
+        "body"
+            .parse::<my_post_entity::columns::Col>()
+            .map_err(|e| e.to_string())
+    );
+    println!("BNQ: {:?}", by_name.map(|e| e.query()));
+    println!("BNSQ: {:?}", by_str.map(|e| e.query()));
+    println!("BNNQ: {:?}", by_nullable.map(|e| e.query()));
+    println!(
+        "BNE: {:?}",
+        mistyped.map(|e| e.query()).map_err(|e| e.to_string())
+    );
+    println!("META: {:?}", my_entity::Entity::meta());
+    println!(
+        "RELATIONS: {:?}",
+        my_account_entity::Entity::meta().relations
+    );
+    println!(
+        "API: {:?}",
+        sky_orm::serde_json::to_string(&my_user_entity::UserResponse::from(
+            my_user_entity::Model {
+                id: 1,
+                email: "someone@example.com".to_string(),
+                password_hash: "$argon2id$...".to_string(),
+            }
+        ))
+    );
+    let user = my_user_entity::Model {
+        id: 1,
+        email: "someone@example.com".to_string(),
+        password_hash: "$argon2id$...".to_string(),
+    };
+    println!("SENSITIVE: {user:?}");
+    println!("SENSITIVE ACTIVE: {:?}", user.into_active());
+    let account = my_account_entity::ModelFactory::new().build();
+    println!("FACTORY: {account:?}");
+    println!(
+        "FACTORY RELATED: {:?}",
+        my_message_entity::ModelFactory::new()
+            .for_sender(&account)
+            .for_recipient(&account)
+            .body("hello".to_string())
+            .build_many(2)
+    );
+    println!("PLQ: {}", post_ids.query());
+    println!("PLTQ: {}", post_titles.query());
+    println!("SESSION: {:?}", session.queries());
+    println!("BLOG SESSION: {:?}", blog_session.queries());
+    println!(
+        "CRQ: {}",
+        my_author_entity::Entity::find()
+            .where_composite_relation::<(my_article_entity::columns::ReviewerEmail,), _, _>(
+                my_article_entity::columns::Id::eq(1)
+            )
+            .query()
+    );
+    println!(
+        "ICRQ: {}",
+        my_article_entity::Entity::find()
+            .where_inverse_composite_relation::<(my_article_entity::columns::ReviewerEmail,), _, _>(
+                my_author_entity::columns::Email::eq("someone@example.com".to_string())
+            )
+            .query()
+    );
+    println!("IVQ: {}", ivq.query());
+    println!("DFQ: {}", dfq.query());
+    println!("HOOKS: {hooked_result:?}");
+    println!(
+        "AUDIT: {:?}",
+        sky_orm::entity::model::AuditedModel::audit_values(&other_active)
+    );
 }
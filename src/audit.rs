@@ -0,0 +1,161 @@
+//! Recording the writes of a [`Session`](crate::session::Session) in an audit log, e.g. for
+//! compliance.
+//!
+//! Only entities with `#[sky_orm(audit)]` are recorded (see
+//! [`AuditedModel`](crate::entity::model::AuditedModel)). For every insert, update and delete
+//! written when [flushing](crate::session::Session::flush_audited) a session, an [`AuditRecord`]
+//! with the old and new values of the written columns is passed to an [`AuditSink`], e.g. an
+//! [`AuditTable`] or a closure.
+//!
+//! Statements built by hand (e.g. [`Entity::update_many`](crate::entity::Entity::update_many))
+//! are not recorded, since they do not know the values of the rows they change.
+
+use serde_json::{Map, Value};
+use sqlx::{Database, Encode, Executor, IntoArguments, QueryBuilder, Type, types::Json};
+
+use crate::query::{max_bind_parameters, quote_ident};
+
+pub use crate::change::ChangeOperation;
+
+/// The old and new value of a single column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditColumn {
+    pub name: &'static str,
+    /// The value before the write, or [`None`] for an insert.
+    pub old: Option<Value>,
+    /// The value after the write, or [`None`] for a delete.
+    pub new: Option<Value>,
+}
+
+/// A single write to a row of an audited entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditRecord {
+    pub table: &'static str,
+    pub operation: ChangeOperation,
+    /// The primary key of the written row. Keys generated by the database on insert are read back
+    /// from the insert, see [`Session::flush_audited`](crate::session::Session::flush_audited).
+    pub primary_key: Value,
+    /// The columns that were written. For an update, these are only the changed columns.
+    pub columns: Vec<AuditColumn>,
+}
+
+impl AuditRecord {
+    /// The written columns as a JSON object, with an object holding the `old` and `new` value for
+    /// every column.
+    #[must_use]
+    pub fn changes(&self) -> Value {
+        Value::Object(
+            self.columns
+                .iter()
+                .map(|e| {
+                    let mut change = Map::new();
+                    change.insert("old".to_string(), e.old.clone().unwrap_or_default());
+                    change.insert("new".to_string(), e.new.clone().unwrap_or_default());
+
+                    (e.name.to_string(), Value::Object(change))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A destination for the [`AuditRecord`]s of database `DB`.
+///
+/// Implemented by [`AuditTable`], and by closures taking the records, e.g. to send them to an
+/// external service.
+pub trait AuditSink<DB>
+where
+    DB: Database,
+{
+    /// Record the writes of a session. This is called once per flush, inside its transaction,
+    /// after all statements have been run but before the transaction is committed.
+    ///
+    /// # Errors
+    ///
+    /// To roll back the transaction, so that none of the unrecorded writes are committed.
+    fn record(
+        &mut self,
+        records: Vec<AuditRecord>,
+        connection: &mut DB::Connection,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send;
+}
+
+impl<DB, F> AuditSink<DB> for F
+where
+    DB: Database,
+    F: FnMut(Vec<AuditRecord>) -> Result<(), sqlx::Error> + Send,
+{
+    fn record(
+        &mut self,
+        records: Vec<AuditRecord>,
+        _connection: &mut DB::Connection,
+    ) -> impl Future<Output = Result<(), sqlx::Error>> + Send {
+        std::future::ready(self(records))
+    }
+}
+
+/// Insert audit records into a table, in the same transaction as the writes they record.
+///
+/// The table needs the columns `table_name` and `operation` of a text type, and the columns
+/// `primary_key` and `changes` of a JSON type (see [`AuditRecord::changes`]). Any other columns,
+/// e.g. an ID or a timestamp, need to have a default value.
+///
+/// The records of a flush are inserted in as few statements as the bind parameter limit of the
+/// database allows, see [`max_bind_parameters`].
+pub struct AuditTable {
+    table: String,
+}
+
+impl Default for AuditTable {
+    fn default() -> Self {
+        Self::new("sky_orm_audit")
+    }
+}
+
+impl AuditTable {
+    /// Insert audit records into the table of the given name.
+    #[must_use]
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+        }
+    }
+}
+
+impl<DB> AuditSink<DB> for AuditTable
+where
+    DB: Database,
+    for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    for<'q> DB::Arguments<'q>: IntoArguments<'q, DB> + 'static,
+    String: for<'a> Encode<'a, DB> + Type<DB>,
+    Json<Value>: for<'a> Encode<'a, DB> + Type<DB>,
+{
+    async fn record(
+        &mut self,
+        records: Vec<AuditRecord>,
+        connection: &mut DB::Connection,
+    ) -> Result<(), sqlx::Error> {
+        let sql = format!(
+            "INSERT INTO {} (\"table_name\", \"operation\", \"primary_key\", \"changes\") ",
+            quote_ident(&self.table)
+        );
+        // Every record binds 4 parameters.
+        let chunk_size = max_bind_parameters::<DB>() / 4;
+
+        for chunk in records.chunks(chunk_size) {
+            let mut builder = QueryBuilder::new(&sql);
+
+            builder.push_values(chunk, |mut builder, e| {
+                builder
+                    .push_bind(e.table.to_string())
+                    .push_bind(e.operation.to_string())
+                    .push_bind(Json(e.primary_key.clone()))
+                    .push_bind(Json(e.changes()));
+            });
+
+            (&mut *connection).execute(builder.build()).await?;
+        }
+
+        Ok(())
+    }
+}
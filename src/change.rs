@@ -0,0 +1,191 @@
+use std::{fmt::Display, marker::PhantomData, str::FromStr};
+
+use serde::de::DeserializeOwned;
+use sqlx::{
+    ColumnIndex, Connection, Database, Decode, Encode, Executor, IntoArguments, QueryBuilder, Row,
+    Type, types::Json,
+};
+
+use crate::{entity::Entity, query::quote_ident};
+
+/// The kind of change recorded for a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl Display for ChangeOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Insert => "INSERT",
+                Self::Update => "UPDATE",
+                Self::Delete => "DELETE",
+            }
+        )
+    }
+}
+
+impl FromStr for ChangeOperation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "INSERT" => Ok(Self::Insert),
+            "UPDATE" => Ok(Self::Update),
+            "DELETE" => Ok(Self::Delete),
+            _ => Err(format!("unknown change operation \"{s}\"")),
+        }
+    }
+}
+
+/// A single change to a row of some entity, as recorded by the triggers generated with
+/// `sky-orm-cli generate-triggers`.
+#[derive(Debug, Clone)]
+pub struct Change<M> {
+    /// The ID of the change, increasing monotonically.
+    pub id: i64,
+    pub operation: ChangeOperation,
+    /// The row after the change, or before it, in case of a [`ChangeOperation::Delete`].
+    pub model: M,
+}
+
+/// A consumer of the changes recorded for entity `E`.
+///
+/// The recorded rows are decoded from JSON, so the model needs to implement
+/// [`Deserialize`](serde::Deserialize), with fields named like their database columns.
+///
+/// Changes are consumed in the order of their IDs, which are assigned when a change is recorded
+/// rather than when its transaction commits. A transaction that commits after one that recorded
+/// later changes can therefore have its changes skipped, once a poll has moved past their IDs.
+/// Consumers that must not miss any change should periodically poll again from an earlier ID
+/// via [`starting_after`](Self::starting_after), and skip the changes they have already seen.
+pub struct ChangeFeed<E>
+where
+    E: Entity,
+{
+    marker: PhantomData<E>,
+    change_table: String,
+    last_id: i64,
+    batch_size: i64,
+}
+
+impl<E> Default for ChangeFeed<E>
+where
+    E: Entity,
+    E::Model: DeserializeOwned,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> ChangeFeed<E>
+where
+    E: Entity,
+    E::Model: DeserializeOwned,
+{
+    /// Create a new change feed, starting at the very first recorded change.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            marker: PhantomData,
+            change_table: "sky_orm_changes".to_string(),
+            last_id: 0,
+            batch_size: 1000,
+        }
+    }
+
+    /// Only yield changes recorded after the change with the given ID, e.g. to resume consuming
+    /// after a restart.
+    #[must_use]
+    pub const fn starting_after(mut self, id: i64) -> Self {
+        self.last_id = id;
+        self
+    }
+
+    /// Set the name of the table changes are recorded into. Defaults to `sky_orm_changes`, needs
+    /// to match the `--change-table` passed to the CLI.
+    #[must_use]
+    pub fn change_table(mut self, change_table: impl Into<String>) -> Self {
+        self.change_table = change_table.into();
+        self
+    }
+
+    /// Set the maximum number of changes returned per [`poll`](Self::poll).
+    #[must_use]
+    pub const fn batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// The ID of the last change returned by this feed.
+    #[must_use]
+    pub const fn last_id(&self) -> i64 {
+        self.last_id
+    }
+
+    /// Fetch the next batch of changes, oldest first. Returns an empty list if there are no new
+    /// changes. Changes of transactions that are still running are not visible yet, and may be
+    /// skipped once they commit, see [`ChangeFeed`].
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or the recorded rows cannot be
+    /// decoded into the model. See [`Error`](crate::Error) for more information.
+    pub async fn poll<'c, C>(
+        &mut self,
+        connection: &'c mut C,
+    ) -> crate::Result<Vec<Change<E::Model>>>
+    where
+        C: Connection<Database = E::Database>,
+        &'c mut C: Executor<'c, Database = E::Database>,
+        for<'q> <E::Database as Database>::Arguments<'q>: IntoArguments<'q, E::Database> + 'c,
+        for<'a> &'a str: ColumnIndex<<E::Database as Database>::Row>,
+        i64: for<'a> Encode<'a, E::Database> + for<'a> Decode<'a, E::Database> + Type<E::Database>,
+        String:
+            for<'a> Encode<'a, E::Database> + for<'a> Decode<'a, E::Database> + Type<E::Database>,
+        Json<E::Model>: for<'a> Decode<'a, E::Database> + Type<E::Database>,
+    {
+        let mut builder = QueryBuilder::new(format!(
+            "SELECT \"id\", \"operation\", \"row_data\" FROM {} WHERE \"table_name\" = ",
+            quote_ident(&self.change_table)
+        ));
+        builder.push_bind(E::TABLE_NAME.to_string());
+        builder.push(" AND \"id\" > ");
+        builder.push_bind(self.last_id);
+        builder.push(" ORDER BY \"id\" LIMIT ");
+        builder.push_bind(self.batch_size);
+
+        let rows = connection.fetch_all(builder.build()).await?;
+
+        let changes = rows
+            .iter()
+            .map(|row| {
+                let operation: String = row.try_get("operation")?;
+                let Json(model) = row.try_get("row_data")?;
+
+                Ok(Change {
+                    id: row.try_get("id")?,
+                    operation: operation.parse().map_err(|e: String| {
+                        sqlx::Error::ColumnDecode {
+                            index: "operation".to_string(),
+                            source: e.into(),
+                        }
+                    })?,
+                    model,
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()?;
+
+        if let Some(last) = changes.last() {
+            self.last_id = last.id;
+        }
+
+        Ok(changes)
+    }
+}
@@ -1,27 +1,211 @@
 pub mod column;
+pub mod meta;
 pub mod model;
 pub mod relation;
 
-use column::ComparableColumn;
-use model::Model;
+use column::{Column, ColumnEnum, ComparableColumn, EntityConditionExpr};
+use meta::EntityMeta;
+use model::{ActiveModel, Model};
 use sqlx::Database;
 
-use crate::query::{parse::ParseFromRow, select::Select};
+#[cfg(feature = "exec")]
+use crate::query::find_or_create::FindOrCreate;
+use crate::query::{
+    PushToQuery,
+    delete::Delete,
+    insert::Insert,
+    parse::ParseFromRow,
+    select::Select,
+    update::{Update, UpdateFrom},
+};
 
 pub trait Entity: Send + Sync + Sized {
-    type PrimaryKeyColumn: ComparableColumn<Entity = Self>;
-
-    type Model: Model + ParseFromRow<Self::Database>;
+    type Model: Model<Entity = Self> + ParseFromRow<Self::Database>;
 
     type Database: Database + Sync;
 
+    /// The enum of this entity's columns, i.e. `columns::Col`.
+    ///
+    /// This has no default, since defaults of associated types are not stable yet, so entities
+    /// implemented by hand instead of with the derive macro have to declare an enum of their own.
+    type Col: ColumnEnum<Entity = Self>;
+
     /// The name of this entity's table in the database.
     const TABLE_NAME: &'static str;
 
+    /// The schema (or namespace) of this entity's table, set with `#[sky_orm(schema = "..")]`, or
+    /// [`None`] if it's in the default one.
+    const SCHEMA: Option<&'static str> = None;
+
+    /// How statements refer to this entity's table, i.e. its quoted name qualified by its
+    /// [`SCHEMA`](Self::SCHEMA), e.g. `"analytics"."events"`, or `"events"` in the default one.
+    /// Defaults to the unquoted [`TABLE_NAME`](Self::TABLE_NAME), the derive macro always quotes
+    /// it.
+    const QUALIFIED_TABLE_NAME: &'static str = Self::TABLE_NAME;
+
     const COLUMN_NAMES: &[&'static str];
 
+    /// The start of every `SELECT` statement of this entity, i.e. the qualified
+    /// [`COLUMN_NAMES`](Self::COLUMN_NAMES) and the `FROM` clause. Generated by the derive macro,
+    /// so that queries don't have to build it again every time.
+    const BASE_SELECT: &'static str;
+
+    /// The tables referred to by this entity's foreign keys, which need to be written before it.
+    ///
+    /// Generated by the derive macro for `belongs_to` fields, the relations of `model!`, and the
+    /// entities given with `#[sky_orm(references = ..)]`. Models with hand-written
+    /// [`Related`](relation::Related) impls must declare their targets with the latter, or
+    /// sessions may write them in the wrong order.
+    const REFERENCED_TABLES: &[&'static str] = &[];
+
+    /// A condition added to every `SELECT`, `UPDATE` and `DELETE` statement of this entity, unless
+    /// it is skipped with `unscoped()`, e.g. [`Select::unscoped`]. Generated by the derive macro
+    /// for [`TenantScoped`] entities and entities with an [`EntityScope`].
+    #[must_use]
+    fn implicit_filter() -> Option<Box<dyn PushToQuery<Self::Database>>> {
+        None
+    }
+
+    /// A description of this entity's table, columns and relations, generated by the derive macro.
+    ///
+    /// This has no default, since the SQL types of the columns and the primary key are not known
+    /// to the trait, so entities implemented by hand instead of with the derive macro have to
+    /// describe themselves.
+    #[must_use]
+    fn meta() -> EntityMeta;
+
     #[must_use]
-    fn find() -> Select<Self> {
+    fn find<'a>() -> Select<'a, Self>
+    where
+        Self: 'a,
+    {
         Select::new()
     }
+
+    /// Like [`find`](Self::find), but without the [implicit filter](Self::implicit_filter), e.g. to
+    /// also find the rows hidden by a [default scope](EntityScope).
+    #[must_use]
+    fn find_unscoped<'a>() -> Select<'a, Self>
+    where
+        Self: 'a,
+    {
+        Select::new().unscoped()
+    }
+
+    /// Start building an `INSERT` statement for this entity's table.
+    #[must_use]
+    fn insert() -> Insert<Self> {
+        Insert::new()
+    }
+
+    /// Start building an `INSERT` statement for a single row, given as column values.
+    #[must_use]
+    fn insert_values<I>(values: I) -> Insert<Self>
+    where
+        I: IntoIterator<Item = column::ColumnAssignment<Self>>,
+    {
+        Insert::new().values(values)
+    }
+
+    /// Start building a lookup of the entry matching `condition`, inserting the active model
+    /// returned by `create` if there is none. See [`FindOrCreate`].
+    #[cfg(feature = "exec")]
+    #[must_use]
+    fn find_or_create<'a, Q, F, A>(
+        condition: EntityConditionExpr<Q, Self>,
+        create: F,
+    ) -> FindOrCreate<'a, Self, F>
+    where
+        Self: 'a,
+        Q: PushToQuery<Self::Database> + 'a,
+        F: FnOnce() -> A,
+        A: ActiveModel<Model = Self::Model>,
+    {
+        FindOrCreate::new(condition, create)
+    }
+
+    /// Start building an `UPDATE` statement for all rows of this entity's table that match its
+    /// conditions.
+    #[must_use]
+    fn update_many() -> Update<Self> {
+        Update::new()
+    }
+
+    /// Start building a `DELETE` statement for all rows of this entity's table that match its
+    /// conditions.
+    #[must_use]
+    fn delete_many() -> Delete<Self> {
+        Delete::new()
+    }
+}
+
+/// An entity that has a primary key. Every entity backed by a table should have one, which is
+/// required for relations. Entities without a primary key (e.g. views) can only be queried.
+pub trait HasPrimaryKey: Entity {
+    type PrimaryKeyColumn: ComparableColumn<Entity = Self>;
+
+    /// Start building a `SELECT` statement for the entry with the given primary key.
+    #[must_use]
+    fn find_by_id<'a>(id: <Self::PrimaryKeyColumn as Column>::Type) -> Select<'a, Self>
+    where
+        Self: 'a,
+    {
+        Self::find().filter(Self::PrimaryKeyColumn::eq(id))
+    }
+
+    /// Start building `UPDATE` statements writing the changed columns of each of `active_models`
+    /// to its own row, e.g. after changing a batch of loaded models. See [`UpdateFrom`].
+    #[must_use]
+    fn update_many_from<'a, A, I>(active_models: I) -> UpdateFrom<Self>
+    where
+        A: ActiveModel<Model = Self::Model> + 'a,
+        I: IntoIterator<Item = &'a A>,
+    {
+        UpdateFrom::new(active_models)
+    }
+}
+
+/// An entity whose rows belong to different tenants, identified by
+/// [`TenantColumn`](Self::TenantColumn).
+///
+/// Marking the model with `#[sky_orm(tenant_scoped)]` restricts every `SELECT`, `UPDATE` and
+/// `DELETE` statement of the entity to the rows of [`current_tenant`](Self::current_tenant), see
+/// [`Entity::implicit_filter`]. The trait itself is implemented by hand, e.g. with the tenant of
+/// the current request:
+///
+/// ```ignore
+/// impl TenantScoped for Entity {
+///     type TenantColumn = columns::TenantId;
+///
+///     fn current_tenant() -> i64 {
+///         CURRENT_TENANT.get()
+///     }
+/// }
+/// ```
+///
+/// Inserted rows are not scoped, their tenant has to be set like any other column.
+pub trait TenantScoped: Entity {
+    type TenantColumn: ComparableColumn<Entity = Self>;
+
+    /// The tenant whose rows statements are restricted to.
+    fn current_tenant() -> <Self::TenantColumn as Column>::Type;
+}
+
+/// An entity whose rows are filtered by a condition by default, e.g. to hide unpublished or
+/// soft-deleted rows.
+///
+/// Marking the model with `#[sky_orm(default_scope)]` adds
+/// [`default_scope`](Self::default_scope) to every `SELECT`, `UPDATE` and `DELETE` statement of the
+/// entity, see [`Entity::implicit_filter`]. Use [`Entity::find_unscoped`] to find all rows.
+///
+/// ```ignore
+/// impl EntityScope for Entity {
+///     fn default_scope() -> EntityConditionExpr<impl PushToQuery<Self::Database> + 'static, Self> {
+///         columns::Published::eq(true)
+///     }
+/// }
+/// ```
+pub trait EntityScope: Entity {
+    /// The condition rows are filtered by.
+    fn default_scope() -> EntityConditionExpr<impl PushToQuery<Self::Database> + 'static, Self>;
 }
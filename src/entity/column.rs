@@ -1,20 +1,51 @@
-use std::{fmt::Display, marker::PhantomData};
+#[cfg(feature = "postgres")]
+pub mod array;
+#[cfg(feature = "postgres")]
+pub mod geo;
+#[cfg(feature = "postgres")]
+pub mod interval;
+// `Json` and `JsonValue` need sqlx's `json` feature, which only `exec` enables.
+#[cfg(all(feature = "postgres", feature = "exec"))]
+pub mod json;
+#[cfg(all(feature = "postgres", feature = "ipnetwork"))]
+pub mod network;
+#[cfg(feature = "pgvector")]
+pub mod vector;
+
+use std::{
+    any::TypeId,
+    fmt::Display,
+    marker::PhantomData,
+    ops::{Add, Sub},
+    str::FromStr,
+};
 
 use crate::{
-    entity::Entity,
+    entity::{Entity, model::ColumnValue},
     query::{
-        BinaryExpr, BinaryExprOperand, BracketsExpr, PushToQuery, QueryVariable, SingletonExpr,
+        BinaryExpr, BinaryExprOperand, BracketsExpr, IN_LIST_CHUNK_SIZE, InListExpr, PushToQuery,
+        QueryVariable, RowExpr, SingletonExpr, SingletonExprOperand,
     },
 };
 use sqlx::{ColumnIndex, Database, Decode, Encode, Row, Type};
 
 /// A struct that represents the name of a column on a particular table.
+///
+/// Names without a table are rendered unqualified, i.e. `"column"` instead of
+/// `"table"."column"`, as required by the column lists of `INSERT` and `UPDATE` statements.
 pub struct ColumnName {
     table_or_alias: Option<String>,
     column_name: String,
 }
 
 impl ColumnName {
+    pub(crate) const fn new(column_name: String) -> Self {
+        Self {
+            table_or_alias: None,
+            column_name,
+        }
+    }
+
     pub(crate) const fn new_with_table_or_alias(
         table_or_alias: String,
         column_name: String,
@@ -36,6 +67,13 @@ impl ColumnName {
     pub const fn table_or_alias(&self) -> Option<&String> {
         self.table_or_alias.as_ref()
     }
+
+    /// This name without its table, rendering as `"column"`.
+    #[must_use]
+    pub fn unqualified(mut self) -> Self {
+        self.table_or_alias = None;
+        self
+    }
 }
 
 impl Display for ColumnName {
@@ -54,6 +92,12 @@ where
     fn push_to(&self, builder: &mut sqlx::QueryBuilder<'_, DB>) {
         builder.push(self.to_string());
     }
+
+    fn push_binds(&self, _builder: &mut sqlx::QueryBuilder<'_, DB>) {}
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        tables.extend(self.table_or_alias.as_deref());
+    }
 }
 
 /// A struct that represents a conditional expression (such as `=`, `>`, `IS NULL`) on a given
@@ -125,6 +169,25 @@ where
     }
 }
 
+/// A new value for a column of entity `E`, to be written by an `UPDATE` or `INSERT` statement, e.g.
+/// `columns::Status::value("archived")`.
+pub struct ColumnAssignment<E>
+where
+    E: Entity,
+{
+    column_name: &'static str,
+    value: Box<dyn PushToQuery<E::Database>>,
+}
+
+impl<E> ColumnAssignment<E>
+where
+    E: Entity,
+{
+    pub(crate) fn into_column_value(self) -> ColumnValue<E::Database> {
+        (self.column_name, self.value)
+    }
+}
+
 impl<Q, E> PushToQuery<E::Database> for EntityConditionExpr<Q, E>
 where
     Q: PushToQuery<E::Database>,
@@ -133,6 +196,143 @@ where
     fn push_to(&self, builder: &mut sqlx::QueryBuilder<'_, E::Database>) {
         self.inner.push_to(builder);
     }
+
+    fn push_binds(&self, builder: &mut sqlx::QueryBuilder<'_, E::Database>) {
+        self.inner.push_binds(builder);
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        self.inner.push_tables(tables);
+    }
+}
+
+/// The error of parsing the `columns::Col` enum generated for every entity from a string that is not
+/// the name of one of its columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownColumn {
+    /// The table of the entity.
+    pub entity: &'static str,
+    /// The name that was parsed.
+    pub name: String,
+}
+
+impl Display for UnknownColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown column \"{}\" of {}", self.name, self.entity)
+    }
+}
+
+impl std::error::Error for UnknownColumn {}
+
+/// The error of referring to a column by its name at runtime, e.g. with
+/// [`Select::filter_by_name`](crate::query::select::Select::filter_by_name).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnByNameError {
+    /// The entity has no column of that name.
+    Unknown(UnknownColumn),
+    /// The column exists, but the value compared with it has a different type.
+    MismatchedType {
+        /// The table of the entity.
+        entity: &'static str,
+        /// The name of the column.
+        column: &'static str,
+        /// The rust type of the column.
+        expected: &'static str,
+        /// The rust type of the value.
+        found: &'static str,
+    },
+}
+
+impl Display for ColumnByNameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(e) => e.fmt(f),
+            Self::MismatchedType {
+                entity,
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "column \"{column}\" of {entity} holds values of type `{expected}`, not `{found}`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ColumnByNameError {}
+
+impl From<UnknownColumn> for ColumnByNameError {
+    fn from(value: UnknownColumn) -> Self {
+        Self::Unknown(value)
+    }
+}
+
+/// The columns of an entity as values of an enum, generated as `columns::Col` by the derive macro.
+/// Used to refer to columns by their names at runtime.
+pub trait ColumnEnum:
+    Copy + FromStr<Err = UnknownColumn> + Display + Send + Sync + 'static
+{
+    /// The entity that these columns belong to.
+    type Entity: Entity;
+
+    /// All columns, in the order they are declared in.
+    const ALL: &'static [Self];
+
+    /// The name of this column in the database.
+    fn name(self) -> &'static str;
+
+    /// The fully qualified name of this column.
+    fn full_column_name(self) -> ColumnName;
+
+    /// The [`TypeId`] of the rust type of this column.
+    fn type_id(self) -> TypeId;
+
+    /// The name of the rust type of this column, for error messages.
+    fn type_name(self) -> &'static str;
+
+    /// The SQL type that values compared against this column are cast to, see
+    /// [`Column::CAST`].
+    fn cast(self) -> Option<&'static str>;
+
+    /// Whether values of type `V` can be compared with this column, i.e. whether `V` is its rust
+    /// type, the type held by it if it is nullable, or a string slice for a text column.
+    #[must_use]
+    fn accepts<V>(self) -> bool
+    where
+        V: 'static,
+    {
+        let type_id = self.type_id();
+        let is_str = TypeId::of::<V>() == TypeId::of::<&'static str>();
+
+        type_id == TypeId::of::<V>()
+            || type_id == TypeId::of::<Option<V>>()
+            || (is_str
+                && (type_id == TypeId::of::<String>() || type_id == TypeId::of::<Option<String>>()))
+    }
+}
+
+/// A rust type that can be stored in a column on `DB`, i.e. one that satisfies the bounds of
+/// [`Column::Type`]. Checked by the derive macro for every field, so that errors point at the field
+/// instead of the generated code.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be stored in a column on `{DB}`",
+    label = "this field's type",
+    note = "column types must implement sqlx's `Encode`, `Decode` and `Type` for `{DB}`, as well as `Clone`, `Send` and `Sync`",
+    note = "to store it as some other type, convert it with `#[sky_orm(json)]`, `#[sky_orm(try_from = ..)]` or `#[sky_orm(into = ..)]`"
+)]
+pub trait ColumnType<DB>
+where
+    DB: Database,
+{
+}
+
+impl<T, DB> ColumnType<DB> for T
+where
+    DB: Database,
+    T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone + Send + Sync,
+{
 }
 
 pub trait Column {
@@ -150,6 +350,11 @@ pub trait Column {
     /// The name this column has in the database;
     const NAME: &'static str;
 
+    /// The SQL type that values compared against this column are cast to, if they are bound as
+    /// a different one. For example, values of `citext` columns are bound as `text`, which would
+    /// make comparisons case-sensitive, so they are cast to `citext`.
+    const CAST: Option<&'static str> = None;
+
     /// The fully qualified name of this column, usually something like
     /// `"entity_table_name"."column_name"`.
     #[must_use]
@@ -160,7 +365,27 @@ pub trait Column {
         )
     }
 
-    /// Try to parse a return value from a sqlx row into this column's rust type.
+    /// The name of this column, not qualified by its table, e.g. for the column list of an
+    /// `INSERT` statement.
+    #[must_use]
+    fn column_name() -> ColumnName {
+        ColumnName::new(Self::NAME.to_string())
+    }
+
+    /// The name this column is selected as when selecting whole models, i.e. `__c` followed by its
+    /// position in [`COLUMN_NAMES`](Entity::COLUMN_NAMES), e.g. `__c0`. Drivers name the columns
+    /// of a row after their alias, never after their qualified name, and aliases built from the
+    /// names of tables and columns could exceed the 63 bytes postgres truncates identifiers to.
+    #[must_use]
+    fn select_alias() -> String {
+        Self::Entity::COLUMN_NAMES
+            .iter()
+            .position(|e| *e == Self::NAME)
+            .map_or_else(|| Self::NAME.to_string(), |i| format!("__c{i}"))
+    }
+
+    /// Try to parse a return value from a sqlx row into this column's rust type, looking it up by
+    /// its [`select_alias`](Self::select_alias).
     ///
     /// # Errors
     ///
@@ -170,10 +395,311 @@ pub trait Column {
         R: Row<Database = <Self::Entity as Entity>::Database>,
         for<'a> &'a str: ColumnIndex<R>,
     {
-        row.try_get(Self::full_column_name().to_string().as_str())
+        row.try_get(Self::select_alias().as_str())
     }
 }
 
+/// An alias for a table joined into a query, so that the same table can be joined more than once,
+/// e.g. an entity with itself, or the same entity through two different relations.
+///
+/// ```ignore
+/// struct Sender;
+///
+/// impl TableAlias for Sender {
+///     const ALIAS: &'static str = "sender";
+/// }
+/// ```
+pub trait TableAlias: Send + Sync + 'static {
+    /// The name the table is referred to by within the query.
+    const ALIAS: &'static str;
+}
+
+/// Column `C`, but on the table joined under alias `A`, e.g.
+/// `Aliased::<account::columns::Owner, Sender>::eq(owner)`. It supports the same operators as `C`.
+///
+/// See [`Select::where_relation_as`](crate::query::select::Select::where_relation_as) and
+/// [`Select::where_inverse_relation_as`](crate::query::select::Select::where_inverse_relation_as).
+pub struct Aliased<C, A>(PhantomData<(C, A)>);
+
+impl<C, A> Column for Aliased<C, A>
+where
+    C: Column,
+    A: TableAlias,
+{
+    type Type = C::Type;
+
+    type Entity = C::Entity;
+
+    const NAME: &'static str = C::NAME;
+
+    const CAST: Option<&'static str> = C::CAST;
+
+    fn full_column_name() -> ColumnName {
+        ColumnName::new_with_table_or_alias(A::ALIAS.to_string(), Self::NAME.to_string())
+    }
+}
+
+/// An ordered list of columns on the same entity, expressed as a tuple of column types, e.g.
+/// `(columns::TenantId, columns::UserId)`. Used to describe composite keys.
+pub trait ColumnTuple {
+    /// The entity that all columns in this tuple belong to.
+    type Entity: Entity;
+
+    /// A tuple of the underlying rust types of each column.
+    type Type;
+
+    /// The names of the columns in the database, in order.
+    const NAMES: &'static [&'static str];
+
+    /// The fully qualified names of the columns, in order.
+    #[must_use]
+    fn full_column_names() -> Vec<ColumnName> {
+        Self::NAMES
+            .iter()
+            .map(|e| {
+                ColumnName::new_with_table_or_alias(
+                    <Self::Entity as Entity>::TABLE_NAME.to_string(),
+                    (*e).to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// A value of these columns, bound to the query as a row value.
+    ///
+    /// SQL: `($1, $2, ..)`
+    fn row_value(value: Self::Type) -> RowExpr<<Self::Entity as Entity>::Database>;
+
+    /// These columns as a row value, e.g. to compare them with [`row_value`](Self::row_value).
+    ///
+    /// SQL: `(column_a, column_b, ..)`
+    #[must_use]
+    fn column_row() -> RowExpr<<Self::Entity as Entity>::Database> {
+        RowExpr::new(
+            Self::full_column_names()
+                .into_iter()
+                .map(|e| Box::new(e) as Box<dyn PushToQuery<_>>)
+                .collect(),
+        )
+    }
+
+    /// Try to parse the values of these columns from a sqlx row, taking them from the row's
+    /// columns in order.
+    ///
+    /// # Errors
+    ///
+    /// If the desired values cannot be parsed from the given row. See [`sqlx::Error`].
+    fn values_from_row<R>(row: &R) -> Result<Self::Type, sqlx::Error>
+    where
+        R: Row<Database = <Self::Entity as Entity>::Database>,
+        usize: ColumnIndex<R>;
+
+    /// Check whether the values of these columns occur in some collection, e.g. to look up
+    /// entries by a composite key. Long collections are split like in
+    /// [`ComparableColumn::is_in`].
+    ///
+    /// SQL: `(column_a, column_b) IN (($1, $2), ($3, $4), ..)`
+    #[must_use]
+    fn is_in(
+        other: &[Self::Type],
+    ) -> EntityConditionExpr<
+        impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
+        Self::Entity,
+    >
+    where
+        Self::Type: Clone,
+    {
+        InListExpr::new(
+            Self::column_row(),
+            other.iter().cloned().map(Self::row_value).collect(),
+            false,
+        )
+        .into()
+    }
+
+    /// Check whether the values of these columns do _not_ occur in some collection.
+    ///
+    /// SQL: `(column_a, column_b) NOT IN (($1, $2), ($3, $4), ..)`
+    #[must_use]
+    fn is_not_in(
+        other: &[Self::Type],
+    ) -> EntityConditionExpr<
+        impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
+        Self::Entity,
+    >
+    where
+        Self::Type: Clone,
+    {
+        InListExpr::new(
+            Self::column_row(),
+            other.iter().cloned().map(Self::row_value).collect(),
+            true,
+        )
+        .into()
+    }
+}
+
+macro_rules! impl_column_tuple {
+    ($first:ident $(, $rest:ident)*) => {
+        impl<$first, $($rest),*> ColumnTuple for ($first, $($rest,)*)
+        where
+            $first: Column,
+            $first::Type: 'static,
+            $($rest: Column<Entity = $first::Entity>,)*
+            $($rest::Type: 'static,)*
+        {
+            type Entity = $first::Entity;
+            type Type = ($first::Type, $($rest::Type,)*);
+            const NAMES: &'static [&'static str] = &[$first::NAME, $($rest::NAME),*];
+
+            // The values are bound to the names of their column types.
+            #[allow(non_snake_case)]
+            fn row_value(value: Self::Type) -> RowExpr<<Self::Entity as Entity>::Database> {
+                let ($first, $($rest,)*) = value;
+
+                RowExpr::new(vec![
+                    Box::new($first::variable($first)),
+                    $(Box::new($rest::variable($rest)),)*
+                ])
+            }
+
+            fn values_from_row<R>(row: &R) -> Result<Self::Type, sqlx::Error>
+            where
+                R: Row<Database = <Self::Entity as Entity>::Database>,
+                usize: ColumnIndex<R>,
+            {
+                // Tuple expressions are evaluated from left to right.
+                let mut index = 0;
+                let mut next_index = || {
+                    index += 1;
+                    index - 1
+                };
+
+                Ok((
+                    row.try_get::<$first::Type, _>(next_index())?,
+                    $(row.try_get::<$rest::Type, _>(next_index())?,)*
+                ))
+            }
+        }
+    };
+}
+
+impl_column_tuple!(A);
+impl_column_tuple!(A, B);
+impl_column_tuple!(A, B, C);
+impl_column_tuple!(A, B, C, D);
+impl_column_tuple!(A, B, C, D, E);
+impl_column_tuple!(A, B, C, D, E, F);
+
+/// The database of the entity that column `C` belongs to.
+pub type ColumnDatabase<C> = <<C as Column>::Entity as Entity>::Database;
+
+/// A binary expression with column `C` on the left-hand side, and `Q` on the right.
+pub type ColumnBinaryExpr<C, Q> = BinaryExpr<ColumnName, Q, ColumnDatabase<C>>;
+
+/// A value of column `C`, bound to the query as a parameter.
+pub type ColumnQueryVariable<C> = QueryVariable<<C as Column>::Type, ColumnDatabase<C>>;
+
+/// Building blocks for conditions on a column, implemented for all columns.
+///
+/// This is the extension point for adding operators that sky-orm does not support out of the box.
+/// Define a trait with [`ColumnExt`] as supertrait, and build its conditions from the methods
+/// below, or from the expressions in [`query`](crate::query) directly, e.g. a trigram similarity
+/// check `column % $1` could be written as
+/// `Self::binary_op(BinaryExprOperand::Custom("%"), other)`.
+pub trait ColumnExt: Column + Sized {
+    /// Compare this column against a bound value using the given operator.
+    ///
+    /// SQL: `column [operator] $1`
+    #[must_use]
+    fn binary_op(
+        operand: BinaryExprOperand,
+        other: Self::Type,
+    ) -> EntityConditionExpr<ColumnBinaryExpr<Self, ColumnQueryVariable<Self>>, Self::Entity>
+    where
+        Self::Type: 'static,
+    {
+        Self::binary_expr(operand, Self::variable(other))
+    }
+
+    /// A bound value to compare this column against, cast to the column's [`CAST`](Column::CAST)
+    /// type, if any.
+    ///
+    /// SQL: `$1`
+    #[must_use]
+    fn variable(value: Self::Type) -> ColumnQueryVariable<Self>
+    where
+        Self::Type: 'static,
+    {
+        let variable = QueryVariable::new(value);
+
+        match Self::CAST {
+            Some(sql_type) => variable.cast(sql_type),
+            None => variable,
+        }
+    }
+
+    /// Compare this column against an arbitrary expression using the given operator.
+    ///
+    /// SQL: `column [operator] expression`
+    #[must_use]
+    fn binary_expr<Q>(
+        operand: BinaryExprOperand,
+        other: Q,
+    ) -> EntityConditionExpr<ColumnBinaryExpr<Self, Q>, Self::Entity>
+    where
+        Q: PushToQuery<ColumnDatabase<Self>>,
+    {
+        BinaryExpr::new(Self::full_column_name(), other, operand).into()
+    }
+
+    /// This column on its own, e.g. to sort by via
+    /// [`Select::order_by`](crate::query::select::Select::order_by).
+    ///
+    /// SQL: `column`
+    #[must_use]
+    fn column() -> EntityConditionExpr<ColumnName, Self::Entity> {
+        Self::full_column_name().into()
+    }
+
+    /// Assign a bound value to this column, in an `UPDATE` or `INSERT` statement.
+    ///
+    /// SQL: `column = $1`
+    #[must_use]
+    fn value(value: Self::Type) -> ColumnAssignment<Self::Entity>
+    where
+        Self::Type: 'static,
+    {
+        Self::assign_expr(QueryVariable::new(value))
+    }
+
+    /// Assign an arbitrary expression to this column, in an `UPDATE` or `INSERT` statement.
+    ///
+    /// SQL: `column = expression`
+    #[must_use]
+    fn assign_expr<Q>(expr: Q) -> ColumnAssignment<Self::Entity>
+    where
+        Q: PushToQuery<ColumnDatabase<Self>> + 'static,
+    {
+        ColumnAssignment {
+            column_name: Self::NAME,
+            value: Box::new(expr),
+        }
+    }
+
+    /// Apply a postfix operator to this column.
+    ///
+    /// SQL: `column [operator]`
+    #[must_use]
+    fn postfix_op(
+        operand: SingletonExprOperand,
+    ) -> EntityConditionExpr<SingletonExpr<ColumnName, ColumnDatabase<Self>>, Self::Entity> {
+        SingletonExpr::new(Self::full_column_name(), operand).into()
+    }
+}
+
+impl<T> ColumnExt for T where T: Column {}
+
 pub trait NullableColumn: Column + Sized {
     /// Check whether this column is `null`.
     ///
@@ -224,19 +750,66 @@ pub trait ComparableColumn: Column + Sized {
     ) -> EntityConditionExpr<impl PushToQuery<<Self::Entity as Entity>::Database>, Self::Entity>;
 
     /// Check whether the value of this column occurs in some collection.
+    ///
+    /// Long collections are split into several `IN` lists, see [`InListExpr`].
     fn is_in(
         other: &[Self::Type],
     ) -> EntityConditionExpr<
         impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
         Self::Entity,
-    >;
+    > {
+        Self::is_in_chunks(other, IN_LIST_CHUNK_SIZE)
+    }
 
     /// Check whether the value of this column does _not_ occur in some collection.
+    ///
+    /// Long collections are split into several `NOT IN` lists, see [`InListExpr`].
     fn is_not_in(
         other: &[Self::Type],
     ) -> EntityConditionExpr<
         impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
         Self::Entity,
+    > {
+        Self::is_not_in_chunks(other, IN_LIST_CHUNK_SIZE)
+    }
+
+    /// Like [`is_in`](Self::is_in), but taking ownership of the values instead of cloning them,
+    /// e.g. for a large set of keys collected only for this query. As with `is_in`, long
+    /// collections are split into lists of at most [`IN_LIST_CHUNK_SIZE`] values.
+    fn is_in_values(
+        other: impl IntoIterator<Item = Self::Type>,
+    ) -> EntityConditionExpr<
+        impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
+        Self::Entity,
+    >;
+
+    /// Like [`is_not_in`](Self::is_not_in), but taking ownership of the values instead of cloning
+    /// them. As with `is_not_in`, long collections are split into lists of at most
+    /// [`IN_LIST_CHUNK_SIZE`] values.
+    fn is_not_in_values(
+        other: impl IntoIterator<Item = Self::Type>,
+    ) -> EntityConditionExpr<
+        impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
+        Self::Entity,
+    >;
+
+    /// Like [`is_in`](Self::is_in), but putting at most `chunk_size` values into a single list.
+    fn is_in_chunks(
+        other: &[Self::Type],
+        chunk_size: usize,
+    ) -> EntityConditionExpr<
+        impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
+        Self::Entity,
+    >;
+
+    /// Like [`is_not_in`](Self::is_not_in), but putting at most `chunk_size` values into a single
+    /// list.
+    fn is_not_in_chunks(
+        other: &[Self::Type],
+        chunk_size: usize,
+    ) -> EntityConditionExpr<
+        impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
+        Self::Entity,
     >;
 }
 
@@ -251,7 +824,7 @@ where
     {
         BinaryExpr::new(
             Self::full_column_name(),
-            QueryVariable::new(other),
+            Self::variable(other),
             crate::query::BinaryExprOperand::Equals,
         )
         .into()
@@ -263,45 +836,71 @@ where
     {
         BinaryExpr::new(
             Self::full_column_name(),
-            QueryVariable::new(other),
+            Self::variable(other),
             crate::query::BinaryExprOperand::DoesNotEqual,
         )
         .into()
     }
 
-    fn is_in(
+    fn is_in_values(
+        other: impl IntoIterator<Item = Self::Type>,
+    ) -> EntityConditionExpr<
+        impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
+        Self::Entity,
+    > {
+        InListExpr::new(
+            Self::full_column_name(),
+            other.into_iter().map(Self::variable).collect(),
+            false,
+        )
+        .with_chunk_size(IN_LIST_CHUNK_SIZE)
+        .into()
+    }
+
+    fn is_not_in_values(
+        other: impl IntoIterator<Item = Self::Type>,
+    ) -> EntityConditionExpr<
+        impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
+        Self::Entity,
+    > {
+        InListExpr::new(
+            Self::full_column_name(),
+            other.into_iter().map(Self::variable).collect(),
+            true,
+        )
+        .with_chunk_size(IN_LIST_CHUNK_SIZE)
+        .into()
+    }
+
+    fn is_in_chunks(
         other: &[Self::Type],
+        chunk_size: usize,
     ) -> EntityConditionExpr<
         impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
         Self::Entity,
     > {
-        BinaryExpr::new(
+        InListExpr::new(
             Self::full_column_name(),
-            other
-                .iter()
-                .cloned()
-                .map(QueryVariable::new)
-                .collect::<Vec<_>>(),
-            crate::query::BinaryExprOperand::In,
+            other.iter().cloned().map(Self::variable).collect(),
+            false,
         )
+        .with_chunk_size(chunk_size)
         .into()
     }
 
-    fn is_not_in(
+    fn is_not_in_chunks(
         other: &[Self::Type],
+        chunk_size: usize,
     ) -> EntityConditionExpr<
         impl PushToQuery<<Self::Entity as Entity>::Database> + 'static,
         Self::Entity,
     > {
-        BinaryExpr::new(
+        InListExpr::new(
             Self::full_column_name(),
-            other
-                .iter()
-                .cloned()
-                .map(QueryVariable::new)
-                .collect::<Vec<_>>(),
-            crate::query::BinaryExprOperand::NotIn,
+            other.iter().cloned().map(Self::variable).collect(),
+            true,
         )
+        .with_chunk_size(chunk_size)
         .into()
     }
 }
@@ -386,8 +985,8 @@ where
         BinaryExpr::new(
             Self::full_column_name(),
             BinaryExpr::new(
-                QueryVariable::new(left),
-                QueryVariable::new(right),
+                Self::variable(left),
+                Self::variable(right),
                 BinaryExprOperand::And,
             ),
             BinaryExprOperand::Between,
@@ -403,8 +1002,8 @@ where
         BinaryExpr::new(
             Self::full_column_name(),
             BinaryExpr::new(
-                QueryVariable::new(left),
-                QueryVariable::new(right),
+                Self::variable(left),
+                Self::variable(right),
                 BinaryExprOperand::And,
             ),
             BinaryExprOperand::NotBetween,
@@ -418,7 +1017,7 @@ where
     {
         BinaryExpr::new(
             Self::full_column_name(),
-            QueryVariable::new(other),
+            Self::variable(other),
             BinaryExprOperand::Gt,
         )
         .into()
@@ -430,7 +1029,7 @@ where
     {
         BinaryExpr::new(
             Self::full_column_name(),
-            QueryVariable::new(other),
+            Self::variable(other),
             BinaryExprOperand::Lt,
         )
         .into()
@@ -442,7 +1041,7 @@ where
     {
         BinaryExpr::new(
             Self::full_column_name(),
-            QueryVariable::new(other),
+            Self::variable(other),
             BinaryExprOperand::Geq,
         )
         .into()
@@ -454,9 +1053,48 @@ where
     {
         BinaryExpr::new(
             Self::full_column_name(),
-            QueryVariable::new(other),
+            Self::variable(other),
             BinaryExprOperand::Leq,
         )
         .into()
     }
 }
+
+/// A column holding numbers, which can be changed relative to its current value.
+pub trait NumericColumn: Column + Sized {
+    /// Increase the value of this column by `amount` in an `UPDATE` statement, without reading it
+    /// first.
+    ///
+    /// SQL: `column = column + $1`
+    #[must_use]
+    fn increment(amount: Self::Type) -> ColumnAssignment<Self::Entity>;
+
+    /// Decrease the value of this column by `amount` in an `UPDATE` statement, without reading it
+    /// first.
+    ///
+    /// SQL: `column = column - $1`
+    #[must_use]
+    fn decrement(amount: Self::Type) -> ColumnAssignment<Self::Entity>;
+}
+
+impl<T> NumericColumn for T
+where
+    T: Column,
+    T::Type: Add<Output = T::Type> + Sub<Output = T::Type> + 'static,
+{
+    fn increment(amount: Self::Type) -> ColumnAssignment<Self::Entity> {
+        Self::assign_expr(BinaryExpr::new(
+            Self::full_column_name(),
+            QueryVariable::new(amount),
+            BinaryExprOperand::Add,
+        ))
+    }
+
+    fn decrement(amount: Self::Type) -> ColumnAssignment<Self::Entity> {
+        Self::assign_expr(BinaryExpr::new(
+            Self::full_column_name(),
+            QueryVariable::new(amount),
+            BinaryExprOperand::Subtract,
+        ))
+    }
+}
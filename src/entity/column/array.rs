@@ -0,0 +1,122 @@
+//! Operators on postgres array columns.
+
+use sqlx::{Encode, Postgres, Type};
+
+use crate::{
+    entity::{
+        Entity,
+        column::{Column, ColumnBinaryExpr, ColumnExt, ColumnQueryVariable, EntityConditionExpr},
+    },
+    query::{BinaryExprOperand, BracketsExpr, PushToQuery, QueryVariable},
+};
+
+/// The rust type of an array column, i.e. a [`Vec`], or an [`Option`] of one.
+///
+/// Note that `Vec<u8>` is stored as `bytea` rather than an array, so the operators of
+/// [`ArrayColumn`] will be rejected by the database for it.
+pub trait ArrayType {}
+
+impl<T> ArrayType for Vec<T> {}
+
+impl<T> ArrayType for Option<Vec<T>> {}
+
+/// A condition on an array column, comparing it against a bound array.
+pub type ArrayConditionExpr<C> =
+    EntityConditionExpr<ColumnBinaryExpr<C, ColumnQueryVariable<C>>, <C as Column>::Entity>;
+
+pub trait ArrayColumn: ColumnExt<Entity: Entity<Database = Postgres>>
+where
+    Self::Type: ArrayType + 'static,
+{
+    /// Check whether this column contains all elements of `other`.
+    ///
+    /// SQL: `column @> $1`
+    #[must_use]
+    fn contains(other: Self::Type) -> ArrayConditionExpr<Self> {
+        Self::binary_op(BinaryExprOperand::Custom("@>"), other)
+    }
+
+    /// Check whether all elements of this column are contained in `other`.
+    ///
+    /// SQL: `column <@ $1`
+    #[must_use]
+    fn contained_by(other: Self::Type) -> ArrayConditionExpr<Self> {
+        Self::binary_op(BinaryExprOperand::Custom("<@"), other)
+    }
+
+    /// Check whether this column and `other` have any elements in common.
+    ///
+    /// SQL: `column && $1`
+    #[must_use]
+    fn overlaps(other: Self::Type) -> ArrayConditionExpr<Self> {
+        Self::binary_op(BinaryExprOperand::Custom("&&"), other)
+    }
+}
+
+impl<T> ArrayColumn for T
+where
+    T: Column<Entity: Entity<Database = Postgres>>,
+    T::Type: ArrayType + 'static,
+{
+}
+
+/// Comparisons of a column against the elements of an array.
+///
+/// The array is bound as a single parameter regardless of its length, so unlike
+/// [`is_in`](crate::entity::column::ComparableColumn::is_in), this works for collections beyond
+/// the parameter limit of postgres.
+pub trait AnyColumn: ColumnExt<Entity: Entity<Database = Postgres>>
+where
+    Vec<Self::Type>: for<'a> Encode<'a, Postgres> + Type<Postgres>,
+    Self::Type: 'static,
+{
+    /// Check whether the value of this column equals any element of `other`.
+    ///
+    /// SQL: `column = ANY ($1)`
+    #[must_use]
+    fn eq_any(
+        other: Vec<Self::Type>,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres> + 'static, Self::Entity> {
+        Self::binary_expr(
+            BinaryExprOperand::Custom("= ANY"),
+            BracketsExpr::new(array_variable::<Self>(other)),
+        )
+    }
+
+    /// Check whether the value of this column differs from all elements of `other`.
+    ///
+    /// SQL: `column <> ALL ($1)`
+    #[must_use]
+    fn ne_all(
+        other: Vec<Self::Type>,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres> + 'static, Self::Entity> {
+        Self::binary_expr(
+            BinaryExprOperand::Custom("<> ALL"),
+            BracketsExpr::new(array_variable::<Self>(other)),
+        )
+    }
+}
+
+/// An array of values to compare column `C` against, cast to an array of its
+/// [`CAST`](crate::entity::column::Column::CAST) type, if any.
+fn array_variable<C>(values: Vec<C::Type>) -> QueryVariable<Vec<C::Type>, Postgres>
+where
+    C: Column,
+    Vec<C::Type>: for<'a> Encode<'a, Postgres> + Type<Postgres>,
+    C::Type: 'static,
+{
+    let variable = QueryVariable::new(values);
+
+    match C::CAST {
+        Some(sql_type) => variable.cast(format!("{sql_type}[]")),
+        None => variable,
+    }
+}
+
+impl<T> AnyColumn for T
+where
+    T: Column<Entity: Entity<Database = Postgres>>,
+    Vec<T::Type>: for<'a> Encode<'a, Postgres> + Type<Postgres>,
+    T::Type: 'static,
+{
+}
@@ -0,0 +1,210 @@
+//! Spatial functions on [postgis](https://postgis.net) `geometry` and `geography` columns.
+//!
+//! Spatial values are passed through as bytes, [`Wkb`] by default, to be converted by a geometry
+//! crate such as `geozero`. `model!` maps both column types to it, unless another type is given
+//! with its `types` directive, e.g. `types: { geometry: MyPoint }`. Such a type gets the functions
+//! of [`GeoColumn`] by implementing [`GeoType`].
+//!
+//! Nearest-neighbor searches sort by [`knn_distance`](GeoColumn::knn_distance), which postgis can
+//! answer from a spatial index:
+//!
+//! ```ignore
+//! place::Entity::find()
+//!     .filter(place::columns::Location::within_distance(here.clone(), 500.0))
+//!     .order_by(place::columns::Location::knn_distance(here), Order::Asc)
+//!     .limit(10)
+//! ```
+
+use sqlx::{
+    Decode, Encode, Postgres, QueryBuilder, Type,
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef},
+};
+
+use crate::{
+    entity::{
+        Entity,
+        column::{Column, ColumnExt, ColumnName, EntityConditionExpr},
+    },
+    query::{BinaryExprOperand, PushToQuery, QueryVariable},
+};
+
+/// A spatial value in the (extended) well-known binary format, as postgis sends and receives
+/// them. Values sent by postgis are EWKB, i.e. they include the SRID of the value, if any.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Wkb(pub Vec<u8>);
+
+impl Type<Postgres> for Wkb {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("geometry")
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        *ty == PgTypeInfo::with_name("geometry") || *ty == PgTypeInfo::with_name("geography")
+    }
+}
+
+impl PgHasArrayType for Wkb {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_geometry")
+    }
+}
+
+impl Encode<'_, Postgres> for Wkb {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        buf.extend_from_slice(&self.0);
+
+        Ok(IsNull::No)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Wkb {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => Ok(Self(value.as_bytes()?.to_vec())),
+            PgValueFormat::Text => Err(
+                "not implemented: decode spatial values in text mode (unprepared queries)".into(),
+            ),
+        }
+    }
+}
+
+/// The rust type of a spatial column, i.e. [`Wkb`], or an [`Option`] of it. Implement this for
+/// other types of spatial values to use the functions of [`GeoColumn`] on their columns.
+pub trait GeoType {
+    /// The type of spatial value to compare this column against.
+    type Geometry: for<'a> Encode<'a, Postgres> + Type<Postgres> + Send + Sync + 'static;
+}
+
+impl GeoType for Wkb {
+    type Geometry = Self;
+}
+
+impl<T> GeoType for Option<T>
+where
+    T: GeoType,
+{
+    type Geometry = T::Geometry;
+}
+
+/// A call to a postgis function on a column and a spatial value, and optionally a distance.
+struct SpatialFunction<G>
+where
+    G: for<'a> Encode<'a, Postgres> + Type<Postgres> + Send + Sync + 'static,
+{
+    name: &'static str,
+    column: ColumnName,
+    other: QueryVariable<G, Postgres>,
+    distance: Option<QueryVariable<f64, Postgres>>,
+}
+
+impl<G> PushToQuery<Postgres> for SpatialFunction<G>
+where
+    G: for<'a> Encode<'a, Postgres> + Type<Postgres> + Send + Sync + 'static,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push(format_args!("{}(", self.name));
+        self.column.push_to(builder);
+        builder.push(", ");
+        self.other.push_to(builder);
+        if let Some(distance) = &self.distance {
+            builder.push(", ");
+            distance.push_to(builder);
+        }
+        builder.push(")");
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        PushToQuery::<Postgres>::push_tables(&self.column, tables);
+    }
+}
+
+fn spatial_function<C>(
+    name: &'static str,
+    other: <C::Type as GeoType>::Geometry,
+    distance: Option<f64>,
+) -> EntityConditionExpr<impl PushToQuery<Postgres>, C::Entity>
+where
+    C: Column<Entity: Entity<Database = Postgres>>,
+    C::Type: GeoType,
+{
+    SpatialFunction {
+        name,
+        column: C::full_column_name(),
+        other: QueryVariable::new(other),
+        distance: distance.map(QueryVariable::new),
+    }
+    .into()
+}
+
+pub trait GeoColumn: ColumnExt<Entity: Entity<Database = Postgres>>
+where
+    Self::Type: GeoType,
+{
+    /// Check whether the value of this column is within `distance` of `other`, in the units of
+    /// its spatial reference system for `geometry` columns, or in meters for `geography` columns.
+    ///
+    /// SQL: `ST_DWithin(column, $1, $2)`
+    #[must_use]
+    fn within_distance(
+        other: <Self::Type as GeoType>::Geometry,
+        distance: f64,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        spatial_function::<Self>("ST_DWithin", other, Some(distance))
+    }
+
+    /// Check whether the value of this column shares any space with `other`.
+    ///
+    /// SQL: `ST_Intersects(column, $1)`
+    #[must_use]
+    fn intersects(
+        other: <Self::Type as GeoType>::Geometry,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        spatial_function::<Self>("ST_Intersects", other, None)
+    }
+
+    /// Check whether `other` lies completely inside the value of this column. Only supported
+    /// for `geometry` columns.
+    ///
+    /// SQL: `ST_Contains(column, $1)`
+    #[must_use]
+    fn contains(
+        other: <Self::Type as GeoType>::Geometry,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        spatial_function::<Self>("ST_Contains", other, None)
+    }
+
+    /// The distance between the value of this column and `other`, in the same units as
+    /// [`within_distance`](Self::within_distance).
+    ///
+    /// SQL: `ST_Distance(column, $1)`
+    #[must_use]
+    fn distance(
+        other: <Self::Type as GeoType>::Geometry,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        spatial_function::<Self>("ST_Distance", other, None)
+    }
+
+    /// The distance between the value of this column and `other` as computed by the spatial
+    /// index, for sorting by in nearest-neighbor searches.
+    ///
+    /// SQL: `column <-> $1`
+    #[must_use]
+    fn knn_distance(
+        other: <Self::Type as GeoType>::Geometry,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("<->"), QueryVariable::new(other))
+    }
+}
+
+impl<T> GeoColumn for T
+where
+    T: Column<Entity: Entity<Database = Postgres>>,
+    T::Type: GeoType,
+{
+}
@@ -0,0 +1,142 @@
+//! The rust type of postgres `interval` columns.
+
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    time::Duration,
+};
+
+use sqlx::{
+    Decode, Encode, Postgres, Type,
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef, types::PgInterval},
+};
+
+const MICROSECONDS_PER_DAY: i128 = 86_400_000_000;
+
+/// A postgres `interval`, compared the way postgres compares intervals.
+///
+/// It is made up of months, days and microseconds like [`PgInterval`], but unlike it, columns of
+/// this type are [orderable](super::OrderableColumn), e.g. to find jobs that are due more often
+/// than hourly:
+///
+/// ```ignore
+/// job::Entity::find().filter(job::columns::RunEvery::lt(Interval::new(0, 0, 3_600_000_000)));
+/// ```
+///
+/// For comparisons, a month counts as 30 days and a day as 24 hours, so `1 month` equals
+/// `30 days` even though they are stored differently. Convert to [`PgInterval`] to compare the
+/// fields as they are stored instead.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub microseconds: i64,
+}
+
+impl Interval {
+    #[must_use]
+    pub const fn new(months: i32, days: i32, microseconds: i64) -> Self {
+        Self {
+            months,
+            days,
+            microseconds,
+        }
+    }
+
+    /// The length of this interval in microseconds, counting months as 30 days and days as 24
+    /// hours, like postgres does when comparing intervals.
+    #[must_use]
+    pub const fn span(self) -> i128 {
+        (self.months as i128 * 30 + self.days as i128) * MICROSECONDS_PER_DAY
+            + self.microseconds as i128
+    }
+
+    /// This interval as a [`Duration`] of its [`span`](Self::span), or [`None`] if it is
+    /// negative, or too long to be represented as one.
+    #[must_use]
+    pub fn to_duration(self) -> Option<Duration> {
+        u64::try_from(self.span()).ok().map(Duration::from_micros)
+    }
+}
+
+impl PartialEq for Interval {
+    fn eq(&self, other: &Self) -> bool {
+        self.span() == other.span()
+    }
+}
+
+impl Eq for Interval {}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.span().cmp(&other.span())
+    }
+}
+
+impl Hash for Interval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.span().hash(state);
+    }
+}
+
+impl From<PgInterval> for Interval {
+    fn from(value: PgInterval) -> Self {
+        Self::new(value.months, value.days, value.microseconds)
+    }
+}
+
+impl From<Interval> for PgInterval {
+    fn from(value: Interval) -> Self {
+        Self {
+            months: value.months,
+            days: value.days,
+            microseconds: value.microseconds,
+        }
+    }
+}
+
+impl TryFrom<Duration> for Interval {
+    type Error = BoxDynError;
+
+    /// Convert a [`Duration`] to an interval of only microseconds. Fails if the duration has
+    /// a fraction of a microsecond, or does not fit into an interval.
+    fn try_from(value: Duration) -> Result<Self, Self::Error> {
+        PgInterval::try_from(value).map(Self::from)
+    }
+}
+
+impl Type<Postgres> for Interval {
+    fn type_info() -> PgTypeInfo {
+        PgInterval::type_info()
+    }
+}
+
+impl PgHasArrayType for Interval {
+    fn array_type_info() -> PgTypeInfo {
+        PgInterval::array_type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for Interval {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        PgInterval::from(*self).encode_by_ref(buf)
+    }
+
+    fn size_hint(&self) -> usize {
+        PgInterval::from(*self).size_hint()
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for Interval {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        PgInterval::decode(value).map(Self::from)
+    }
+}
@@ -0,0 +1,131 @@
+//! Operators on postgres `jsonb` columns.
+//!
+//! Field accesses are expressions rather than conditions, so they can be compared further, e.g.
+//! `columns::Settings::get_text_eq("theme", "dark")` is shorthand for
+//! `BinaryExpr::new(columns::Settings::get_text("theme"), QueryVariable::new("dark".to_string()), BinaryExprOperand::Equals)`.
+
+use sqlx::{
+    Postgres, QueryBuilder,
+    types::{Json, JsonValue},
+};
+
+use crate::{
+    entity::{
+        Entity,
+        column::{Column, ColumnBinaryExpr, ColumnExt, ColumnName, EntityConditionExpr},
+    },
+    query::{BinaryExpr, BinaryExprOperand, PushToQuery, QueryVariable},
+};
+
+/// The rust type of a json column, i.e. [`Json`], [`JsonValue`], or an [`Option`] of either.
+pub trait JsonType {}
+
+impl<T> JsonType for Json<T> {}
+
+impl JsonType for JsonValue {}
+
+impl<T> JsonType for Option<T> where T: JsonType {}
+
+/// A call to `jsonb_path_exists` on a column.
+struct JsonPathExists {
+    column: ColumnName,
+    path: QueryVariable<String, Postgres>,
+}
+
+impl PushToQuery<Postgres> for JsonPathExists {
+    fn push_to(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push("jsonb_path_exists(");
+        self.column.push_to(builder);
+        builder.push(", ");
+        self.path.push_to(builder);
+        builder.push("::jsonpath)");
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        PushToQuery::<Postgres>::push_tables(&self.column, tables);
+    }
+}
+
+/// Operators on json columns.
+pub trait JsonColumn: ColumnExt<Entity: Entity<Database = Postgres>>
+where
+    Self::Type: JsonType,
+{
+    /// The json value stored under `key` in this column.
+    ///
+    /// SQL: `column -> $1`
+    fn get(key: impl Into<String>) -> ColumnBinaryExpr<Self, QueryVariable<String, Postgres>> {
+        BinaryExpr::new(
+            Self::full_column_name(),
+            QueryVariable::new(key.into()),
+            BinaryExprOperand::Custom("->"),
+        )
+    }
+
+    /// The value stored under `key` in this column, as text.
+    ///
+    /// SQL: `column ->> $1`
+    fn get_text(key: impl Into<String>) -> ColumnBinaryExpr<Self, QueryVariable<String, Postgres>> {
+        BinaryExpr::new(
+            Self::full_column_name(),
+            QueryVariable::new(key.into()),
+            BinaryExprOperand::Custom("->>"),
+        )
+    }
+
+    /// Check whether the value stored under `key` in this column equals `value`, as text.
+    ///
+    /// SQL: `column ->> $1 = $2`
+    fn get_text_eq(
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        BinaryExpr::new(
+            Self::get_text(key),
+            QueryVariable::new(value.into()),
+            BinaryExprOperand::Equals,
+        )
+        .into()
+    }
+
+    /// Check whether the document in this column contains `other`, i.e. all of its keys and
+    /// values at the top level.
+    ///
+    /// SQL: `column @> $1`
+    #[must_use]
+    fn contains(other: JsonValue) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("@>"), QueryVariable::new(other))
+    }
+
+    /// Check whether `key` exists at the top level of the document in this column.
+    ///
+    /// SQL: `column ? $1`
+    fn has_key(
+        key: impl Into<String>,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(
+            BinaryExprOperand::Custom("?"),
+            QueryVariable::new(key.into()),
+        )
+    }
+
+    /// Check whether the SQL/JSON `path` returns any item for the document in this column.
+    ///
+    /// SQL: `jsonb_path_exists(column, $1::jsonpath)`
+    fn path_exists(
+        path: impl Into<String>,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        JsonPathExists {
+            column: Self::full_column_name(),
+            path: QueryVariable::new(path.into()),
+        }
+        .into()
+    }
+}
+
+impl<T> JsonColumn for T
+where
+    T: Column<Entity: Entity<Database = Postgres>>,
+    T::Type: JsonType,
+{
+}
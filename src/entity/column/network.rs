@@ -0,0 +1,81 @@
+//! Operators on postgres `inet` and `cidr` columns.
+
+use std::net::IpAddr;
+
+use sqlx::{Postgres, types::ipnetwork::IpNetwork};
+
+use crate::{
+    entity::{
+        Entity,
+        column::{Column, ColumnExt, EntityConditionExpr},
+    },
+    query::{BinaryExprOperand, PushToQuery, QueryVariable},
+};
+
+/// The rust type of a network address column, i.e. [`IpNetwork`], [`IpAddr`], or an [`Option`]
+/// of either.
+pub trait NetworkType {}
+
+impl NetworkType for IpNetwork {}
+
+impl NetworkType for IpAddr {}
+
+impl<T> NetworkType for Option<T> where T: NetworkType {}
+
+pub trait NetworkColumn: ColumnExt<Entity: Entity<Database = Postgres>>
+where
+    Self::Type: NetworkType,
+{
+    /// Check whether the network in this column is strictly contained in `other`.
+    ///
+    /// SQL: `column << $1`
+    #[must_use]
+    fn contained_by(
+        other: IpNetwork,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("<<"), QueryVariable::new(other))
+    }
+
+    /// Check whether the network in this column is contained in or equal to `other`.
+    ///
+    /// SQL: `column <<= $1`
+    #[must_use]
+    fn contained_by_or_eq(
+        other: IpNetwork,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("<<="), QueryVariable::new(other))
+    }
+
+    /// Check whether the network in this column strictly contains `other`.
+    ///
+    /// SQL: `column >> $1`
+    #[must_use]
+    fn contains(other: IpNetwork) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom(">>"), QueryVariable::new(other))
+    }
+
+    /// Check whether the network in this column contains or equals `other`.
+    ///
+    /// SQL: `column >>= $1`
+    #[must_use]
+    fn contains_or_eq(
+        other: IpNetwork,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom(">>="), QueryVariable::new(other))
+    }
+
+    /// Check whether the network in this column and `other` contain or equal each other.
+    ///
+    /// SQL: `column && $1`
+    #[must_use]
+    fn overlaps(other: IpNetwork) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("&&"), QueryVariable::new(other))
+    }
+}
+
+impl<T> NetworkColumn for T
+where
+    T: Column<Entity: Entity<Database = Postgres>>,
+    T::Type: NetworkType,
+{
+}
@@ -0,0 +1,87 @@
+//! Distance operators of the [pgvector](https://github.com/pgvector/pgvector) extension.
+//!
+//! Distances are expressions rather than conditions, so they are mostly useful for sorting, e.g. a
+//! nearest-neighbor search looks like
+//! `Entity::find().order_by(columns::Embedding::l2_distance(target), Order::Asc).limit(5)`.
+
+use pgvector::{SparseVector, Vector};
+use sqlx::{Encode, Postgres, Type};
+
+use crate::{
+    entity::{
+        Entity,
+        column::{Column, ColumnExt, EntityConditionExpr},
+    },
+    query::{BinaryExprOperand, PushToQuery, QueryVariable},
+};
+
+/// The rust type of a vector column, i.e. [`Vector`], [`SparseVector`], or an [`Option`] of
+/// either.
+pub trait VectorType {
+    /// The type of vector to measure distances against.
+    type Vector: for<'a> Encode<'a, Postgres> + Type<Postgres> + Send + Sync + 'static;
+}
+
+impl VectorType for Vector {
+    type Vector = Self;
+}
+
+impl VectorType for SparseVector {
+    type Vector = Self;
+}
+
+impl<T> VectorType for Option<T>
+where
+    T: VectorType,
+{
+    type Vector = T::Vector;
+}
+
+pub trait VectorColumn: ColumnExt<Entity: Entity<Database = Postgres>>
+where
+    Self::Type: VectorType,
+{
+    /// The euclidean distance between this column and `other`.
+    ///
+    /// SQL: `column <-> $1`
+    fn l2_distance(
+        other: <Self::Type as VectorType>::Vector,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("<->"), QueryVariable::new(other))
+    }
+
+    /// The cosine distance between this column and `other`.
+    ///
+    /// SQL: `column <=> $1`
+    fn cosine_distance(
+        other: <Self::Type as VectorType>::Vector,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("<=>"), QueryVariable::new(other))
+    }
+
+    /// The _negative_ inner product of this column and `other`, so that sorting in ascending order
+    /// yields the closest vectors first.
+    ///
+    /// SQL: `column <#> $1`
+    fn inner_product(
+        other: <Self::Type as VectorType>::Vector,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("<#>"), QueryVariable::new(other))
+    }
+
+    /// The taxicab distance between this column and `other`.
+    ///
+    /// SQL: `column <+> $1`
+    fn l1_distance(
+        other: <Self::Type as VectorType>::Vector,
+    ) -> EntityConditionExpr<impl PushToQuery<Postgres>, Self::Entity> {
+        Self::binary_expr(BinaryExprOperand::Custom("<+>"), QueryVariable::new(other))
+    }
+}
+
+impl<T> VectorColumn for T
+where
+    T: Column<Entity: Entity<Database = Postgres>>,
+    T::Type: VectorType,
+{
+}
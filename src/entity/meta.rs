@@ -0,0 +1,68 @@
+//! Descriptions of entities that can be inspected at runtime, e.g. to build admin interfaces or
+//! exporters that work with any entity. Generated by the derive macro, see [`Entity::meta`].
+
+#[cfg(doc)]
+use super::Entity;
+
+/// The table, columns and relations of an entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityMeta {
+    /// The name of the entity's table.
+    pub table: &'static str,
+    /// The schema of the entity's table, or [`None`] if it's in the default one.
+    pub schema: Option<&'static str>,
+    /// The columns of the entity, in the order they are declared in.
+    pub columns: Vec<ColumnMeta>,
+    /// The name of the primary key column, or [`None`] for read-only entities without one.
+    pub primary_key: Option<&'static str>,
+    /// The relations declared on the entity, i.e. its foreign keys and the foreign keys referring
+    /// to it. Only those of `belongs_to`, `has_many` and `has_one` are known, not the ones of
+    /// hand-written `Related` impls.
+    pub relations: Vec<RelationMeta>,
+}
+
+impl EntityMeta {
+    /// The column called `name`, if there is one.
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&ColumnMeta> {
+        self.columns.iter().find(|e| e.name == name)
+    }
+}
+
+/// A column of an entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMeta {
+    /// The name of the column in the database.
+    pub name: &'static str,
+    /// The name of the column's SQL type, as reported by sqlx, e.g. `INT8`.
+    pub sql_type: String,
+    /// The name of the rust type that the column is decoded as.
+    pub rust_type: &'static str,
+    /// Whether the column may hold `NULL`, i.e. whether its rust type is an [`Option`].
+    pub nullable: bool,
+}
+
+/// How two entities are related, from the perspective of the entity describing the relation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationKind {
+    /// This entity holds a foreign key to the other one, which may be shared with other entries.
+    ManyToOne,
+    /// This entity holds a unique foreign key to the other one.
+    OneToOne,
+    /// The other entity holds a foreign key to this one, and there may be many of them per entry.
+    HasMany,
+    /// The other entity holds a unique foreign key to this one.
+    HasOne,
+}
+
+/// A relation between an entity and some other entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationMeta {
+    /// How the entities are related.
+    pub kind: RelationKind,
+    /// The table of the other entity.
+    pub table: &'static str,
+    /// The name of the column holding the foreign key, which is on the other entity for
+    /// [`HasMany`](RelationKind::HasMany) and [`HasOne`](RelationKind::HasOne) relations.
+    pub foreign_key: &'static str,
+}
@@ -1,12 +1,15 @@
-use std::marker::PhantomData;
+use std::{fmt::Debug, marker::PhantomData};
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sqlx::{Database, Decode, Encode, Type};
 
-use crate::{entity::column::Column, query::parse::ParseFromRow};
+use crate::{
+    entity::column::Column,
+    query::{PushToQuery, QueryVariable, parse::ParseFromRow},
+};
 
 use super::Entity;
 
-#[derive(Clone)]
 pub enum ActiveModelValue<T, DB>
 where
     T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone,
@@ -17,6 +20,91 @@ where
     NotSet(PhantomData<DB>),
 }
 
+// These are implemented manually, since deriving them would needlessly require `DB` to implement
+// them too.
+impl<T, DB> Clone for ActiveModelValue<T, DB>
+where
+    T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone,
+    DB: Database,
+{
+    fn clone(&self) -> Self {
+        match self {
+            Self::Set(e) => Self::Set(e.clone()),
+            Self::Unchanged(e) => Self::Unchanged(e.clone()),
+            Self::NotSet(_) => Self::NotSet(PhantomData),
+        }
+    }
+}
+
+impl<T, DB> PartialEq for ActiveModelValue<T, DB>
+where
+    T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone + PartialEq,
+    DB: Database,
+{
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Set(a), Self::Set(b)) | (Self::Unchanged(a), Self::Unchanged(b)) => a == b,
+            (Self::NotSet(_), Self::NotSet(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T, DB> Debug for ActiveModelValue<T, DB>
+where
+    T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone + Debug,
+    DB: Database,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Set(e) => f.debug_tuple("Set").field(e).finish(),
+            Self::Unchanged(e) => f.debug_tuple("Unchanged").field(e).finish(),
+            Self::NotSet(_) => f.write_str("NotSet"),
+        }
+    }
+}
+
+/// Stands in for the value of a field marked `#[sky_orm(sensitive)]`, in generated [`Debug`] impls
+/// and in audit records, so that e.g. passwords or tokens do not end up in logs.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Redacted;
+
+impl Debug for Redacted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+impl Serialize for Redacted {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str("<redacted>")
+    }
+}
+
+/// The state of an [`ActiveModelValue`], with its value replaced by [`Redacted`] in debug output.
+/// See [`ActiveModelValue::redacted`].
+pub struct RedactedValue<'m, T, DB>(&'m ActiveModelValue<T, DB>)
+where
+    T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone,
+    DB: Database;
+
+impl<T, DB> Debug for RedactedValue<'_, T, DB>
+where
+    T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone,
+    DB: Database,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            ActiveModelValue::Set(_) => f.debug_tuple("Set").field(&Redacted).finish(),
+            ActiveModelValue::Unchanged(_) => f.debug_tuple("Unchanged").field(&Redacted).finish(),
+            ActiveModelValue::NotSet(_) => f.write_str("NotSet"),
+        }
+    }
+}
+
 impl<T, DB> ActiveModelValue<T, DB>
 where
     T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone,
@@ -34,6 +122,16 @@ where
         *self = Self::NotSet(PhantomData);
     }
 
+    /// Whether the value has been [`Set`](Self::Set), i.e. changed since it was loaded.
+    pub const fn is_set(&self) -> bool {
+        matches!(self, Self::Set(_))
+    }
+
+    /// Debug output showing whether the value is set, but not the value itself.
+    pub const fn redacted(&self) -> RedactedValue<'_, T, DB> {
+        RedactedValue(self)
+    }
+
     pub fn mark_unchanged(&mut self) {
         if let Self::Set(e) = self {
             *self = Self::Unchanged(e.clone());
@@ -41,6 +139,18 @@ where
     }
 }
 
+impl<T, DB> ActiveModelValue<T, DB>
+where
+    T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone + Send + Sync + 'static,
+    DB: Database + Sync,
+{
+    /// Turn the contained value into a query parameter, or [`None`] if the value is not set.
+    pub fn to_query_variable(&self) -> Option<Box<dyn PushToQuery<DB>>> {
+        self.get()
+            .map(|e| Box::new(QueryVariable::new(e.clone())) as Box<dyn PushToQuery<DB>>)
+    }
+}
+
 impl<'m, T, DB> From<&'m ActiveModelValue<T, DB>> for Option<&'m T>
 where
     T: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB> + Clone,
@@ -56,13 +166,145 @@ where
 
 pub trait Model: Send + Sync + ParseFromRow<<Self::Entity as Entity>::Database> + Sized {
     type Entity: Entity;
-    type ActiveModel: ActiveModel;
+}
+
+/// A model that can be turned into an [`ActiveModel`] for modification. Read-only models (e.g.
+/// those backed by views) do not implement this trait.
+pub trait IntoActiveModel: Model {
+    type ActiveModel: ActiveModel<Model = Self>;
 
     fn into_active(self) -> Self::ActiveModel;
 }
 
-pub trait ActiveModel {
+/// The name of a column paired with a value to be bound to it.
+pub type ColumnValue<DB> = (&'static str, Box<dyn PushToQuery<DB>>);
+
+/// The database backing the entity of a given model.
+pub type ModelDatabase<M> = <<M as Model>::Entity as Entity>::Database;
+
+/// Hooks around the writes of an [`ActiveModel`], e.g. to validate or normalize its values, or to
+/// log changes.
+///
+/// Hooks are run by the persistence APIs that own the models they write, i.e.
+/// [`Session`](crate::session::Session) and [`Importer`](crate::import::Importer), but not by
+/// statements built by hand. The after hooks run inside the same transaction as the write, so
+/// returning an error from any hook aborts it.
+///
+/// An empty implementation is generated for every active model. Use `#[sky_orm(hooks)]` to
+/// implement this trait by hand instead.
+pub trait ActiveModelHooks {
+    /// Called before the model is inserted.
+    ///
+    /// # Errors
+    ///
+    /// To abort the insert.
+    fn before_insert(&mut self) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    /// Called after the model has been inserted.
+    ///
+    /// # Errors
+    ///
+    /// To abort the insert.
+    fn after_insert(&self) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    /// Called before the changed columns of the model are updated.
+    ///
+    /// # Errors
+    ///
+    /// To abort the update.
+    fn before_update(&mut self) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    /// Called after the changed columns of the model have been updated.
+    ///
+    /// # Errors
+    ///
+    /// To abort the update.
+    fn after_update(&self) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    /// Called before the model is deleted.
+    ///
+    /// # Errors
+    ///
+    /// To abort the delete.
+    fn before_delete(&self) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+
+    /// Called after the model has been deleted.
+    ///
+    /// # Errors
+    ///
+    /// To abort the delete.
+    fn after_delete(&self) -> Result<(), sqlx::Error> {
+        Ok(())
+    }
+}
+
+/// The values of an [`ActiveModel`] as JSON, to record its writes in an
+/// [audit log](crate::audit).
+///
+/// An implementation recording nothing is generated for every active model. Use
+/// `#[sky_orm(audit)]` to record the writes of an entity instead, which requires the types of all
+/// its fields to implement [`Serialize`](serde::Serialize).
+pub trait AuditedModel {
+    /// Whether writes of this model are recorded.
+    const AUDITED: bool = false;
+
+    /// The values of all columns that are not [`NotSet`](ActiveModelValue::NotSet), paired with
+    /// the names of their columns in the database.
+    fn audit_values(&self) -> Vec<(&'static str, serde_json::Value)> {
+        vec![]
+    }
+}
+
+pub trait ActiveModel: ActiveModelHooks + AuditedModel {
     type Model: Model;
+
+    /// The values of all columns that are not [`NotSet`](ActiveModelValue::NotSet), paired with
+    /// the names of their columns in the database.
+    fn values(&self) -> Vec<ColumnValue<ModelDatabase<Self::Model>>>;
+
+    /// The values of all columns that are [`Set`](ActiveModelValue::Set), i.e. that have changed
+    /// since the model was loaded, paired with the names of their columns in the database.
+    fn changed_values(&self) -> Vec<ColumnValue<ModelDatabase<Self::Model>>>;
+
+    /// Set the columns that have a value in `patch`, leaving all others untouched.
+    fn set_from<P>(&mut self, patch: P)
+    where
+        P: Patch<ActiveModel = Self>,
+        Self: Sized,
+    {
+        patch.apply_to(self);
+    }
+}
+
+/// New values for some of the columns of an [`ActiveModel`], e.g. from the body of an HTTP `PATCH`
+/// request. Implemented by the patch struct generated with `#[sky_orm(patch)]`.
+pub trait Patch {
+    type ActiveModel: ActiveModel;
+
+    /// Set the columns that have a value in this patch on `model`, leaving all others untouched.
+    fn apply_to(self, model: &mut Self::ActiveModel);
+}
+
+/// Deserialize a field of a patch struct, wrapping any value that is present in [`Some`]. Together
+/// with `#[serde(default)]`, this keeps an explicit `null` for a nullable column (`Some(None)`)
+/// apart from a missing one (`None`).
+#[doc(hidden)]
+pub fn deserialize_patch_field<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 // TODO: Restrict column to entity somehow?
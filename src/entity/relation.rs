@@ -1,11 +1,31 @@
+#[cfg(feature = "exec")]
+pub mod keyed;
+#[cfg(feature = "exec")]
+pub mod nested;
+#[cfg(feature = "exec")]
+pub mod tree;
+
 use sealed::Sealed;
-use sqlx::{Connection, Database, Executor, IntoArguments, Result};
+use std::marker::PhantomData;
+#[cfg(feature = "exec")]
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
 
-use crate::entity::model::{GetColumn, Model};
+#[cfg(feature = "exec")]
+use sqlx::{Connection, Database, Executor, IntoArguments};
+
+#[cfg(feature = "exec")]
+use crate::{
+    Error, Result,
+    entity::model::{GetColumn, Model},
+    query::max_bind_parameters,
+};
 
 use super::{
-    Entity,
-    column::{Column, ComparableColumn},
+    Entity, HasPrimaryKey,
+    column::{Column, ColumnName, ColumnTuple, ComparableColumn},
 };
 
 /// A one-to-one relation.
@@ -50,27 +70,79 @@ pub trait InverseRelation: Sealed {
     type ForwardEquivalent: Relation;
 }
 
+/// The type of the primary key of entity `E`.
+pub type PrimaryKeyType<E> = <<E as HasPrimaryKey>::PrimaryKeyColumn as Column>::Type;
+
+/// The type of a foreign key column referring to a key of type `K`, i.e. `K` itself, or an
+/// [`Option`] of it for nullable foreign keys.
+pub trait ForeignKey<K>: Sized {
+    /// The referenced key, or [`None`] if the foreign key is `NULL`.
+    fn key(&self) -> Option<&K>;
+
+    /// A value referring to `key`.
+    fn from_key(key: K) -> Self;
+}
+
+impl<K> ForeignKey<K> for K {
+    fn key(&self) -> Option<&K> {
+        Some(self)
+    }
+
+    fn from_key(key: K) -> Self {
+        key
+    }
+}
+
+impl<K> ForeignKey<K> for Option<K> {
+    fn key(&self) -> Option<&K> {
+        self.as_ref()
+    }
+
+    fn from_key(key: K) -> Self {
+        Some(key)
+    }
+}
+
 /// The owning side (= the side with the foreign key stored in its table) of a database relation.
 /// `C` is the column holding the foreign key to the other entity's primary key.
 ///
-/// Implementing this trait will automatically implement [`InverseRelated`] for the other side.
+/// The foreign key may be nullable, see [`ForeignKey`]. Implementing this trait will automatically
+/// implement [`InverseRelated`] for the other side.
 pub trait Related<R, C>: Entity
+where
+    R: HasPrimaryKey<Database = Self::Database>,
+    C: ComparableColumn<Entity = Self, Type: ForeignKey<PrimaryKeyType<R>>>,
+{
+    /// The relation type, i.e. how many other entities are expected to be on the other side.
+    type RelationType: Relation;
+}
+
+/// The owning side of a database relation backed by a composite foreign key.
+///
+/// This is also used for foreign keys that reference columns other than the primary key of `R`.
+/// `C` is a [`ColumnTuple`] holding the foreign key columns on this entity. Queries filter by such
+/// relations with [`where_composite_relation`](crate::query::select::Select::where_composite_relation)
+/// and [`where_inverse_composite_relation`](crate::query::select::Select::where_inverse_composite_relation).
+pub trait CompositeRelated<R, C>: Entity
 where
     R: Entity<Database = Self::Database>,
-    C: ComparableColumn<Entity = Self, Type = <R::PrimaryKeyColumn as Column>::Type>,
+    C: ColumnTuple<Entity = Self>,
 {
     /// The relation type, i.e. how many other entities are expected to be on the other side.
     type RelationType: Relation;
+
+    /// The columns on `R` referenced by `C`, in the same order.
+    type ReferencedColumns: ColumnTuple<Entity = R, Type = C::Type>;
 }
 
 /// The non-owning or inverse side of a database relation.
 /// `C` is the column on the other entity holding the foreign key to this entity's primary key.
 ///
 /// This trait is auto implemented for the opposing sides whenever [`Related`] is implemented.
-pub trait InverseRelated<R, C>: Entity
+pub trait InverseRelated<R, C>: HasPrimaryKey
 where
     R: Entity<Database = Self::Database>,
-    C: ComparableColumn<Entity = R, Type = <Self::PrimaryKeyColumn as Column>::Type>,
+    C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<Self>>>,
 {
     /// The relation type, i.e. how many other entities are expected to be on the other side.
     ///
@@ -82,99 +154,192 @@ where
 impl<E, R, C> InverseRelated<E, C> for R
 where
     E: Related<R, C, Database = R::Database>,
-    R: Entity,
-    C: ComparableColumn<Entity = E, Type = <Self::PrimaryKeyColumn as Column>::Type>,
+    R: HasPrimaryKey,
+    C: ComparableColumn<Entity = E, Type: ForeignKey<PrimaryKeyType<Self>>>,
 {
     type InverseRelationType = <E::RelationType as Relation>::InverseEquivalent;
 }
 
+/// A hop from the owning side of a relation to the other side, through foreign key column `C`.
+pub struct Forward<C>(PhantomData<C>);
+
+/// A hop from the non-owning side of a relation to the owning side, through foreign key column `C`
+/// on the other entity.
+pub struct Inverse<C>(PhantomData<C>);
+
+/// An entity that can be joined with `R` through the relation of hop `H`, which is either
+/// [`Forward`] or [`Inverse`].
+///
+/// This trait is auto implemented for both sides whenever [`Related`] is implemented.
+pub trait Joinable<R, H>: Entity
+where
+    R: Entity<Database = Self::Database>,
+{
+    /// The columns on this entity and `R` that are equal for related entries.
+    fn join_columns() -> (ColumnName, ColumnName);
+}
+
+impl<E, R, C> Joinable<R, Forward<C>> for E
+where
+    E: Related<R, C>,
+    R: HasPrimaryKey<Database = E::Database>,
+    C: ComparableColumn<Entity = E, Type: ForeignKey<PrimaryKeyType<R>>>,
+{
+    fn join_columns() -> (ColumnName, ColumnName) {
+        (
+            C::full_column_name(),
+            R::PrimaryKeyColumn::full_column_name(),
+        )
+    }
+}
+
+impl<E, R, C> Joinable<R, Inverse<C>> for E
+where
+    R: Related<E, C, Database = E::Database>,
+    E: HasPrimaryKey,
+    C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<E>>>,
+{
+    fn join_columns() -> (ColumnName, ColumnName) {
+        (
+            E::PrimaryKeyColumn::full_column_name(),
+            C::full_column_name(),
+        )
+    }
+}
+
+/// Loads the entries that models refer to via their foreign key column `C`.
+///
+/// For a slice of models, the results are in the order of the models. A `Vec` of models is
+/// consumed instead, pairing each model with its results, so that they can't be zipped back out
+/// of order by accident.
+#[cfg(feature = "exec")]
 pub trait LoadRelation<T, C, R, O>
 where
-    T: Model + GetColumn<<T::Entity as Entity>::PrimaryKeyColumn> + 'static,
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
     R: Related<T::Entity, C> + Entity<Database = <T::Entity as Entity>::Database> + Send + 'static,
     R::Model: GetColumn<C> + Clone,
-    C: Column
-        + ComparableColumn<
-            Entity = R,
-            Type = <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type,
-        > + 'static,
-    T::Entity: Entity<Model = T>,
-    <T::Entity as Entity>::PrimaryKeyColumn: Clone,
-    <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type: PartialEq,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey<Model = T>,
+    PrimaryKeyType<T::Entity>: PartialEq,
 {
     fn load_relation<'c, Conn>(self, connection: &'c mut Conn) -> impl Future<Output = Result<O>>
     where
         Conn: Connection<Database = R::Database>,
-        &'c mut Conn: Executor<'c, Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
         for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c;
+
+    /// Like [`load_relation`](Self::load_relation), but selecting the relation by its foreign key
+    /// column `via`, for entities that are related to `T` more than once.
+    fn load_relation_via<'c, Conn>(
+        self,
+        _via: C,
+        connection: &'c mut Conn,
+    ) -> impl Future<Output = Result<O>>
+    where
+        Self: Sized,
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
+    {
+        self.load_relation(connection)
+    }
 }
 
 // TODO: add non-nullable variants
 
+#[cfg(feature = "exec")]
 impl<T, C, R> LoadRelation<T, C, R, Vec<Option<T>>> for &[R::Model]
 where
-    T: Model + GetColumn<<T::Entity as Entity>::PrimaryKeyColumn> + Clone + 'static,
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + Clone + 'static,
     R: Related<T::Entity, C> + Entity<Database = <T::Entity as Entity>::Database> + Send + 'static,
     R::Model: GetColumn<C> + Clone,
-    C: Column
-        + ComparableColumn<
-            Entity = R,
-            Type = <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type,
-        > + 'static,
-    T::Entity: Entity<Model = T>,
-    <T::Entity as Entity>::PrimaryKeyColumn: Clone,
-    <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type: PartialEq,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey<Model = T>,
+    PrimaryKeyType<T::Entity>: Eq + Hash,
 {
     async fn load_relation<'c, Conn>(self, connection: &'c mut Conn) -> Result<Vec<Option<T>>>
     where
         Conn: Connection<Database = R::Database>,
-        &'c mut Conn: Executor<'c, Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
         for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
     {
-        let results = <T::Entity as Entity>::find()
-            .filter(<T::Entity as Entity>::PrimaryKeyColumn::is_in(
-                &self.iter().map(|e| e.get().clone()).collect::<Vec<_>>(),
-            ))
-            .all(connection)
-            .await?;
+        // Models whose foreign key is `NULL` are not related to anything, so they are left out of
+        // the query.
+        let results =
+            find_in_chunks::<T::Entity, <T::Entity as HasPrimaryKey>::PrimaryKeyColumn, _, _>(
+                self.iter().filter_map(|e| e.get().key().cloned()),
+                connection,
+            )
+            .await?
+            .into_iter()
+            .map(|r| (r.get().clone(), r))
+            .collect::<HashMap<_, _>>();
 
         Ok(self
             .iter()
-            .map(|e| results.iter().find(|r| r.get() == e.get()).cloned())
+            .map(|e| e.get().key().and_then(|key| results.get(key).cloned()))
             .collect())
     }
 }
 
+/// Loads the relation for every model, pairing each model with its related entry.
+#[cfg(feature = "exec")]
+impl<T, C, R> LoadRelation<T, C, R, Vec<(R::Model, Option<T>)>> for Vec<R::Model>
+where
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + Clone + 'static,
+    R: Related<T::Entity, C> + Entity<Database = <T::Entity as Entity>::Database> + Send + 'static,
+    R::Model: GetColumn<C> + Clone,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey<Model = T>,
+    PrimaryKeyType<T::Entity>: Eq + Hash,
+{
+    async fn load_relation<'c, Conn>(
+        self,
+        connection: &'c mut Conn,
+    ) -> Result<Vec<(R::Model, Option<T>)>>
+    where
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
+    {
+        let related =
+            LoadRelation::<T, C, R, Vec<Option<T>>>::load_relation(self.as_slice(), connection)
+                .await?;
+
+        Ok(self.into_iter().zip(related).collect())
+    }
+}
+
+#[cfg(feature = "exec")]
 impl<T, C, R> LoadRelation<T, C, R, Option<T>> for &R::Model
 where
-    T: Model + GetColumn<<T::Entity as Entity>::PrimaryKeyColumn> + Clone + 'static,
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + Clone + 'static,
     R: Related<T::Entity, C> + Entity<Database = <T::Entity as Entity>::Database> + Send + 'static,
     R::Model: GetColumn<C> + Clone,
-    C: Column
-        + ComparableColumn<
-            Entity = R,
-            Type = <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type,
-        > + 'static,
-    T::Entity: Entity<Model = T>,
-    <T::Entity as Entity>::PrimaryKeyColumn: Clone,
-    <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type: PartialEq,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey<Model = T>,
+    PrimaryKeyType<T::Entity>: PartialEq,
 {
     async fn load_relation<'c, Conn>(self, connection: &'c mut Conn) -> Result<Option<T>>
     where
         Conn: Connection<Database = R::Database>,
-        &'c mut Conn: Executor<'c, Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
         for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
     {
+        let Some(key) = self.get().key() else {
+            return Ok(None);
+        };
+
         let result = <T::Entity as Entity>::find()
             .filter(
-                <<T::Entity as Entity>::PrimaryKeyColumn as ComparableColumn>::eq(
-                    self.get().clone(),
+                <<T::Entity as HasPrimaryKey>::PrimaryKeyColumn as ComparableColumn>::eq(
+                    key.clone(),
                 ),
             )
             .one(connection)
             .await;
 
-        if matches!(result, Err(sqlx::Error::RowNotFound)) {
+        if matches!(result, Err(Error::NotFound { .. })) {
             Ok(None)
         } else {
             Ok(Some(result?))
@@ -182,132 +347,197 @@ where
     }
 }
 
+/// Loads the entries that refer to models via their foreign key column `C`, the inverse of
+/// [`LoadRelation`]. Slices and `Vec`s of models are loaded like for [`LoadRelation`].
+#[cfg(feature = "exec")]
 pub trait LoadInverse<T, C, R, O>
 where
-    T: Model + GetColumn<<T::Entity as Entity>::PrimaryKeyColumn> + 'static,
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
     R: Related<T::Entity, C> + Entity<Database = <T::Entity as Entity>::Database> + Send + 'static,
     R::Model: GetColumn<C> + Clone,
-    C: Column
-        + ComparableColumn<
-            Entity = R,
-            Type = <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type,
-        > + 'static,
-    <T::Entity as Entity>::PrimaryKeyColumn: Clone,
-    <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type: PartialEq,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey,
+    PrimaryKeyType<T::Entity>: PartialEq,
 {
     fn load_inverse<'c, Conn>(self, connection: &'c mut Conn) -> impl Future<Output = Result<O>>
     where
         Conn: Connection<Database = R::Database>,
-        &'c mut Conn: Executor<'c, Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
         for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c;
+
+    /// Like [`load_inverse`](Self::load_inverse), but selecting the relation by its foreign key
+    /// column `via`, for entities that are related to `T` more than once.
+    fn load_inverse_via<'c, Conn>(
+        self,
+        _via: C,
+        connection: &'c mut Conn,
+    ) -> impl Future<Output = Result<O>>
+    where
+        Self: Sized,
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
+    {
+        self.load_inverse(connection)
+    }
 }
 
+#[cfg(feature = "exec")]
 impl<T, C, R> LoadInverse<T, C, R, Vec<Option<R::Model>>> for &[T]
 where
-    T: Model + GetColumn<<T::Entity as Entity>::PrimaryKeyColumn> + 'static,
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
     R: Related<T::Entity, C, RelationType = OneToOne>
         + Entity<Database = <T::Entity as Entity>::Database>
         + Send
         + 'static,
     R::Model: GetColumn<C> + Clone,
-    C: Column
-        + ComparableColumn<
-            Entity = R,
-            Type = <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type,
-        > + 'static,
-    <T::Entity as Entity>::PrimaryKeyColumn: Clone,
-    <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type: PartialEq,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey,
+    PrimaryKeyType<T::Entity>: Eq + Hash,
 {
     async fn load_inverse<'c, Conn>(self, connection: &'c mut Conn) -> Result<Vec<Option<R::Model>>>
     where
         Conn: Connection<Database = R::Database>,
-        &'c mut Conn: Executor<'c, Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
         for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
     {
-        let results = R::find()
-            .filter(C::is_in(
-                &self.iter().map(|e| e.get().clone()).collect::<Vec<_>>(),
-            ))
-            .all(connection)
-            .await?;
+        let results =
+            find_in_chunks::<R, C, _, _>(self.iter().map(|e| e.get().clone()), connection)
+                .await?
+                .into_iter()
+                .filter_map(|r| Some((GetColumn::<C>::get(&r).key()?.clone(), r)))
+                .collect::<HashMap<_, _>>();
 
-        Ok(self
-            .iter()
-            .map(|e| results.iter().find(|r| r.get() == e.get()).cloned())
-            .collect())
+        Ok(self.iter().map(|e| results.get(e.get()).cloned()).collect())
     }
 }
 
+#[cfg(feature = "exec")]
 impl<T, C, R> LoadInverse<T, C, R, Vec<Vec<R::Model>>> for &[T]
 where
-    T: Model + GetColumn<<T::Entity as Entity>::PrimaryKeyColumn> + 'static,
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
     R: Related<T::Entity, C, RelationType = ManyToOne>
         + Entity<Database = <T::Entity as Entity>::Database>
         + Send
         + 'static,
     R::Model: GetColumn<C> + Clone,
-    C: Column
-        + ComparableColumn<
-            Entity = R,
-            Type = <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type,
-        > + 'static,
-    <T::Entity as Entity>::PrimaryKeyColumn: Clone,
-    <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type: PartialEq,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey,
+    PrimaryKeyType<T::Entity>: Eq + Hash,
 {
     async fn load_inverse<'c, Conn>(self, connection: &'c mut Conn) -> Result<Vec<Vec<R::Model>>>
     where
         Conn: Connection<Database = R::Database>,
-        &'c mut Conn: Executor<'c, Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
         for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
     {
-        let results = R::find()
-            .filter(C::is_in(
-                &self.iter().map(|e| e.get().clone()).collect::<Vec<_>>(),
-            ))
-            .all(connection)
-            .await?;
+        let results =
+            find_in_chunks::<R, C, _, _>(self.iter().map(|e| e.get().clone()), connection)
+                .await?
+                .into_iter()
+                .fold(HashMap::<_, Vec<_>>::new(), |mut acc, r| {
+                    if let Some(key) = GetColumn::<C>::get(&r).key() {
+                        acc.entry(key.clone()).or_default().push(r);
+                    }
+                    acc
+                });
 
         Ok(self
             .iter()
-            .map(|e| {
-                results
-                    .iter()
-                    .filter(|r| r.get() == e.get())
-                    .cloned()
-                    .collect()
-            })
+            .map(|e| results.get(e.get()).cloned().unwrap_or_default())
             .collect())
     }
 }
 
+/// Loads the inverse relation for every model, pairing each model with its related entries.
+#[cfg(feature = "exec")]
+impl<T, C, R> LoadInverse<T, C, R, Vec<(T, Option<R::Model>)>> for Vec<T>
+where
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
+    R: Related<T::Entity, C, RelationType = OneToOne>
+        + Entity<Database = <T::Entity as Entity>::Database>
+        + Send
+        + 'static,
+    R::Model: GetColumn<C> + Clone,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey,
+    PrimaryKeyType<T::Entity>: Eq + Hash,
+{
+    async fn load_inverse<'c, Conn>(
+        self,
+        connection: &'c mut Conn,
+    ) -> Result<Vec<(T, Option<R::Model>)>>
+    where
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
+    {
+        let related = LoadInverse::<T, C, R, Vec<Option<R::Model>>>::load_inverse(
+            self.as_slice(),
+            connection,
+        )
+        .await?;
+
+        Ok(self.into_iter().zip(related).collect())
+    }
+}
+
+/// Loads the inverse relation for every model, pairing each model with its related entries.
+#[cfg(feature = "exec")]
+impl<T, C, R> LoadInverse<T, C, R, Vec<(T, Vec<R::Model>)>> for Vec<T>
+where
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
+    R: Related<T::Entity, C, RelationType = ManyToOne>
+        + Entity<Database = <T::Entity as Entity>::Database>
+        + Send
+        + 'static,
+    R::Model: GetColumn<C> + Clone,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey,
+    PrimaryKeyType<T::Entity>: Eq + Hash,
+{
+    async fn load_inverse<'c, Conn>(
+        self,
+        connection: &'c mut Conn,
+    ) -> Result<Vec<(T, Vec<R::Model>)>>
+    where
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
+    {
+        let related =
+            LoadInverse::<T, C, R, Vec<Vec<R::Model>>>::load_inverse(self.as_slice(), connection)
+                .await?;
+
+        Ok(self.into_iter().zip(related).collect())
+    }
+}
+
+#[cfg(feature = "exec")]
 impl<T, C, R> LoadInverse<T, C, R, Option<R::Model>> for &T
 where
-    T: Model + GetColumn<<T::Entity as Entity>::PrimaryKeyColumn> + 'static,
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
     R: Related<T::Entity, C, RelationType = OneToOne>
         + Entity<Database = <T::Entity as Entity>::Database>
         + Send
         + 'static,
     R::Model: GetColumn<C> + Clone,
-    C: Column
-        + ComparableColumn<
-            Entity = R,
-            Type = <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type,
-        > + 'static,
-    <T::Entity as Entity>::PrimaryKeyColumn: Clone,
-    <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type: PartialEq,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey,
+    PrimaryKeyType<T::Entity>: PartialEq,
 {
     async fn load_inverse<'c, Conn>(self, connection: &'c mut Conn) -> Result<Option<R::Model>>
     where
         Conn: Connection<Database = R::Database>,
-        &'c mut Conn: Executor<'c, Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
         for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
     {
         let result = R::find()
-            .filter(C::eq(self.get().clone()))
+            .filter(C::eq(ForeignKey::from_key(self.get().clone())))
             .one(connection)
             .await;
 
-        if matches!(result, Err(sqlx::Error::RowNotFound)) {
+        if matches!(result, Err(Error::NotFound { .. })) {
             Ok(None)
         } else {
             Ok(Some(result?))
@@ -315,35 +545,89 @@ where
     }
 }
 
+#[cfg(feature = "exec")]
 impl<T, C, R> LoadInverse<T, C, R, Vec<R::Model>> for &T
 where
-    T: Model + GetColumn<<T::Entity as Entity>::PrimaryKeyColumn> + 'static,
+    T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
     R: Related<T::Entity, C, RelationType = ManyToOne>
         + Entity<Database = <T::Entity as Entity>::Database>
         + Send
         + 'static,
     R::Model: GetColumn<C> + Clone,
-    C: Column
-        + ComparableColumn<
-            Entity = R,
-            Type = <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type,
-        > + 'static,
-    <T::Entity as Entity>::PrimaryKeyColumn: Clone,
-    <<T::Entity as Entity>::PrimaryKeyColumn as Column>::Type: PartialEq,
+    C: Column + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>> + 'static,
+    T::Entity: HasPrimaryKey,
+    PrimaryKeyType<T::Entity>: PartialEq,
 {
     async fn load_inverse<'c, Conn>(self, connection: &'c mut Conn) -> Result<Vec<R::Model>>
     where
         Conn: Connection<Database = R::Database>,
-        &'c mut Conn: Executor<'c, Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
         for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
     {
         R::find()
-            .filter(C::eq(self.get().clone()))
+            .filter(C::eq(ForeignKey::from_key(self.get().clone())))
             .all(connection)
             .await
     }
 }
 
+/// The maximum number of keys bound in a single `IN (...)` list when loading relations for a batch
+/// of models. Larger batches are split into multiple queries, to stay below the bind parameter
+/// limits of the supported databases.
+#[cfg(feature = "exec")]
+const LOAD_CHUNK_SIZE: usize = 10_000;
+
+/// The number of keys bound per query when loading relations on database `DB`, i.e.
+/// [`LOAD_CHUNK_SIZE`], or less if it exceeds the [bind parameter limit](max_bind_parameters) of
+/// `DB`. A tenth of the limit is left to the parameters of the implicit filter.
+#[cfg(feature = "exec")]
+fn load_chunk_size<DB>() -> usize
+where
+    DB: Database,
+{
+    LOAD_CHUNK_SIZE.min(max_bind_parameters::<DB>() / 10 * 9)
+}
+
+/// Find all entries of `E` whose column `C` refers to any of `keys`, querying at most
+/// [`load_chunk_size`] keys at a time. Duplicate keys are only queried once.
+#[cfg(feature = "exec")]
+async fn find_in_chunks<'c, E, C, K, Conn>(
+    keys: impl IntoIterator<Item = K>,
+    connection: &'c mut Conn,
+) -> Result<Vec<E::Model>>
+where
+    E: Entity + 'static,
+    C: ComparableColumn<Entity = E, Type: ForeignKey<K>> + 'static,
+    K: Eq + Hash,
+    Conn: Connection<Database = E::Database>,
+    for<'e> &'e mut Conn: Executor<'e, Database = E::Database>,
+    for<'q> <E::Database as Database>::Arguments<'q>: IntoArguments<'q, E::Database> + 'c,
+{
+    let mut keys = keys
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+
+    let chunk_size = load_chunk_size::<E::Database>();
+    let mut results = Vec::with_capacity(keys.len());
+
+    // The keys are moved into the queries chunk by chunk, rather than cloned once more.
+    while !keys.is_empty() {
+        let rest = keys.split_off(keys.len().min(chunk_size));
+        let chunk = std::mem::replace(&mut keys, rest);
+
+        results.extend(
+            E::find()
+                .filter(C::is_in_values(chunk.into_iter().map(ForeignKey::from_key)))
+                .all(&mut *connection)
+                .await?,
+        );
+    }
+
+    Ok(results)
+}
+
 mod sealed {
     use super::{ManyToOne, OneToMany, OneToOne};
 
@@ -0,0 +1,193 @@
+//! Loading entries for a batch of keys, grouped by key, which is the shape dataloaders expect.
+//!
+//! With the `async-graphql` feature, [`KeyedLoader`] and [`GroupedLoader`] implement the `Loader`
+//! trait of `async-graphql` with these functions, e.g. to load the posts of a batch of authors:
+//!
+//! ```ignore
+//! let loader = DataLoader::new(GroupedLoader::<post::columns::AuthorId>::new(pool), tokio::spawn);
+//!
+//! let posts: Option<Vec<post::Model>> = loader.load_one(author.id).await?;
+//! ```
+//!
+//! The models of `model!` derive `SimpleObject` with the feature, and the enums generated with them
+//! `Enum`. Hand-written models have to derive them on their own.
+//!
+//! Like the implementations of [`LoadRelation`](super::LoadRelation) and
+//! [`LoadInverse`](super::LoadInverse), every batch is loaded with as few queries as possible.
+
+use std::{collections::HashMap, hash::Hash};
+#[cfg(feature = "async-graphql")]
+use std::{marker::PhantomData, sync::Arc};
+
+#[cfg(feature = "async-graphql")]
+use async_graphql::dataloader::Loader;
+#[cfg(feature = "async-graphql")]
+use sqlx::Pool;
+use sqlx::{Connection, Database, Executor, IntoArguments};
+
+use super::find_in_chunks;
+use crate::{
+    Result,
+    entity::{Entity, column::ComparableColumn, model::GetColumn},
+};
+
+/// Load the entries of `C::Entity` whose column `C` holds any of `keys`, by the value of `C`.
+///
+/// Meant for unique columns, e.g. the primary key: if more than one entry has the same value, only
+/// one of them is returned. Keys without an entry are left out.
+///
+/// # Errors
+///
+/// If there's been a problem communicating with the database. See [`Error`](crate::Error) for more
+/// information.
+pub async fn load_by<'c, C, Conn>(
+    keys: impl IntoIterator<Item = C::Type>,
+    connection: &'c mut Conn,
+) -> Result<HashMap<C::Type, <C::Entity as Entity>::Model>>
+where
+    C: ComparableColumn + 'static,
+    C::Type: Eq + Hash,
+    C::Entity: 'static,
+    <C::Entity as Entity>::Model: GetColumn<C>,
+    Conn: Connection<Database = <C::Entity as Entity>::Database>,
+    for<'e> &'e mut Conn: Executor<'e, Database = <C::Entity as Entity>::Database>,
+    for<'q> <<C::Entity as Entity>::Database as Database>::Arguments<'q>:
+        IntoArguments<'q, <C::Entity as Entity>::Database> + 'c,
+{
+    Ok(find_in_chunks::<C::Entity, C, _, _>(keys, connection)
+        .await?
+        .into_iter()
+        .map(|e| (e.get().clone(), e))
+        .collect())
+}
+
+/// Load the entries of `C::Entity` whose column `C` holds any of `keys`, grouped by the value of
+/// `C`, e.g. all children of a batch of parents by their foreign key. Keys without entries are
+/// left out.
+///
+/// # Errors
+///
+/// If there's been a problem communicating with the database. See [`Error`](crate::Error) for more
+/// information.
+pub async fn load_grouped_by<'c, C, Conn>(
+    keys: impl IntoIterator<Item = C::Type>,
+    connection: &'c mut Conn,
+) -> Result<HashMap<C::Type, Vec<<C::Entity as Entity>::Model>>>
+where
+    C: ComparableColumn + 'static,
+    C::Type: Eq + Hash,
+    C::Entity: 'static,
+    <C::Entity as Entity>::Model: GetColumn<C>,
+    Conn: Connection<Database = <C::Entity as Entity>::Database>,
+    for<'e> &'e mut Conn: Executor<'e, Database = <C::Entity as Entity>::Database>,
+    for<'q> <<C::Entity as Entity>::Database as Database>::Arguments<'q>:
+        IntoArguments<'q, <C::Entity as Entity>::Database> + 'c,
+{
+    Ok(find_in_chunks::<C::Entity, C, _, _>(keys, connection)
+        .await?
+        .into_iter()
+        .fold(HashMap::<_, Vec<_>>::new(), |mut acc, e| {
+            acc.entry(e.get().clone()).or_default().push(e);
+            acc
+        }))
+}
+
+/// A dataloader of the entries of `C::Entity` by the value of their unique column `C`, e.g. the
+/// primary key, see [`load_by`].
+#[cfg(feature = "async-graphql")]
+pub struct KeyedLoader<C>
+where
+    C: ComparableColumn,
+{
+    pool: Pool<<C::Entity as Entity>::Database>,
+    column: PhantomData<fn() -> C>,
+}
+
+#[cfg(feature = "async-graphql")]
+impl<C> KeyedLoader<C>
+where
+    C: ComparableColumn,
+{
+    /// Create a loader running its queries on connections of `pool`.
+    #[must_use]
+    pub const fn new(pool: Pool<<C::Entity as Entity>::Database>) -> Self {
+        Self {
+            pool,
+            column: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "async-graphql")]
+impl<C> Loader<C::Type> for KeyedLoader<C>
+where
+    C: ComparableColumn + 'static,
+    C::Type: Eq + Hash + Send + Sync + Clone + 'static,
+    C::Entity: 'static,
+    <C::Entity as Entity>::Model: GetColumn<C> + Clone + 'static,
+    for<'e> &'e mut <<C::Entity as Entity>::Database as Database>::Connection:
+        Executor<'e, Database = <C::Entity as Entity>::Database>,
+    for<'q> <<C::Entity as Entity>::Database as Database>::Arguments<'q>:
+        IntoArguments<'q, <C::Entity as Entity>::Database> + 'static,
+{
+    type Value = <C::Entity as Entity>::Model;
+    type Error = Arc<crate::Error>;
+
+    async fn load(&self, keys: &[C::Type]) -> Result<HashMap<C::Type, Self::Value>, Self::Error> {
+        let mut connection = self.pool.acquire().await.map_err(|e| Arc::new(e.into()))?;
+
+        load_by::<C, _>(keys.iter().cloned(), &mut *connection)
+            .await
+            .map_err(Arc::new)
+    }
+}
+
+/// A dataloader of the entries of `C::Entity` grouped by the value of their column `C`, e.g. all
+/// children of a batch of parents by their foreign key, see [`load_grouped_by`].
+#[cfg(feature = "async-graphql")]
+pub struct GroupedLoader<C>
+where
+    C: ComparableColumn,
+{
+    pool: Pool<<C::Entity as Entity>::Database>,
+    column: PhantomData<fn() -> C>,
+}
+
+#[cfg(feature = "async-graphql")]
+impl<C> GroupedLoader<C>
+where
+    C: ComparableColumn,
+{
+    /// Create a loader running its queries on connections of `pool`.
+    #[must_use]
+    pub const fn new(pool: Pool<<C::Entity as Entity>::Database>) -> Self {
+        Self {
+            pool,
+            column: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "async-graphql")]
+impl<C> Loader<C::Type> for GroupedLoader<C>
+where
+    C: ComparableColumn + 'static,
+    C::Type: Eq + Hash + Send + Sync + Clone + 'static,
+    C::Entity: 'static,
+    <C::Entity as Entity>::Model: GetColumn<C> + Clone + 'static,
+    for<'e> &'e mut <<C::Entity as Entity>::Database as Database>::Connection:
+        Executor<'e, Database = <C::Entity as Entity>::Database>,
+    for<'q> <<C::Entity as Entity>::Database as Database>::Arguments<'q>:
+        IntoArguments<'q, <C::Entity as Entity>::Database> + 'static,
+{
+    type Value = Vec<<C::Entity as Entity>::Model>;
+    type Error = Arc<crate::Error>;
+
+    async fn load(&self, keys: &[C::Type]) -> Result<HashMap<C::Type, Self::Value>, Self::Error> {
+        let mut connection = self.pool.acquire().await.map_err(|e| Arc::new(e.into()))?;
+
+        load_grouped_by::<C, _>(keys.iter().cloned(), &mut *connection)
+            .await
+            .map_err(Arc::new)
+    }
+}
@@ -0,0 +1,177 @@
+//! Eager loading of relations across more than one hop, e.g. orders, their customers and the
+//! customers' addresses, using one batched query per hop.
+//!
+//! Each hop takes the pairs produced by the previous one (see the implementations of
+//! [`LoadRelation`] and [`LoadInverse`] for [`Vec`]) and loads the next relation for the models on
+//! their right-hand side:
+//!
+//! ```ignore
+//! let orders: Vec<(order::Model, Option<(customer::Model, Option<address::Model>)>)> =
+//!     LoadRelation::<customer::Model, order::columns::CustomerId, order::Entity, _>::load_relation(orders, &mut conn)
+//!         .await?
+//!         .then_load_relation::<address::Model, customer::columns::AddressId>(&mut conn)
+//!         .await?;
+//! ```
+
+use std::hash::Hash;
+
+use sqlx::{Connection, Database, Executor, IntoArguments};
+
+use super::{ForeignKey, LoadInverse, LoadRelation, ManyToOne, PrimaryKeyType, Related};
+use crate::{
+    Result,
+    entity::{
+        Entity, HasPrimaryKey,
+        column::{Column, ComparableColumn},
+        model::{GetColumn, Model},
+    },
+};
+
+/// Models of type `A`, each paired with its related `B` (if any), which is in turn paired with its
+/// related `T` (if any).
+pub type NestedRelations<A, B, T> = Vec<(A, Option<(B, Option<T>)>)>;
+
+/// Models of type `A`, each paired with its related `T`s, which are in turn paired with their
+/// related `R`s.
+pub type NestedInverses<A, T, R> = Vec<(A, Vec<(T, Vec<R>)>)>;
+
+/// Load the relation of the models loaded by a previous hop, which are owning side of it.
+pub trait ThenLoadRelation<A, R>: Sized
+where
+    R: Entity,
+{
+    /// Load the entries of `T::Entity` referred to by column `C` of the models loaded by the
+    /// previous hop, nesting each of them next to its model.
+    fn then_load_relation<'c, T, C, Conn>(
+        self,
+        connection: &'c mut Conn,
+    ) -> impl Future<Output = Result<NestedRelations<A, R::Model, T>>>
+    where
+        T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + Clone + 'static,
+        R: Related<T::Entity, C>
+            + Entity<Database = <T::Entity as Entity>::Database>
+            + Send
+            + 'static,
+        R::Model: GetColumn<C> + Clone,
+        C: Column
+            + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>>
+            + 'static,
+        T::Entity: HasPrimaryKey<Model = T>,
+        PrimaryKeyType<T::Entity>: Eq + Hash,
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c;
+}
+
+impl<A, R> ThenLoadRelation<A, R> for Vec<(A, Option<R::Model>)>
+where
+    R: Entity,
+{
+    async fn then_load_relation<'c, T, C, Conn>(
+        self,
+        connection: &'c mut Conn,
+    ) -> Result<NestedRelations<A, R::Model, T>>
+    where
+        T: Model + GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + Clone + 'static,
+        R: Related<T::Entity, C>
+            + Entity<Database = <T::Entity as Entity>::Database>
+            + Send
+            + 'static,
+        R::Model: GetColumn<C> + Clone,
+        C: Column
+            + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>>
+            + 'static,
+        T::Entity: HasPrimaryKey<Model = T>,
+        PrimaryKeyType<T::Entity>: Eq + Hash,
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
+    {
+        let (parents, models): (Vec<_>, Vec<_>) = self.into_iter().unzip();
+        let present = models.iter().map(Option::is_some).collect::<Vec<_>>();
+
+        let mut loaded = LoadRelation::<T, C, R, Vec<(R::Model, Option<T>)>>::load_relation(
+            models.into_iter().flatten().collect::<Vec<_>>(),
+            connection,
+        )
+        .await?
+        .into_iter();
+
+        Ok(parents
+            .into_iter()
+            .zip(present)
+            .map(|(parent, present)| (parent, if present { loaded.next() } else { None }))
+            .collect())
+    }
+}
+
+/// Load the inverse relation of the models loaded by a previous hop, which are the non-owning side
+/// of it.
+pub trait ThenLoadInverse<A, T>: Sized
+where
+    T: Model,
+{
+    /// Load the entries of `R` referring to the models loaded by the previous hop through column
+    /// `C`, nesting them next to their model.
+    fn then_load_inverse<'c, R, C, Conn>(
+        self,
+        connection: &'c mut Conn,
+    ) -> impl Future<Output = Result<NestedInverses<A, T, R::Model>>>
+    where
+        T: GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
+        R: Related<T::Entity, C, RelationType = ManyToOne>
+            + Entity<Database = <T::Entity as Entity>::Database>
+            + Send
+            + 'static,
+        R::Model: GetColumn<C> + Clone,
+        C: Column
+            + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>>
+            + 'static,
+        T::Entity: HasPrimaryKey,
+        PrimaryKeyType<T::Entity>: Eq + Hash,
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c;
+}
+
+impl<A, T> ThenLoadInverse<A, T> for Vec<(A, Vec<T>)>
+where
+    T: Model,
+{
+    async fn then_load_inverse<'c, R, C, Conn>(
+        self,
+        connection: &'c mut Conn,
+    ) -> Result<NestedInverses<A, T, R::Model>>
+    where
+        T: GetColumn<<T::Entity as HasPrimaryKey>::PrimaryKeyColumn> + 'static,
+        R: Related<T::Entity, C, RelationType = ManyToOne>
+            + Entity<Database = <T::Entity as Entity>::Database>
+            + Send
+            + 'static,
+        R::Model: GetColumn<C> + Clone,
+        C: Column
+            + ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T::Entity>>>
+            + 'static,
+        T::Entity: HasPrimaryKey,
+        PrimaryKeyType<T::Entity>: Eq + Hash,
+        Conn: Connection<Database = R::Database>,
+        for<'e> &'e mut Conn: Executor<'e, Database = R::Database>,
+        for<'q> <R::Database as Database>::Arguments<'q>: IntoArguments<'q, R::Database> + 'c,
+    {
+        let (parents, models): (Vec<_>, Vec<_>) = self.into_iter().unzip();
+        let counts = models.iter().map(Vec::len).collect::<Vec<_>>();
+
+        let mut loaded = LoadInverse::<T, C, R, Vec<(T, Vec<R::Model>)>>::load_inverse(
+            models.into_iter().flatten().collect::<Vec<_>>(),
+            connection,
+        )
+        .await?
+        .into_iter();
+
+        Ok(parents
+            .into_iter()
+            .zip(counts)
+            .map(|(parent, count)| (parent, loaded.by_ref().take(count).collect()))
+            .collect())
+    }
+}
@@ -0,0 +1,130 @@
+//! Relations of an entity to itself, e.g. a `parent_id` column on categories, which arrange its
+//! entries in a tree.
+
+use futures::StreamExt;
+use sqlx::{Connection, Database, Executor, IntoArguments, QueryBuilder};
+
+use super::{ForeignKey, PrimaryKeyType};
+use crate::{
+    Result,
+    entity::{
+        Entity, HasPrimaryKey,
+        column::{Column, ComparableColumn},
+        model::{GetColumn, Model},
+    },
+    query::{PushToQuery, parse::ParseFromRow, select::push_aliased_columns},
+};
+
+/// An entity whose column `C` refers to the primary key of its parent entry of the same entity.
+pub trait SelfRelated<C>: HasPrimaryKey
+where
+    C: ComparableColumn<Entity = Self>,
+    C::Type: ForeignKey<PrimaryKeyType<Self>>,
+{
+}
+
+/// Traversal of the tree formed by [`SelfRelated`] entities.
+pub trait LoadTree<C>: Model
+where
+    C: ComparableColumn<Entity = Self::Entity>,
+{
+    /// Load the direct children of this entry.
+    fn load_children<'c, Conn>(
+        &self,
+        connection: &'c mut Conn,
+    ) -> impl Future<Output = Result<Vec<Self>>>
+    where
+        Conn: Connection<Database = <Self::Entity as Entity>::Database>,
+        &'c mut Conn: Executor<'c, Database = <Self::Entity as Entity>::Database>,
+        for<'q> <<Self::Entity as Entity>::Database as Database>::Arguments<'q>:
+            IntoArguments<'q, <Self::Entity as Entity>::Database> + 'c;
+
+    /// Load all ancestors of this entry via a recursive query, starting with its parent and
+    /// ending with the root of the tree.
+    ///
+    /// Note that the query does not terminate if the entries form a cycle.
+    fn load_ancestors<'c, Conn>(
+        &self,
+        connection: &'c mut Conn,
+    ) -> impl Future<Output = Result<Vec<Self>>>
+    where
+        Conn: Connection<Database = <Self::Entity as Entity>::Database>,
+        &'c mut Conn: Executor<'c, Database = <Self::Entity as Entity>::Database>,
+        for<'q> <<Self::Entity as Entity>::Database as Database>::Arguments<'q>:
+            IntoArguments<'q, <Self::Entity as Entity>::Database> + 'c;
+}
+
+impl<M, C> LoadTree<C> for M
+where
+    M: Model + GetColumn<<M::Entity as HasPrimaryKey>::PrimaryKeyColumn> + GetColumn<C> + 'static,
+    M::Entity: SelfRelated<C> + HasPrimaryKey<Model = M> + 'static,
+    C: ComparableColumn<Entity = M::Entity> + 'static,
+    C::Type: ForeignKey<PrimaryKeyType<M::Entity>> + PartialEq + 'static,
+{
+    async fn load_children<'c, Conn>(&self, connection: &'c mut Conn) -> Result<Vec<Self>>
+    where
+        Conn: Connection<Database = <Self::Entity as Entity>::Database>,
+        &'c mut Conn: Executor<'c, Database = <Self::Entity as Entity>::Database>,
+        for<'q> <<Self::Entity as Entity>::Database as Database>::Arguments<'q>:
+            IntoArguments<'q, <Self::Entity as Entity>::Database> + 'c,
+    {
+        let id = GetColumn::<<M::Entity as HasPrimaryKey>::PrimaryKeyColumn>::get(self).clone();
+
+        <M::Entity as Entity>::find()
+            .filter(C::eq(C::Type::from_key(id)))
+            .all(connection)
+            .await
+    }
+
+    async fn load_ancestors<'c, Conn>(&self, connection: &'c mut Conn) -> Result<Vec<Self>>
+    where
+        Conn: Connection<Database = <Self::Entity as Entity>::Database>,
+        &'c mut Conn: Executor<'c, Database = <Self::Entity as Entity>::Database>,
+        for<'q> <<Self::Entity as Entity>::Database as Database>::Arguments<'q>:
+            IntoArguments<'q, <Self::Entity as Entity>::Database> + 'c,
+    {
+        let Some(parent) = GetColumn::<C>::get(self).key().cloned() else {
+            return Ok(vec![]);
+        };
+
+        let table = <M::Entity as Entity>::TABLE_NAME;
+        let from = <M::Entity as Entity>::SCHEMA.map_or_else(
+            || format!("\"{table}\""),
+            |schema| format!("\"{schema}\".\"{table}\""),
+        );
+        let primary_key = <<M::Entity as HasPrimaryKey>::PrimaryKeyColumn as Column>::NAME;
+        let foreign_key = C::NAME;
+
+        let mut builder = QueryBuilder::new(format!(
+            "WITH RECURSIVE \"__ancestors\" AS (SELECT \"{table}\".*, 1 AS \"__depth\" FROM {from} AS \"{table}\" WHERE \"{table}\".\"{primary_key}\" = "
+        ));
+        builder.push_bind(parent);
+        builder.push(format!(
+            " UNION ALL SELECT \"{table}\".*, \"__ancestors\".\"__depth\" + 1 FROM {from} AS \"{table}\" JOIN \"__ancestors\" ON \"{table}\".\"{primary_key}\" = \"__ancestors\".\"{foreign_key}\") SELECT "
+        ));
+
+        push_aliased_columns::<M::Entity>(&mut builder, table);
+
+        builder.push(format_args!(" FROM \"__ancestors\" AS \"{table}\""));
+
+        // Ancestors outside of the scope are left out of the result.
+        if let Some(scope) = <M::Entity as Entity>::implicit_filter() {
+            builder.push(" WHERE ");
+            scope.push_to(&mut builder);
+        }
+
+        builder.push(format_args!(" ORDER BY \"{table}\".\"__depth\""));
+
+        let result = connection
+            .fetch(builder.build())
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(result
+            .iter()
+            .map(<M as ParseFromRow<<M::Entity as Entity>::Database>>::parse_from_row)
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+}
@@ -0,0 +1,330 @@
+//! The error type returned when running queries.
+
+use std::{fmt::Display, time::Duration};
+
+use sqlx::error::{DatabaseError, ErrorKind};
+
+use crate::entity::{Entity, column::Column};
+
+/// A result with the error type of this crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// An error returned when running queries, classifying the most common failures in the same way
+/// across all database backends.
+///
+/// Errors of sqlx are converted using [`From`], so that constraint violations and decoding
+/// failures can be matched on without inspecting driver-specific error codes. All other errors are
+/// kept as they are in [`Sqlx`](Self::Sqlx).
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A query expected to return a row returned none.
+    NotFound {
+        /// The table of the queried entity.
+        entity: &'static str,
+        /// The SQL of the query that returned no row.
+        filter: String,
+    },
+    /// A write violated a unique constraint, e.g. the primary key. See
+    /// [`unique_violation_on`](Self::unique_violation_on) to check which column it belongs to.
+    UniqueViolation {
+        /// The name of the violated constraint, if reported by the database.
+        constraint: Option<String>,
+        /// The constrained table, if reported by the database.
+        table: Option<String>,
+        /// The constrained columns, if reported by the database.
+        columns: Vec<String>,
+        source: sqlx::Error,
+    },
+    /// A write violated a foreign key constraint. See
+    /// [`foreign_key_violation_on`](Self::foreign_key_violation_on) to check which column it
+    /// belongs to.
+    ForeignKeyViolation {
+        /// The name of the violated constraint, if reported by the database.
+        constraint: Option<String>,
+        /// The constrained table, if reported by the database.
+        table: Option<String>,
+        /// The constrained columns, if reported by the database.
+        columns: Vec<String>,
+        source: sqlx::Error,
+    },
+    /// A query took longer than the timeout it was given, e.g. with
+    /// [`Select::timeout`](crate::query::select::Select::timeout).
+    Timeout {
+        /// The table of the queried entity.
+        entity: &'static str,
+        /// The timeout that was exceeded.
+        timeout: Duration,
+    },
+    /// A query run with [`Select::cached`](crate::query::select::Select::cached) has a different
+    /// number of parameters than the SQL cached under its key, i.e. the key does not determine the
+    /// SQL completely. See [`QueryCache`](crate::query::cache::QueryCache).
+    CachedQueryMismatch {
+        /// The number of parameters of the cached SQL.
+        expected: usize,
+        /// The number of parameters of the query.
+        actual: usize,
+    },
+    /// A column of a row could not be decoded, or was not part of the row at all.
+    Decode { column: String, source: sqlx::Error },
+    /// Any other error returned by sqlx.
+    Sqlx(sqlx::Error),
+}
+
+impl Error {
+    /// The underlying error of sqlx, or [`None`] for [`NotFound`](Self::NotFound),
+    /// [`Timeout`](Self::Timeout) and [`CachedQueryMismatch`](Self::CachedQueryMismatch).
+    #[must_use]
+    pub const fn as_sqlx(&self) -> Option<&sqlx::Error> {
+        match self {
+            Self::NotFound { .. } | Self::Timeout { .. } | Self::CachedQueryMismatch { .. } => None,
+            Self::UniqueViolation { source, .. }
+            | Self::ForeignKeyViolation { source, .. }
+            | Self::Decode { source, .. }
+            | Self::Sqlx(source) => Some(source),
+        }
+    }
+
+    /// The HTTP status code to respond with when a request fails with this error, i.e. 404 if
+    /// nothing was found, 409 for constraint violations, 504 for timeouts and 500 for anything else.
+    #[must_use]
+    pub const fn http_status(&self) -> u16 {
+        match self {
+            Self::NotFound { .. } => 404,
+            Self::UniqueViolation { .. } | Self::ForeignKeyViolation { .. } => 409,
+            Self::Timeout { .. } => 504,
+            Self::CachedQueryMismatch { .. } | Self::Decode { .. } | Self::Sqlx(_) => 500,
+        }
+    }
+
+    /// A json body to respond with when a request fails with this error, along with its
+    /// [`http_status`](Self::http_status). With the `axum` or `actix` feature, errors are turned
+    /// into such responses on their own, see [`web`](crate::web).
+    ///
+    /// The body names the kind of error, as well as the entity or columns it concerns where
+    /// known, e.g. `{"error": "unique_violation", "columns": ["email"]}`. It never contains SQL or
+    /// messages of the database, which could expose details of the schema.
+    #[must_use]
+    pub fn response_body(&self) -> serde_json::Value {
+        match self {
+            Self::NotFound { entity, .. } => {
+                serde_json::json!({ "error": "not_found", "entity": entity })
+            }
+            Self::UniqueViolation { columns, .. } => {
+                serde_json::json!({ "error": "unique_violation", "columns": columns })
+            }
+            Self::ForeignKeyViolation { columns, .. } => {
+                serde_json::json!({ "error": "foreign_key_violation", "columns": columns })
+            }
+            Self::Timeout { entity, .. } => {
+                serde_json::json!({ "error": "timeout", "entity": entity })
+            }
+            Self::CachedQueryMismatch { .. } | Self::Decode { .. } | Self::Sqlx(_) => {
+                serde_json::json!({ "error": "internal" })
+            }
+        }
+    }
+
+    /// Whether this is a violation of a unique constraint covering column `C`, e.g. to tell
+    /// that an email address is already taken:
+    ///
+    /// ```ignore
+    /// match user.insert().exec(&mut conn).await {
+    ///     Err(e) if e.unique_violation_on::<user::columns::Email>() => { /* ... */ }
+    ///     result => { /* ... */ }
+    /// }
+    /// ```
+    ///
+    /// The constrained columns are reported by postgres (requires the `postgres` feature) and
+    /// sqlite. For other databases, the name of the constraint is compared to the names chosen
+    /// by default instead, i.e. the name of the column on mysql.
+    #[must_use]
+    pub fn unique_violation_on<C>(&self) -> bool
+    where
+        C: Column,
+    {
+        matches!(
+            self,
+            Self::UniqueViolation { constraint, table, columns, .. }
+                if covers::<C>(constraint.as_deref(), table.as_deref(), columns)
+        )
+    }
+
+    /// Whether this is a violation of a foreign key constraint on column `C`. See
+    /// [`unique_violation_on`](Self::unique_violation_on) for the databases this works with.
+    #[must_use]
+    pub fn foreign_key_violation_on<C>(&self) -> bool
+    where
+        C: Column,
+    {
+        matches!(
+            self,
+            Self::ForeignKeyViolation { constraint, table, columns, .. }
+                if covers::<C>(constraint.as_deref(), table.as_deref(), columns)
+        )
+    }
+}
+
+/// Whether a violated constraint covers column `C`.
+fn covers<C>(constraint: Option<&str>, table: Option<&str>, columns: &[String]) -> bool
+where
+    C: Column,
+{
+    let table_name = C::Entity::TABLE_NAME;
+
+    if table.is_some_and(|e| e != table_name) {
+        return false;
+    }
+
+    if columns.is_empty() {
+        // The names postgres chooses for single-column constraints by default.
+        return constraint.is_some_and(|e| {
+            e == format!("{table_name}_{}_key", C::NAME)
+                || e == format!("{table_name}_{}_fkey", C::NAME)
+        });
+    }
+
+    columns.iter().any(|e| e == C::NAME)
+}
+
+/// The text of `value` between the first `start` and the next `end` after it.
+fn between<'a>(value: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let (_, rest) = value.split_once(start)?;
+    rest.split_once(end).map(|(e, _)| e)
+}
+
+/// Split a list of column names reported by the database, removing any quotes.
+fn split_columns(columns: &str) -> Vec<String> {
+    columns
+        .split(',')
+        .map(|e| e.trim().trim_matches(['"', '`']).to_string())
+        .collect()
+}
+
+/// The table and columns of a violated constraint, parsed from the error reported by the
+/// database.
+fn constrained_columns(error: &dyn DatabaseError) -> (Option<String>, Vec<String>) {
+    // e.g. `Key (email)=(someone@example.com) already exists.`
+    #[cfg(feature = "postgres")]
+    if let Some(e) = error.try_downcast_ref::<sqlx::postgres::PgDatabaseError>() {
+        let detail = e.detail().unwrap_or_default();
+        let columns = between(detail, "Key (", ")=")
+            .map(split_columns)
+            .unwrap_or_default();
+
+        // When deleting a row that is still referred to, the reported table is the referring one,
+        // but the key belongs to the table the row was deleted from.
+        if detail.contains("is still referenced from table") {
+            return (
+                between(e.message(), "on table \"", "\"").map(ToString::to_string),
+                columns,
+            );
+        }
+
+        return (e.table().map(ToString::to_string), columns);
+    }
+
+    let message = error.message();
+
+    // sqlite, e.g. `UNIQUE constraint failed: user.email`
+    if let Some((_, columns)) = message.split_once("UNIQUE constraint failed: ") {
+        let columns = columns.split(", ").filter_map(|e| e.split_once('.'));
+
+        return (
+            columns.clone().next().map(|(table, _)| table.to_string()),
+            columns.map(|(_, column)| column.to_string()).collect(),
+        );
+    }
+
+    // mysql, e.g. `Duplicate entry '..' for key 'user.email'`, where the key is named after the
+    // first column by default.
+    if let Some(key) = between(message, "for key '", "'") {
+        let (table, key) = key
+            .split_once('.')
+            .map_or((None, key), |(t, k)| (Some(t), k));
+
+        return (table.map(ToString::to_string), vec![key.to_string()]);
+    }
+
+    // mysql, e.g. `.. CONSTRAINT `fk` FOREIGN KEY (`customer_id`) REFERENCES ..`
+    if let Some(columns) = between(message, "FOREIGN KEY (", ")") {
+        return (None, split_columns(columns));
+    }
+
+    (error.table().map(ToString::to_string), vec![])
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound { entity, filter } => {
+                write!(f, "no row of {entity} found for query `{filter}`")
+            }
+            Self::UniqueViolation { source, .. } => write!(f, "unique violation: {source}"),
+            Self::ForeignKeyViolation { source, .. } => {
+                write!(f, "foreign key violation: {source}")
+            }
+            Self::Timeout { entity, timeout } => {
+                write!(f, "query of {entity} timed out after {timeout:?}")
+            }
+            Self::CachedQueryMismatch { expected, actual } => write!(
+                f,
+                "query has {actual} parameters, but the SQL cached under its key has {expected}"
+            ),
+            Self::Decode { column, source } => {
+                write!(f, "failed to decode column {column}: {source}")
+            }
+            Self::Sqlx(source) => write!(f, "{source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.as_sqlx()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(value: sqlx::Error) -> Self {
+        match value {
+            sqlx::Error::Database(e) => {
+                let constraint = e.constraint().map(ToString::to_string);
+
+                match e.kind() {
+                    ErrorKind::UniqueViolation => {
+                        let (table, columns) = constrained_columns(e.as_ref());
+
+                        Self::UniqueViolation {
+                            constraint,
+                            table,
+                            columns,
+                            source: sqlx::Error::Database(e),
+                        }
+                    }
+                    ErrorKind::ForeignKeyViolation => {
+                        let (table, columns) = constrained_columns(e.as_ref());
+
+                        Self::ForeignKeyViolation {
+                            constraint,
+                            table,
+                            columns,
+                            source: sqlx::Error::Database(e),
+                        }
+                    }
+                    _ => Self::Sqlx(sqlx::Error::Database(e)),
+                }
+            }
+            sqlx::Error::ColumnDecode { index, source } => Self::Decode {
+                column: index.clone(),
+                source: sqlx::Error::ColumnDecode { index, source },
+            },
+            sqlx::Error::ColumnNotFound(column) => Self::Decode {
+                column: column.clone(),
+                source: sqlx::Error::ColumnNotFound(column),
+            },
+            e => Self::Sqlx(e),
+        }
+    }
+}
@@ -0,0 +1,987 @@
+//! Seeding a database with rows defined in JSON, e.g. for demo environments or integration tests.
+//! With the `yaml` feature, fixtures can be written in YAML as well, see [`Fixtures::load_yaml`].
+//!
+//! Fixtures are grouped by table. Rows can be given a `$name`, which other rows use to refer to
+//! them in their foreign key columns with `{"$ref": "<name>"}`:
+//!
+//! ```json
+//! {
+//!   "user": [
+//!     { "$name": "alice", "email": "alice@example.com" }
+//!   ],
+//!   "post": [
+//!     { "title": "Hello", "user_id": { "$ref": "alice" } }
+//!   ]
+//! }
+//! ```
+//!
+//! References are resolved via the relations of the tables, i.e. `user_id` becomes the primary key
+//! of the row named `alice`, no matter whether it was given in the fixture or generated by the
+//! database. Rows are inserted in the order they are given in, tables included, except for those
+//! that have to wait for the rows they refer to:
+//!
+//! ```ignore
+//! let keys = Fixtures::new()
+//!     .entity::<user::Entity>()
+//!     .entity::<post::Entity>()
+//!     .load(&std::fs::read_to_string("fixtures.json")?)?
+//!     .insert(&mut conn)
+//!     .await?;
+//!
+//! let alice_id = keys.get("alice");
+//! ```
+//!
+//! Values are passed as text and cast to the column's type on postgres, so e.g. timestamps and
+//! UUIDs can be given as strings. Other databases convert them on their own.
+//!
+//! Generated primary keys are fetched with `RETURNING`, which sqlite supports since version 3.35.
+//! The sqlite bundled with sqlx is recent enough, but with the `sqlite-unbundled` feature, the
+//! system's sqlite has to be as well, or named rows have to specify their primary keys.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use serde_json::{Map, Value};
+use sqlx::{
+    ColumnIndex, Connection, Database, Decode, Encode, Executor, IntoArguments, Row, Type,
+    query::Query,
+};
+
+use crate::{
+    entity::{Entity, meta::RelationKind},
+    query::exec_result::IntoExecResult,
+};
+
+/// The key naming a row, so that other rows can refer to it.
+const NAME_KEY: &str = "$name";
+
+/// The key of an object referring to a named row.
+const REF_KEY: &str = "$ref";
+
+/// An error that stopped fixtures from being loaded or inserted.
+#[derive(Debug)]
+pub enum FixtureError {
+    /// The fixtures are not valid JSON.
+    Json(serde_json::Error),
+    /// The fixtures are not valid YAML.
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml_ng::Error),
+    /// The fixtures are not shaped like an object of tables holding arrays of rows.
+    Format(String),
+    /// The fixtures contain rows of a table that has not been registered.
+    UnknownTable(String),
+    /// A row has a value for a column its table does not have.
+    UnknownColumn { table: String, column: String },
+    /// More than one row has the same `$name`.
+    DuplicateName(String),
+    /// A row refers to a name that no row has.
+    UnknownReference {
+        table: String,
+        column: String,
+        name: String,
+    },
+    /// A row refers to another row in a column that is not a foreign key to the other row's table,
+    /// or the referenced value of the other row is not known.
+    InvalidReference {
+        table: String,
+        column: String,
+        name: String,
+    },
+    /// Rows refer to each other in a cycle, so none of them can be inserted first. Holds the names
+    /// of the rows that could not be inserted.
+    Circular(Vec<String>),
+    /// Inserting a row failed.
+    Database(crate::Error),
+}
+
+impl Display for FixtureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid fixtures: {e}"),
+            #[cfg(feature = "yaml")]
+            Self::Yaml(e) => write!(f, "invalid fixtures: {e}"),
+            Self::Format(e) => write!(f, "invalid fixtures: {e}"),
+            Self::UnknownTable(table) => write!(f, "unknown table \"{table}\""),
+            Self::UnknownColumn { table, column } => {
+                write!(f, "unknown column \"{column}\" of table \"{table}\"")
+            }
+            Self::DuplicateName(name) => write!(f, "duplicate fixture name \"{name}\""),
+            Self::UnknownReference {
+                table,
+                column,
+                name,
+            } => write!(
+                f,
+                "\"{table}\".\"{column}\" refers to unknown fixture \"{name}\""
+            ),
+            Self::InvalidReference {
+                table,
+                column,
+                name,
+            } => write!(
+                f,
+                "\"{table}\".\"{column}\" cannot refer to fixture \"{name}\", since it is not a foreign key to its table or its key is not known"
+            ),
+            Self::Circular(names) => write!(
+                f,
+                "fixtures refer to each other in a cycle: {}",
+                names.join(", ")
+            ),
+            Self::Database(e) => write!(f, "failed to insert fixture: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {}
+
+impl From<serde_json::Error> for FixtureError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml_ng::Error> for FixtureError {
+    fn from(value: serde_yaml_ng::Error) -> Self {
+        Self::Yaml(value)
+    }
+}
+
+impl From<crate::Error> for FixtureError {
+    fn from(value: crate::Error) -> Self {
+        Self::Database(value)
+    }
+}
+
+impl From<sqlx::Error> for FixtureError {
+    fn from(value: sqlx::Error) -> Self {
+        Self::Database(value.into())
+    }
+}
+
+/// A foreign key of a [`FixtureTable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FixtureReference {
+    table: String,
+    /// The referenced column, or [`None`] for the primary key of `table`.
+    column: Option<String>,
+}
+
+/// A table that fixtures can be inserted into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureTable {
+    name: String,
+    schema: Option<String>,
+    primary_key: Option<String>,
+    /// The name of the SQL type of every column.
+    columns: HashMap<String, String>,
+    /// The foreign keys, by the columns holding them.
+    references: HashMap<String, FixtureReference>,
+}
+
+impl FixtureTable {
+    /// Describe a table without any columns.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            schema: None,
+            primary_key: None,
+            columns: HashMap::new(),
+            references: HashMap::new(),
+        }
+    }
+
+    /// Describe the table of entity `E`, with its columns and the foreign keys of its relations.
+    #[must_use]
+    pub fn of<E>() -> Self
+    where
+        E: Entity,
+    {
+        let meta = E::meta();
+
+        Self {
+            name: meta.table.to_string(),
+            schema: meta.schema.map(ToString::to_string),
+            primary_key: meta.primary_key.map(ToString::to_string),
+            columns: meta
+                .columns
+                .into_iter()
+                .map(|e| (e.name.to_string(), e.sql_type))
+                .collect(),
+            references: meta
+                .relations
+                .into_iter()
+                .filter(|e| matches!(e.kind, RelationKind::ManyToOne | RelationKind::OneToOne))
+                .map(|e| {
+                    (
+                        e.foreign_key.to_string(),
+                        FixtureReference {
+                            table: e.table.to_string(),
+                            column: None,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Set the schema the table is in.
+    #[must_use]
+    pub fn schema(mut self, schema: impl Into<String>) -> Self {
+        self.schema = Some(schema.into());
+        self
+    }
+
+    /// Set the primary key column, which is fetched after inserting a named row that does not
+    /// specify it.
+    #[must_use]
+    pub fn primary_key(mut self, column: impl Into<String>) -> Self {
+        self.primary_key = Some(column.into());
+        self
+    }
+
+    /// Add a column, along with the name of its SQL type, e.g. `BIGINT`.
+    #[must_use]
+    pub fn column(mut self, name: impl Into<String>, sql_type: impl Into<String>) -> Self {
+        self.columns.insert(name.into(), sql_type.into());
+        self
+    }
+
+    /// Add a foreign key from `column` to `target_column` of table `target_table`, or to its
+    /// primary key if `target_column` is [`None`].
+    #[must_use]
+    pub fn reference(
+        mut self,
+        column: impl Into<String>,
+        target_table: impl Into<String>,
+        target_column: Option<String>,
+    ) -> Self {
+        self.references.insert(
+            column.into(),
+            FixtureReference {
+                table: target_table.into(),
+                column: target_column,
+            },
+        );
+        self
+    }
+
+    fn qualified_name(&self) -> String {
+        self.schema.as_ref().map_or_else(
+            || format!("\"{}\"", self.name),
+            |schema| format!("\"{schema}\".\"{}\"", self.name),
+        )
+    }
+}
+
+/// A single row of fixtures.
+#[derive(Debug, Clone)]
+struct FixtureRow {
+    table: String,
+    name: Option<String>,
+    values: Map<String, Value>,
+}
+
+impl FixtureRow {
+    /// The names of the rows this row refers to.
+    fn references(&self) -> impl Iterator<Item = &str> {
+        self.values.values().filter_map(reference_name)
+    }
+}
+
+/// The name referred to by `value`, if it is a reference.
+fn reference_name(value: &Value) -> Option<&str> {
+    value
+        .as_object()
+        .filter(|e| e.len() == 1)
+        .and_then(|e| e.get(REF_KEY))
+        .and_then(Value::as_str)
+}
+
+/// The values of the named rows that have been inserted, by their names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FixtureKeys(HashMap<String, Map<String, Value>>);
+
+impl FixtureKeys {
+    /// The primary key of the row called `name`. Generated keys are returned as strings on
+    /// postgres and sqlite, since their types are not known.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.get(name).and_then(|e| e.get(PRIMARY_KEY))
+    }
+
+    /// The value of `column` of the row called `name`, if it was given in the fixtures, or it is
+    /// the row's generated primary key.
+    #[must_use]
+    pub fn column(&self, name: &str, column: &str) -> Option<&Value> {
+        self.0.get(name).and_then(|e| e.get(column))
+    }
+
+    /// The number of named rows that have been inserted.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no named rows have been inserted.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// The key the primary key of a named row is stored under in [`FixtureKeys`], additionally to
+/// its column name.
+const PRIMARY_KEY: &str = "$primary_key";
+
+/// A set of rows to insert, see the [module documentation](self).
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    tables: HashMap<String, FixtureTable>,
+    rows: Vec<FixtureRow>,
+}
+
+impl Fixtures {
+    /// Create an empty set of fixtures, without any tables to insert rows into.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow inserting rows into the table of entity `E`. See [`FixtureTable::of`].
+    #[must_use]
+    pub fn entity<E>(self) -> Self
+    where
+        E: Entity,
+    {
+        self.table(FixtureTable::of::<E>())
+    }
+
+    /// Allow inserting rows into `table`.
+    #[must_use]
+    pub fn table(mut self, table: FixtureTable) -> Self {
+        self.tables.insert(table.name.clone(), table);
+        self
+    }
+
+    /// Add the rows of the fixtures in `json`. Can be called several times, e.g. for several
+    /// files, whose rows may refer to each other.
+    ///
+    /// # Errors
+    ///
+    /// If `json` is not valid, or its rows do not match the registered tables. See
+    /// [`FixtureError`] for more information.
+    pub fn load(self, json: &str) -> Result<Self, FixtureError> {
+        self.load_value(serde_json::from_str(json)?)
+    }
+
+    /// Add the rows of the fixtures in `yaml`, which are shaped the same as those in JSON. See
+    /// [`load`](Self::load).
+    ///
+    /// # Errors
+    ///
+    /// If `yaml` is not valid, or its rows do not match the registered tables. See
+    /// [`FixtureError`] for more information.
+    #[cfg(feature = "yaml")]
+    pub fn load_yaml(self, yaml: &str) -> Result<Self, FixtureError> {
+        self.load_value(serde_yaml_ng::from_str(yaml)?)
+    }
+
+    /// Add the rows of the fixtures in `value`. See [`load`](Self::load).
+    ///
+    /// # Errors
+    ///
+    /// If `value` is not valid, or its rows do not match the registered tables. See
+    /// [`FixtureError`] for more information.
+    pub fn load_value(mut self, value: Value) -> Result<Self, FixtureError> {
+        let Value::Object(tables) = value else {
+            return Err(FixtureError::Format(
+                "expected an object of tables".to_string(),
+            ));
+        };
+
+        for (table_name, rows) in tables {
+            let Some(table) = self.tables.get(&table_name) else {
+                return Err(FixtureError::UnknownTable(table_name));
+            };
+
+            let Value::Array(rows) = rows else {
+                return Err(FixtureError::Format(format!(
+                    "expected an array of rows for table \"{table_name}\""
+                )));
+            };
+
+            for row in rows {
+                let Value::Object(mut values) = row else {
+                    return Err(FixtureError::Format(format!(
+                        "expected rows of table \"{table_name}\" to be objects"
+                    )));
+                };
+
+                let name = match values.remove(NAME_KEY) {
+                    Some(Value::String(name)) => Some(name),
+                    Some(_) => {
+                        return Err(FixtureError::Format(format!(
+                            "expected {NAME_KEY} of a row of table \"{table_name}\" to be a string"
+                        )));
+                    }
+                    None => None,
+                };
+
+                if let Some(column) = values.keys().find(|e| !table.columns.contains_key(*e)) {
+                    return Err(FixtureError::UnknownColumn {
+                        table: table_name,
+                        column: column.clone(),
+                    });
+                }
+
+                if let Some(name) = name
+                    .as_ref()
+                    .filter(|e| self.rows.iter().any(|r| r.name.as_ref() == Some(e)))
+                {
+                    return Err(FixtureError::DuplicateName(name.clone()));
+                }
+
+                self.rows.push(FixtureRow {
+                    table: table_name.clone(),
+                    name,
+                    values,
+                });
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Make sure every reference points to a named row of the table its column refers to.
+    fn check_references(&self) -> Result<(), FixtureError> {
+        let named = self
+            .rows
+            .iter()
+            .filter_map(|e| e.name.as_deref().map(|name| (name, e)))
+            .collect::<HashMap<_, _>>();
+
+        for row in &self.rows {
+            for (column, value) in &row.values {
+                let Some(name) = reference_name(value) else {
+                    continue;
+                };
+
+                let error = |unknown: bool| {
+                    let (table, column, name) =
+                        (row.table.clone(), column.clone(), name.to_string());
+
+                    if unknown {
+                        FixtureError::UnknownReference {
+                            table,
+                            column,
+                            name,
+                        }
+                    } else {
+                        FixtureError::InvalidReference {
+                            table,
+                            column,
+                            name,
+                        }
+                    }
+                };
+
+                let Some(target) = named.get(name) else {
+                    return Err(error(true));
+                };
+
+                let reference = self.tables[&row.table]
+                    .references
+                    .get(column)
+                    .filter(|e| e.table == target.table)
+                    .ok_or_else(|| error(false))?;
+
+                // The referenced value needs to be given, unless it is the primary key, which is
+                // fetched after inserting the row.
+                let target_table = &self.tables[&target.table];
+                let known = match &reference.column {
+                    Some(e) if Some(e) != target_table.primary_key.as_ref() => {
+                        target.values.contains_key(e)
+                    }
+                    _ => target_table.primary_key.is_some(),
+                };
+
+                if !known {
+                    return Err(error(false));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insert all rows, returning the keys of the named ones.
+    ///
+    /// Rows are inserted one by one, so to not leave some of them behind if one fails, `connection`
+    /// should be inside a transaction. On sqlite, named rows without a primary key need version
+    /// 3.35 or later, see the [module documentation](self).
+    ///
+    /// # Errors
+    ///
+    /// If a reference cannot be resolved, or a row could not be inserted. See [`FixtureError`] for
+    /// more information.
+    pub async fn insert<DB, C>(&self, connection: &mut C) -> Result<FixtureKeys, FixtureError>
+    where
+        DB: Database,
+        C: Connection<Database = DB>,
+        for<'e> &'e mut C: Executor<'e, Database = DB>,
+        for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+        DB::QueryResult: IntoExecResult,
+        String: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB>,
+        i64: for<'a> Encode<'a, DB> + Type<DB>,
+        f64: for<'a> Encode<'a, DB> + Type<DB>,
+        bool: for<'a> Encode<'a, DB> + Type<DB>,
+        usize: ColumnIndex<DB::Row>,
+    {
+        self.check_references()?;
+
+        let mut keys = FixtureKeys::default();
+        let mut pending = self.rows.iter().collect::<Vec<_>>();
+
+        while !pending.is_empty() {
+            let (ready, waiting) = pending
+                .into_iter()
+                .partition::<Vec<_>, _>(|e| e.references().all(|e| keys.0.contains_key(e)));
+
+            if ready.is_empty() {
+                let waiting_names = waiting
+                    .iter()
+                    .flat_map(|e| e.references())
+                    .collect::<HashSet<_>>();
+
+                let mut names = waiting
+                    .iter()
+                    .filter_map(|e| e.name.clone())
+                    .filter(|e| waiting_names.contains(e.as_str()))
+                    .collect::<Vec<_>>();
+                names.sort();
+
+                return Err(FixtureError::Circular(names));
+            }
+
+            for row in ready {
+                let key = self.insert_row(row, &keys, connection).await?;
+
+                if let Some(name) = &row.name {
+                    let mut values = row.values.clone();
+                    let table = &self.tables[&row.table];
+
+                    if let Some(primary_key) = &table.primary_key {
+                        let value = values.get(primary_key).cloned().or(key);
+
+                        if let Some(value) = value {
+                            values.insert(primary_key.clone(), value.clone());
+                            values.insert(PRIMARY_KEY.to_string(), value);
+                        }
+                    }
+
+                    keys.0.insert(name.clone(), values);
+                }
+            }
+
+            pending = waiting;
+        }
+
+        Ok(keys)
+    }
+
+    /// Insert a single row, returning its generated primary key if it is named.
+    async fn insert_row<DB, C>(
+        &self,
+        row: &FixtureRow,
+        keys: &FixtureKeys,
+        connection: &mut C,
+    ) -> Result<Option<Value>, FixtureError>
+    where
+        DB: Database,
+        C: Connection<Database = DB>,
+        for<'e> &'e mut C: Executor<'e, Database = DB>,
+        for<'q> DB::Arguments<'q>: IntoArguments<'q, DB>,
+        DB::QueryResult: IntoExecResult,
+        String: for<'a> Encode<'a, DB> + for<'a> Decode<'a, DB> + Type<DB>,
+        i64: for<'a> Encode<'a, DB> + Type<DB>,
+        f64: for<'a> Encode<'a, DB> + Type<DB>,
+        bool: for<'a> Encode<'a, DB> + Type<DB>,
+        usize: ColumnIndex<DB::Row>,
+    {
+        let table = &self.tables[&row.table];
+        let is_postgres = DB::NAME == "PostgreSQL";
+
+        let mut statement = RowStatement::new(
+            format!("INSERT INTO {} ", table.qualified_name()),
+            is_postgres,
+        );
+
+        if row.values.is_empty() {
+            // mysql does not support `DEFAULT VALUES`, see `Insert`.
+            statement.sql.push_str(if DB::NAME == "MySQL" {
+                "() VALUES ()"
+            } else {
+                "DEFAULT VALUES"
+            });
+        } else {
+            statement.sql.push('(');
+            statement.sql.push_str(
+                &row.values
+                    .keys()
+                    .map(|e| format!("\"{e}\""))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+            statement.sql.push_str(") VALUES (");
+
+            for (i, (column, value)) in row.values.iter().enumerate() {
+                if i > 0 {
+                    statement.sql.push_str(", ");
+                }
+
+                let value = reference_name(value)
+                    .map_or(value, |name| resolve_reference(table, column, name, keys));
+
+                if is_postgres {
+                    statement.push_cast_value(value, &table.columns[column]);
+                } else {
+                    statement.push_value(value);
+                }
+            }
+
+            statement.sql.push(')');
+        }
+
+        // Only the keys of named rows are needed, since no other row can refer to the others.
+        let primary_key = table
+            .primary_key
+            .as_ref()
+            .filter(|e| row.name.is_some() && !row.values.contains_key(*e));
+
+        match primary_key {
+            Some(primary_key) if DB::NAME != "MySQL" => {
+                statement.sql.push_str(" RETURNING CAST(\"");
+                statement.sql.push_str(primary_key);
+                statement.sql.push_str("\" AS TEXT)");
+
+                let key: String = (&mut *connection)
+                    .fetch_one(statement.query())
+                    .await?
+                    .try_get(0)?;
+
+                Ok(Some(Value::String(key)))
+            }
+            _ => {
+                let result = (&mut *connection)
+                    .execute(statement.query())
+                    .await?
+                    .into_exec_result();
+
+                Ok(primary_key
+                    .and(result.last_insert_id)
+                    .map(|e| Value::Number(e.into())))
+            }
+        }
+    }
+}
+
+/// The value referred to by `column` of `table`, in the inserted row called `name`.
+///
+/// Falls back to `NULL` if the row's primary key is unknown, since it was neither given nor
+/// generated, leaving it to the database to reject the row.
+fn resolve_reference<'k>(
+    table: &FixtureTable,
+    column: &str,
+    name: &str,
+    keys: &'k FixtureKeys,
+) -> &'k Value {
+    static NULL: Value = Value::Null;
+
+    let target_column = table.references[column]
+        .column
+        .as_deref()
+        .unwrap_or(PRIMARY_KEY);
+
+    keys.column(name, target_column).unwrap_or(&NULL)
+}
+
+/// A value bound to a [`RowStatement`].
+enum BindValue {
+    Text(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+/// The SQL and bound values of an insert statement.
+///
+/// The values are only bound once the statement is executed, rather than using
+/// a [`QueryBuilder`](sqlx::QueryBuilder), whose arguments would have to outlive the builder on
+/// databases with borrowed arguments, i.e. sqlite.
+struct RowStatement {
+    sql: String,
+    values: Vec<BindValue>,
+    is_postgres: bool,
+}
+
+impl RowStatement {
+    const fn new(sql: String, is_postgres: bool) -> Self {
+        Self {
+            sql,
+            values: vec![],
+            is_postgres,
+        }
+    }
+
+    fn bind(&mut self, value: BindValue) {
+        self.values.push(value);
+
+        if self.is_postgres {
+            self.sql.push('$');
+            self.sql.push_str(&self.values.len().to_string());
+        } else {
+            self.sql.push('?');
+        }
+    }
+
+    /// Push `value` as text, cast to `sql_type`.
+    fn push_cast_value(&mut self, value: &Value, sql_type: &str) {
+        let text = match value {
+            Value::Null => {
+                self.sql.push_str("NULL");
+                return;
+            }
+            Value::String(e) => e.clone(),
+            e => e.to_string(),
+        };
+
+        // Arrays have a different text representation than JSON, so they are built from the
+        // elements of the JSON array instead.
+        if value.is_array() && sql_type.ends_with("[]") {
+            self.sql
+                .push_str("CAST(ARRAY(SELECT jsonb_array_elements_text(CAST(");
+            self.bind(BindValue::Text(text));
+            self.sql.push_str(" AS JSONB))) AS ");
+            self.sql.push_str(sql_type);
+            self.sql.push(')');
+            return;
+        }
+
+        self.sql.push_str("CAST(");
+        self.bind(BindValue::Text(text));
+        self.sql.push_str(" AS ");
+        self.sql.push_str(sql_type);
+        self.sql.push(')');
+    }
+
+    /// Push `value` as the type closest to its JSON type, leaving conversions to the database.
+    fn push_value(&mut self, value: &Value) {
+        match value {
+            Value::Null => self.sql.push_str("NULL"),
+            Value::Bool(e) => self.bind(BindValue::Bool(*e)),
+            Value::Number(e) => match e.as_i64() {
+                Some(e) => self.bind(BindValue::Integer(e)),
+                None => self.bind(BindValue::Float(e.as_f64().unwrap_or_default())),
+            },
+            Value::String(e) => self.bind(BindValue::Text(e.clone())),
+            e @ (Value::Array(_) | Value::Object(_)) => self.bind(BindValue::Text(e.to_string())),
+        }
+    }
+
+    /// The statement as a query of sqlx, with all values bound.
+    fn query<DB>(&self) -> Query<'_, DB, DB::Arguments<'_>>
+    where
+        DB: Database,
+        String: for<'a> Encode<'a, DB> + Type<DB>,
+        i64: for<'a> Encode<'a, DB> + Type<DB>,
+        f64: for<'a> Encode<'a, DB> + Type<DB>,
+        bool: for<'a> Encode<'a, DB> + Type<DB>,
+    {
+        self.values
+            .iter()
+            .fold(sqlx::query(&self.sql), |query, value| match value {
+                BindValue::Text(e) => query.bind(e.clone()),
+                BindValue::Integer(e) => query.bind(*e),
+                BindValue::Float(e) => query.bind(*e),
+                BindValue::Bool(e) => query.bind(*e),
+            })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod test {
+    use serde_json::json;
+
+    use super::{FixtureError, FixtureTable, Fixtures, RowStatement};
+
+    fn fixtures() -> Fixtures {
+        Fixtures::new()
+            .table(
+                FixtureTable::new("user")
+                    .primary_key("id")
+                    .column("id", "BIGINT")
+                    .column("email", "TEXT"),
+            )
+            .table(
+                FixtureTable::new("post")
+                    .primary_key("id")
+                    .column("id", "BIGINT")
+                    .column("title", "TEXT")
+                    .column("user_id", "BIGINT")
+                    .column("user_email", "TEXT")
+                    .reference("user_id", "user", None)
+                    .reference("user_email", "user", Some("email".to_string())),
+            )
+    }
+
+    #[test]
+    fn test_load() {
+        let fixtures = fixtures()
+            .load(
+                r#"{
+                    "user": [{ "$name": "alice", "email": "alice@example.com" }],
+                    "post": [{ "title": "Hello", "user_id": { "$ref": "alice" } }]
+                }"#,
+            )
+            .expect("Failed to load fixtures");
+
+        let [user, post] = fixtures.rows.as_slice() else {
+            panic!("Expected two rows, got {}", fixtures.rows.len());
+        };
+
+        assert_eq!(user.table, "user");
+        assert_eq!(post.table, "post");
+        assert_eq!(user.name.as_deref(), Some("alice"));
+        assert!(!user.values.contains_key("$name"));
+        assert_eq!(post.references().collect::<Vec<_>>(), ["alice"]);
+        assert!(fixtures.check_references().is_ok());
+    }
+
+    #[test]
+    fn test_load_errors() {
+        assert!(matches!(
+            fixtures().load(r#"{ "comment": [] }"#),
+            Err(FixtureError::UnknownTable(table)) if table == "comment"
+        ));
+
+        assert!(matches!(
+            fixtures().load(r#"{ "user": [{ "name": "alice" }] }"#),
+            Err(FixtureError::UnknownColumn { column, .. }) if column == "name"
+        ));
+
+        assert!(matches!(
+            fixtures().load(r#"{ "user": { "email": "alice@example.com" } }"#),
+            Err(FixtureError::Format(_))
+        ));
+
+        assert!(matches!(
+            fixtures()
+                .load(r#"{ "user": [{ "$name": "alice" }] }"#)
+                .and_then(|e| e.load(r#"{ "post": [{ "$name": "alice" }] }"#)),
+            Err(FixtureError::DuplicateName(name)) if name == "alice"
+        ));
+    }
+
+    #[test]
+    fn test_check_references() {
+        let unknown = fixtures()
+            .load(r#"{ "post": [{ "user_id": { "$ref": "bob" } }] }"#)
+            .expect("Failed to load fixtures");
+
+        assert!(matches!(
+            unknown.check_references(),
+            Err(FixtureError::UnknownReference { name, .. }) if name == "bob"
+        ));
+
+        // `title` is not a foreign key.
+        let not_a_key = fixtures()
+            .load(
+                r#"{
+                    "user": [{ "$name": "alice" }],
+                    "post": [{ "title": { "$ref": "alice" } }]
+                }"#,
+            )
+            .expect("Failed to load fixtures");
+
+        assert!(matches!(
+            not_a_key.check_references(),
+            Err(FixtureError::InvalidReference { column, .. }) if column == "title"
+        ));
+
+        // The email of `alice` is not given, so it cannot be referred to.
+        let unknown_value = fixtures()
+            .load(
+                r#"{
+                    "user": [{ "$name": "alice" }],
+                    "post": [{ "user_email": { "$ref": "alice" } }]
+                }"#,
+            )
+            .expect("Failed to load fixtures");
+
+        assert!(matches!(
+            unknown_value.check_references(),
+            Err(FixtureError::InvalidReference { column, .. }) if column == "user_email"
+        ));
+    }
+
+    #[test]
+    fn test_row_statement() {
+        let mut statement = RowStatement::new("VALUES (".to_string(), true);
+        statement.push_cast_value(&json!(1), "BIGINT");
+        statement.sql.push_str(", ");
+        statement.push_cast_value(&json!(["a", "b"]), "TEXT[]");
+        statement.sql.push_str(", ");
+        statement.push_cast_value(&json!(null), "TEXT");
+        statement.sql.push(')');
+
+        assert_eq!(
+            statement.sql,
+            "VALUES (CAST($1 AS BIGINT), CAST(ARRAY(SELECT jsonb_array_elements_text(CAST($2 AS JSONB))) AS TEXT[]), NULL)"
+        );
+
+        let mut statement = RowStatement::new("VALUES (".to_string(), false);
+        statement.push_value(&json!(1));
+        statement.sql.push_str(", ");
+        statement.push_value(&json!({ "a": 1 }));
+        statement.sql.push(')');
+
+        assert_eq!(statement.sql, "VALUES (?, ?)");
+        assert_eq!(statement.values.len(), 2);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_load_yaml() {
+        let yaml = fixtures()
+            .load_yaml(
+                "
+user:
+  - $name: alice
+    email: alice@example.com
+post:
+  - title: Hello
+    user_id: { $ref: alice }
+",
+            )
+            .expect("Failed to load fixtures");
+
+        let json = fixtures()
+            .load(
+                r#"{
+                    "user": [{ "$name": "alice", "email": "alice@example.com" }],
+                    "post": [{ "title": "Hello", "user_id": { "$ref": "alice" } }]
+                }"#,
+            )
+            .expect("Failed to load fixtures");
+
+        assert_eq!(
+            yaml.rows.iter().map(|e| &e.values).collect::<Vec<_>>(),
+            json.rows.iter().map(|e| &e.values).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use std::{fmt::Display, pin::pin};
+
+use futures::{Stream, StreamExt};
+use sqlx::{Connection, Database, Executor, IntoArguments};
+
+use crate::{
+    entity::{
+        Entity,
+        model::{ActiveModel, ActiveModelHooks, IntoActiveModel},
+    },
+    query::exec_result::IntoExecResult,
+};
+
+/// The active model type of entity `E`.
+type ActiveModelOf<E> = <<E as Entity>::Model as IntoActiveModel>::ActiveModel;
+
+type Validator<'a, A> = Box<dyn FnMut(&A) -> Result<(), String> + Send + 'a>;
+
+type ProgressCallback<'a> = Box<dyn FnMut(&ImportProgress) + Send + 'a>;
+
+/// What an [`Importer`] should do when a batch fails to validate or to be written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OnFailure {
+    /// Stop the import, returning an [`ImportError::Aborted`]. Previously written batches are
+    /// kept.
+    #[default]
+    Abort,
+    /// Set the affected records aside in the [`ImportReport`] and carry on with the next batch.
+    Quarantine,
+}
+
+/// The reason records have been rejected during an import.
+#[derive(Debug)]
+pub enum ImportFailure {
+    /// The record did not pass validation.
+    Invalid(String),
+    /// Writing the batch containing the records failed.
+    Database(crate::Error),
+}
+
+impl Display for ImportFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(e) => write!(f, "validation failed: {e}"),
+            Self::Database(e) => write!(f, "failed to write batch: {e}"),
+        }
+    }
+}
+
+/// Records that have been rejected during an import, along with the reason why.
+#[derive(Debug)]
+pub struct Quarantined<A> {
+    pub records: Vec<A>,
+    pub reason: ImportFailure,
+}
+
+/// A snapshot of the progress of an import, passed to the progress callback after each batch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ImportProgress {
+    /// The number of records consumed from the input so far.
+    pub processed: u64,
+    /// The number of records successfully written so far.
+    pub inserted: u64,
+    /// The number of records quarantined so far.
+    pub quarantined: u64,
+    /// The number of batches processed so far.
+    pub batches: u64,
+}
+
+/// The outcome of a completed import.
+#[derive(Debug)]
+pub struct ImportReport<A> {
+    pub progress: ImportProgress,
+    pub quarantined: Vec<Quarantined<A>>,
+}
+
+/// An error that stopped an import.
+#[derive(Debug)]
+pub enum ImportError<A> {
+    /// A batch was rejected, and the importer was configured to [`OnFailure::Abort`].
+    Aborted {
+        report: ImportReport<A>,
+        failed: Quarantined<A>,
+    },
+    /// Managing the savepoint of a batch failed.
+    Database(crate::Error),
+}
+
+impl<A> Display for ImportError<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Aborted { report, failed } => write!(
+                f,
+                "import aborted after {} records: {}",
+                report.progress.processed, failed.reason
+            ),
+            Self::Database(e) => write!(f, "import failed: {e}"),
+        }
+    }
+}
+
+impl<A> std::error::Error for ImportError<A> where A: std::fmt::Debug {}
+
+impl<A> From<crate::Error> for ImportError<A> {
+    fn from(value: crate::Error) -> Self {
+        Self::Database(value)
+    }
+}
+
+impl<A> From<sqlx::Error> for ImportError<A> {
+    fn from(value: sqlx::Error) -> Self {
+        Self::Database(value.into())
+    }
+}
+
+/// Bulk importer for records of entity `E`.
+///
+/// Records are consumed from a stream, validated, and written in batches using multi-row
+/// `INSERT` statements. Every batch is written inside its own savepoint (or transaction, if the
+/// passed connection is not inside one already), so a failing batch never leaves partial rows
+/// behind.
+///
+/// Postgres' `COPY` is not used, since it needs the values of each record encoded in its own text
+/// or binary format, while active models only provide them as bind parameters.
+///
+/// The insert [hooks](crate::entity::model::ActiveModelHooks) of each record are run as well.
+/// Records failing `before_insert` are rejected like those failing validation.
+pub struct Importer<'a, E>
+where
+    E: Entity,
+    E::Model: IntoActiveModel,
+{
+    batch_size: usize,
+    on_failure: OnFailure,
+    validator: Option<Validator<'a, ActiveModelOf<E>>>,
+    progress: Option<ProgressCallback<'a>>,
+}
+
+impl<E> Default for Importer<'_, E>
+where
+    E: Entity,
+    E::Model: IntoActiveModel,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, E> Importer<'a, E>
+where
+    E: Entity,
+    E::Model: IntoActiveModel,
+{
+    /// Create a new importer, with a batch size of 1000, aborting on the first failure.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            batch_size: 1000,
+            on_failure: OnFailure::Abort,
+            validator: None,
+            progress: None,
+        }
+    }
+
+    /// Set the number of records written per batch. Batches exceeding the bind parameter limit of
+    /// the backend (see [`max_bind_parameters`](crate::query::max_bind_parameters)) are written by
+    /// several statements.
+    ///
+    /// # Panics
+    ///
+    /// If `batch_size` is zero.
+    #[must_use]
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "Batch size must not be zero");
+
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set what to do when a batch fails.
+    #[must_use]
+    pub const fn on_failure(mut self, on_failure: OnFailure) -> Self {
+        self.on_failure = on_failure;
+        self
+    }
+
+    /// Validate every record before it is written. Records failing validation are rejected
+    /// according to [`on_failure`](Self::on_failure), without affecting the rest of their batch.
+    #[must_use]
+    pub fn validate<F>(mut self, validator: F) -> Self
+    where
+        F: FnMut(&ActiveModelOf<E>) -> Result<(), String> + Send + 'a,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Be notified of the import's progress after every batch.
+    #[must_use]
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&ImportProgress) + Send + 'a,
+    {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Run the import, consuming all records from the stream.
+    ///
+    /// # Errors
+    ///
+    /// If a batch is rejected while configured to [`OnFailure::Abort`], or if a savepoint could
+    /// not be created or released. See [`ImportError`] for more information.
+    pub async fn run<S, C>(
+        mut self,
+        records: S,
+        connection: &mut C,
+    ) -> Result<ImportReport<ActiveModelOf<E>>, ImportError<ActiveModelOf<E>>>
+    where
+        S: Stream<Item = ActiveModelOf<E>>,
+        C: Connection<Database = E::Database>,
+        for<'e> &'e mut <E::Database as Database>::Connection: Executor<'e, Database = E::Database>,
+        for<'q> <E::Database as Database>::Arguments<'q>: IntoArguments<'q, E::Database> + 'static,
+        <E::Database as Database>::QueryResult: IntoExecResult,
+    {
+        let mut report = ImportReport {
+            progress: ImportProgress::default(),
+            quarantined: vec![],
+        };
+
+        let mut batches = pin!(records.chunks(self.batch_size));
+
+        while let Some(batch) = batches.next().await {
+            report.progress.processed += batch.len() as u64;
+            report.progress.batches += 1;
+
+            let mut valid = Vec::with_capacity(batch.len());
+
+            for mut record in batch {
+                let checked = record
+                    .before_insert()
+                    .map_err(|e| e.to_string())
+                    .and_then(|()| self.validator.as_mut().map_or(Ok(()), |e| e(&record)));
+
+                match checked {
+                    Ok(()) => valid.push(record),
+                    Err(e) => self.reject(
+                        &mut report,
+                        Quarantined {
+                            records: vec![record],
+                            reason: ImportFailure::Invalid(e),
+                        },
+                    )?,
+                }
+            }
+
+            if !valid.is_empty() {
+                let mut savepoint = connection.begin().await?;
+
+                let result = E::insert()
+                    .rows(&valid)
+                    .exec(&mut *savepoint)
+                    .await
+                    .and_then(|_| Ok(valid.iter().try_for_each(ActiveModelHooks::after_insert)?));
+
+                match result {
+                    Ok(()) => {
+                        savepoint.commit().await?;
+                        report.progress.inserted += valid.len() as u64;
+                    }
+                    Err(e) => {
+                        savepoint.rollback().await?;
+                        self.reject(
+                            &mut report,
+                            Quarantined {
+                                records: valid,
+                                reason: ImportFailure::Database(e),
+                            },
+                        )?;
+                    }
+                }
+            }
+
+            if let Some(callback) = self.progress.as_mut() {
+                callback(&report.progress);
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Either quarantine the rejected records, or abort the import. The report is moved into the
+    /// error in the latter case.
+    // The error is returned as is from `run`, which is not flagged since it is public.
+    #[allow(clippy::result_large_err)]
+    fn reject<A>(
+        &self,
+        report: &mut ImportReport<A>,
+        rejected: Quarantined<A>,
+    ) -> Result<(), ImportError<A>>
+    where
+        A: ActiveModel,
+    {
+        match self.on_failure {
+            OnFailure::Abort => Err(ImportError::Aborted {
+                report: std::mem::replace(
+                    report,
+                    ImportReport {
+                        progress: report.progress,
+                        quarantined: vec![],
+                    },
+                ),
+                failed: rejected,
+            }),
+            OnFailure::Quarantine => {
+                report.progress.quarantined += rejected.records.len() as u64;
+                report.quarantined.push(rejected);
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,8 +1,45 @@
+#[cfg(feature = "exec")]
+pub mod audit;
+#[cfg(feature = "exec")]
+pub mod change;
 pub mod entity;
+pub mod error;
+#[cfg(feature = "exec")]
+pub mod fixtures;
+#[cfg(feature = "exec")]
+pub mod import;
+#[cfg(all(feature = "postgres", feature = "exec"))]
+pub mod notify;
+pub mod prelude;
 pub mod query;
+#[cfg(all(feature = "postgres", feature = "exec"))]
+pub mod rls;
+#[cfg(feature = "exec")]
+pub mod router;
+#[cfg(feature = "exec")]
+pub mod session;
+#[cfg(all(feature = "sqlite", feature = "exec"))]
+pub mod sqlite;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "exec")]
+pub mod web;
 
+// The derive macro refers to this crate as `::sky_orm`, also when its own tests use it.
+#[cfg(test)]
+extern crate self as sky_orm;
+
+pub use error::{Error, Result};
 pub use sky_orm_macros::DatabaseModel;
+/// Derive macro to store a unit-only enum in a column, either as text or as its discriminant.
+pub use sky_orm_macros::DbEnum;
 /// Derive macro to implement [`ParseFromRow`](query::parse::ParseFromRow).
 pub use sky_orm_macros::FromSqlxRow;
 
+#[cfg(feature = "async-graphql")]
+pub use async_graphql;
+#[cfg(feature = "pgvector")]
+pub use pgvector;
+pub use serde;
+pub use serde_json;
 pub use sqlx;
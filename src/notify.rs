@@ -0,0 +1,218 @@
+//! Reacting to changes of rows as they happen, by listening for the notifications sent by Postgres'
+//! `NOTIFY`, e.g. to invalidate caches or to update live views:
+//!
+//! ```ignore
+//! let mut listener = ChangeListener::<user::Entity>::connect(&database_url, "user_changes").await?;
+//!
+//! loop {
+//!     let change = listener.recv().await?;
+//!     cache.invalidate(change.model.id);
+//! }
+//! ```
+//!
+//! Every notification carries a JSON object with the `table_name`, `operation` and `row_data` of
+//! the change, plus its `id` if it has also been recorded in a change table. Such notifications are
+//! sent by the triggers generated with `sky-orm-cli generate-triggers --notify <channel>`.
+//!
+//! Payloads are limited to 8000 bytes by Postgres, so the triggers leave out the `row_data` of rows
+//! too large for that. [`ChangeListener`] loads those from the change table by their `id`.
+//!
+//! Notifications are only delivered while listening. For changes that must not be missed, use a
+//! [`ChangeFeed`](crate::change::ChangeFeed) instead, e.g. resuming it after the
+//! [`id`](Change::id) of the last notification on reconnect.
+
+use std::marker::PhantomData;
+
+use serde::{Deserialize, de::DeserializeOwned};
+use sqlx::{
+    Postgres,
+    postgres::{PgListener, PgPool},
+    types::{Json, JsonValue},
+};
+
+use crate::{
+    Result,
+    change::{Change, ChangeOperation},
+    entity::Entity,
+    query::quote_ident,
+};
+
+/// The payload of a notification about a change.
+#[derive(Deserialize)]
+struct ChangePayload {
+    /// Only set if the change has also been recorded in a change table.
+    #[serde(default)]
+    id: i64,
+    table_name: String,
+    operation: String,
+    /// Left out if the payload would have been too large with it.
+    #[serde(default)]
+    row_data: Option<JsonValue>,
+}
+
+impl ChangePayload {
+    /// Parse `payload`, or return [`None`] if it is about a change of another table than `E`'s.
+    fn parse<E: Entity>(payload: &str) -> Result<Option<Self>> {
+        let payload =
+            serde_json::from_str::<Self>(payload).map_err(|e| sqlx::Error::Decode(e.into()))?;
+
+        Ok((payload.table_name == E::TABLE_NAME).then_some(payload))
+    }
+
+    /// The change with the row of `row_data`.
+    fn into_change<M: DeserializeOwned>(self, row_data: JsonValue) -> Result<Change<M>> {
+        Ok(Change {
+            id: self.id,
+            operation: self
+                .operation
+                .parse::<ChangeOperation>()
+                .map_err(|e| sqlx::Error::Decode(e.into()))?,
+            model: serde_json::from_value(row_data).map_err(|e| sqlx::Error::Decode(e.into()))?,
+        })
+    }
+}
+
+/// Decode the payload of a notification into a change of entity `E`.
+///
+/// Returns [`None`] if it is about a change of another table, so that the changes of several
+/// entities can be handled with a single [`PgListener`].
+///
+/// # Errors
+///
+/// If the payload is not a change, or the changed row cannot be decoded into the model. Also if the
+/// row has been left out of the payload for its size, see [`ChangeListener`] to load it instead.
+pub fn decode_payload<E>(payload: &str) -> Result<Option<Change<E::Model>>>
+where
+    E: Entity<Database = Postgres>,
+    E::Model: DeserializeOwned,
+{
+    let Some(mut payload) = ChangePayload::parse::<E>(payload)? else {
+        return Ok(None);
+    };
+
+    let row_data = payload.row_data.take().ok_or_else(|| {
+        sqlx::Error::Decode(
+            format!(
+                "the row of change {} is too large for the notification, load it from the change \
+                 table instead",
+                payload.id
+            )
+            .into(),
+        )
+    })?;
+
+    payload.into_change(row_data).map(Some)
+}
+
+/// A listener for the changes of entity `E`, notified on one or more channels.
+///
+/// The recorded rows are decoded from JSON, so the model needs to implement
+/// [`Deserialize`], with fields named like their database columns.
+pub struct ChangeListener<E>
+where
+    E: Entity<Database = Postgres>,
+{
+    listener: PgListener,
+    change_table: String,
+    marker: PhantomData<E>,
+}
+
+impl<E> ChangeListener<E>
+where
+    E: Entity<Database = Postgres>,
+    E::Model: DeserializeOwned,
+{
+    /// Open a new connection to the database at `url`, listening on `channel`.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`](crate::Error) for
+    /// more information.
+    pub async fn connect(url: &str, channel: &str) -> Result<Self> {
+        let mut listener = PgListener::connect(url).await?;
+        listener.listen(channel).await?;
+
+        Ok(Self::from_listener(listener))
+    }
+
+    /// Take a connection from `pool`, listening on `channel`. The connection is not returned to
+    /// the pool, since it is needed for as long as the listener lives.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`](crate::Error) for
+    /// more information.
+    pub async fn connect_with(pool: &PgPool, channel: &str) -> Result<Self> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(channel).await?;
+
+        Ok(Self::from_listener(listener))
+    }
+
+    /// Receive the changes notified to `listener`, on whichever channels it listens on.
+    #[must_use]
+    pub fn from_listener(listener: PgListener) -> Self {
+        Self {
+            listener,
+            change_table: "sky_orm_changes".to_string(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Set the name of the table the rows too large for a notification are loaded from. Defaults
+    /// to `sky_orm_changes`, needs to match the `--change-table` passed to the CLI.
+    #[must_use]
+    pub fn change_table(mut self, change_table: impl Into<String>) -> Self {
+        self.change_table = change_table.into();
+        self
+    }
+
+    /// Also listen on `channel`.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`](crate::Error) for
+    /// more information.
+    pub async fn listen(&mut self, channel: &str) -> Result<()> {
+        Ok(self.listener.listen(channel).await?)
+    }
+
+    /// Wait for the next change of `E`, skipping the notifications about other tables.
+    ///
+    /// If the connection is lost, it is re-established, but notifications sent in the meantime
+    /// are lost. Rows left out of a notification for their size are loaded from the change table.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or a notification cannot be
+    /// decoded into a change. See [`Error`](crate::Error) for more information.
+    pub async fn recv(&mut self) -> Result<Change<E::Model>> {
+        loop {
+            let notification = self.listener.recv().await?;
+
+            let Some(mut payload) = ChangePayload::parse::<E>(notification.payload())? else {
+                continue;
+            };
+
+            if let Some(row_data) = payload.row_data.take() {
+                return payload.into_change(row_data);
+            }
+
+            let (Json(row_data),) = sqlx::query_as::<_, (Json<JsonValue>,)>(&format!(
+                "SELECT \"row_data\" FROM {} WHERE \"id\" = $1",
+                quote_ident(&self.change_table)
+            ))
+            .bind(payload.id)
+            .fetch_one(&mut self.listener)
+            .await?;
+
+            return payload.into_change(row_data);
+        }
+    }
+
+    /// The underlying listener, e.g. to stop listening on some channel.
+    #[must_use]
+    pub fn into_inner(self) -> PgListener {
+        self.listener
+    }
+}
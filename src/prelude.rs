@@ -0,0 +1,43 @@
+//! The traits and macros needed to use sky-orm, for importing them all at once:
+//!
+//! ```ignore
+//! use sky_orm::prelude::*;
+//! ```
+//!
+//! Most query building happens through trait methods (e.g. `columns::Name::eq`), which can only
+//! be called while their trait is in scope.
+
+#[cfg(feature = "exec")]
+pub use crate::entity::relation::{
+    LoadInverse, LoadRelation,
+    nested::{ThenLoadInverse, ThenLoadRelation},
+    tree::{LoadTree, SelfRelated},
+};
+pub use crate::{
+    DatabaseModel, DbEnum, FromSqlxRow,
+    entity::{
+        Entity, EntityScope, HasPrimaryKey, TenantScoped,
+        column::{
+            Column, ColumnEnum, ColumnExt, ColumnTuple, ComparableColumn, NullableColumn,
+            NumericColumn, OrderableColumn, StringComparableColumn, TableAlias,
+        },
+        model::{ActiveModel, ActiveModelHooks, GetColumn, IntoActiveModel, Model, Patch},
+        relation::{CompositeRelated, ForeignKey, InverseRelated, Related},
+    },
+    query::{
+        PushToQuery,
+        aggregate::AggregateColumn,
+        parse::{ParseFromRow, Projection},
+    },
+};
+
+#[cfg(feature = "postgres")]
+pub use crate::entity::column::array::{AnyColumn, ArrayColumn};
+#[cfg(all(feature = "postgres", feature = "exec"))]
+pub use crate::entity::column::json::JsonColumn;
+#[cfg(all(feature = "postgres", feature = "ipnetwork"))]
+pub use crate::entity::column::network::NetworkColumn;
+#[cfg(feature = "pgvector")]
+pub use crate::entity::column::vector::VectorColumn;
+#[cfg(feature = "testing")]
+pub use crate::testing::{Factory, FactoryDefault};
@@ -1,9 +1,48 @@
+pub mod aggregate;
+#[cfg(feature = "exec")]
+pub mod cache;
+pub mod cond;
+pub mod delete;
+#[cfg(feature = "exec")]
+pub mod exec_result;
+#[cfg(feature = "exec")]
+pub mod find_or_create;
+pub mod hint;
+pub mod insert;
 pub mod parse;
 pub mod select;
+#[cfg(feature = "statement")]
+pub mod statement;
+pub mod update;
 
-use std::{fmt::Display, marker::PhantomData, ops::Deref, sync::Arc};
+#[cfg(feature = "exec")]
+use std::time::Duration;
+use std::{borrow::Cow, fmt::Display, marker::PhantomData, ops::Deref, sync::Arc};
 
-use sqlx::{Database, Encode, QueryBuilder, Type};
+use sqlx::{Database, Encode, QueryBuilder, Type, encode::IsNull, error::BoxDynError};
+
+/// Run `future`, failing with [`Error::Timeout`](crate::Error::Timeout) if `timeout` is given and
+/// exceeded.
+///
+/// The future is simply dropped once the timeout elapses, using the timer of the runtime enabled
+/// for sqlx. The database may keep running the statement until it notices that the connection is
+/// gone.
+#[cfg(feature = "exec")]
+pub(crate) async fn with_timeout<F>(
+    entity: &'static str,
+    timeout: Option<Duration>,
+    future: F,
+) -> crate::Result<F::Output>
+where
+    F: Future,
+{
+    match timeout {
+        Some(timeout) => sqlx_core::rt::timeout(timeout, future)
+            .await
+            .map_err(|_| crate::Error::Timeout { entity, timeout }),
+        None => Ok(future.await),
+    }
+}
 
 /// This trait represents anything that can be pushed into a [`QueryBuilder`], i.e. any kind of
 /// query fragment, like a condition or a list of values.
@@ -13,54 +52,150 @@ where
 {
     /// Push the object's contents into a query builder.
     fn push_to(&self, builder: &mut QueryBuilder<'_, DB>);
+
+    /// Push only the values bound by [`push_to`](Self::push_to) into a query builder, in the same
+    /// order, skipping the rest of the query. The SQL of the builder is thrown away, only its
+    /// arguments are used, e.g. to run a query whose SQL has been cached (see
+    /// [`QueryCache`](cache::QueryCache)).
+    ///
+    /// By default, this pushes the whole object, so it only needs to be implemented to avoid
+    /// building SQL for nothing.
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.push_to(builder);
+    }
+
+    /// Push the tables (or aliases) whose columns are referred to by [`push_to`](Self::push_to),
+    /// e.g. to check that a condition only refers to tables that are part of the query.
+    ///
+    /// By default, nothing is pushed, so objects that don't implement this, e.g. raw SQL or
+    /// subqueries, are never rejected by such checks.
+    fn push_tables<'s>(&'s self, _tables: &mut Vec<&'s str>) {}
 }
 
-impl<DB> PushToQuery<DB> for Box<dyn PushToQuery<DB>>
+impl<DB> PushToQuery<DB> for Box<dyn PushToQuery<DB> + '_>
 where
     DB: Database + Sync,
 {
     fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
         self.deref().push_to(builder);
     }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.deref().push_binds(builder);
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        self.deref().push_tables(tables);
+    }
 }
 
-impl<DB> PushToQuery<DB> for Arc<dyn PushToQuery<DB>>
+impl<DB> PushToQuery<DB> for Arc<dyn PushToQuery<DB> + '_>
 where
     DB: Database + Sync,
 {
     fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
         self.deref().push_to(builder);
     }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.deref().push_binds(builder);
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        self.deref().push_tables(tables);
+    }
 }
 
-pub(crate) struct QueryVariable<T, DB>(pub(crate) T, PhantomData<DB>)
+/// A value bound to the query as a parameter, e.g. `$1` on postgres or `?` elsewhere.
+///
+/// The value is shared between every query built from this variable, so pushing it into a query
+/// doesn't copy it, however large it is.
+pub struct QueryVariable<T, DB>(
+    pub(crate) Arc<T>,
+    Option<Cow<'static, str>>,
+    PhantomData<DB>,
+)
 where
-    T: for<'a> Encode<'a, DB> + Type<DB> + 'static + Clone + Send + Sync,
+    T: for<'a> Encode<'a, DB> + Type<DB> + 'static + Send + Sync,
     DB: Database + Sync;
 
 impl<T, DB> QueryVariable<T, DB>
 where
-    T: for<'a> Encode<'a, DB> + Type<DB> + 'static + Clone + Send + Sync,
+    T: for<'a> Encode<'a, DB> + Type<DB> + 'static + Send + Sync,
     DB: Database + Sync,
 {
-    pub const fn new(inner: T) -> Self {
-        Self(inner, PhantomData)
+    pub fn new(inner: T) -> Self {
+        Self(Arc::new(inner), None, PhantomData)
+    }
+
+    /// Cast the value to `sql_type` within the query, e.g. `$1::citext`, for values that are bound
+    /// as a different SQL type than the one they should be compared as. Only supported on
+    /// postgres.
+    #[must_use]
+    pub fn cast(mut self, sql_type: impl Into<Cow<'static, str>>) -> Self {
+        self.1 = Some(sql_type.into());
+        self
     }
 }
 
 impl<T, DB> PushToQuery<DB> for QueryVariable<T, DB>
 where
-    T: for<'a> Encode<'a, DB> + Type<DB> + 'static + Clone + Send + Sync,
+    T: for<'a> Encode<'a, DB> + Type<DB> + 'static + Send + Sync,
     DB: Database + Sync,
 {
     fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
-        builder.push_bind(self.0.clone());
+        builder.push_bind(SharedValue(Arc::clone(&self.0)));
+        if let Some(sql_type) = &self.1 {
+            builder.push(format_args!("::{sql_type}"));
+        }
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        builder.push_bind(SharedValue(Arc::clone(&self.0)));
+    }
+}
+
+/// The value of a [`QueryVariable`] as bound to a query, encoded by reference.
+struct SharedValue<T>(Arc<T>);
+
+impl<'q, T, DB> Encode<'q, DB> for SharedValue<T>
+where
+    T: Encode<'q, DB>,
+    DB: Database,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as Database>::ArgumentBuffer<'q>,
+    ) -> Result<IsNull, BoxDynError> {
+        self.0.encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        self.0.produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.size_hint()
+    }
+}
+
+impl<T, DB> Type<DB> for SharedValue<T>
+where
+    T: Type<DB>,
+    DB: Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        T::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        T::compatible(ty)
     }
 }
 
 impl<T, DB> PushToQuery<DB> for Vec<QueryVariable<T, DB>>
 where
-    T: for<'a> Encode<'a, DB> + Type<DB> + 'static + Clone + Send + Sync,
+    T: for<'a> Encode<'a, DB> + Type<DB> + 'static + Send + Sync,
     DB: Database + Sync,
 {
     fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
@@ -73,9 +208,16 @@ where
         });
         builder.push(")");
     }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        for e in self {
+            e.push_binds(builder);
+        }
+    }
 }
 
-pub(crate) struct BracketsExpr<T, DB>(T, PhantomData<DB>)
+/// An expression wrapped in brackets, i.e. `(inner)`.
+pub struct BracketsExpr<T, DB>(T, PhantomData<DB>)
 where
     T: PushToQuery<DB>,
     DB: Database + Sync;
@@ -85,7 +227,7 @@ where
     T: PushToQuery<DB>,
     DB: Database + Sync,
 {
-    pub(crate) const fn new(inner: T) -> Self {
+    pub const fn new(inner: T) -> Self {
         Self(inner, PhantomData)
     }
 }
@@ -100,9 +242,21 @@ where
         self.0.push_to(builder);
         builder.push(")");
     }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.0.push_binds(builder);
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        self.0.push_tables(tables);
+    }
 }
 
-pub(crate) enum BinaryExprOperand {
+/// The operator of a [`BinaryExpr`].
+///
+/// Operators not covered here can be expressed using [`Custom`](Self::Custom).
+#[non_exhaustive]
+pub enum BinaryExprOperand {
     Equals,
     DoesNotEqual,
     Like,
@@ -117,6 +271,10 @@ pub(crate) enum BinaryExprOperand {
     Lt,
     Geq,
     Leq,
+    Add,
+    Subtract,
+    /// Any other operator, pushed into the query verbatim, e.g. `<->` for pgvector's distance.
+    Custom(&'static str),
 }
 
 impl Display for BinaryExprOperand {
@@ -139,6 +297,9 @@ impl Display for BinaryExprOperand {
                 Self::Lt => "<",
                 Self::Geq => ">=",
                 Self::Leq => "<=",
+                Self::Add => "+",
+                Self::Subtract => "-",
+                Self::Custom(e) => e,
             }
         )
     }
@@ -147,7 +308,7 @@ impl Display for BinaryExprOperand {
 /// A binary SQL expression, glued together with an operator.
 ///
 /// Example: `left-side [operator] right-side`
-pub(crate) struct BinaryExpr<T, C, DB>
+pub struct BinaryExpr<T, C, DB>
 where
     T: PushToQuery<DB>,
     C: PushToQuery<DB>,
@@ -165,7 +326,7 @@ where
     C: PushToQuery<DB>,
     DB: Database + Sync,
 {
-    pub(crate) const fn new(left: T, right: C, operand: BinaryExprOperand) -> Self {
+    pub const fn new(left: T, right: C, operand: BinaryExprOperand) -> Self {
         Self {
             a: left,
             b: right,
@@ -182,15 +343,231 @@ where
     DB: Database + Sync,
 {
     fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
+        // Only postgres knows `ILIKE`, so elsewhere both sides are lowercased instead. This matches
+        // the case-insensitivity of `ILIKE` for ASCII, which is all that sqlite's `LOWER` handles.
+        if matches!(self.operand, BinaryExprOperand::ILike)
+            && matches!(DB::NAME, "SQLite" | "MySQL")
+        {
+            builder.push("LOWER(");
+            self.a.push_to(builder);
+            builder.push(") LIKE LOWER(");
+            self.b.push_to(builder);
+            builder.push(")");
+            return;
+        }
+
         self.a.push_to(builder);
         builder.push(format_args!(" {} ", self.operand));
         self.b.push_to(builder);
     }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.a.push_binds(builder);
+        self.b.push_binds(builder);
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        self.a.push_tables(tables);
+        self.b.push_tables(tables);
+    }
 }
 
-pub(crate) enum SingletonExprOperand {
+/// A row value, i.e. a list of expressions in brackets, e.g. to compare several columns at once.
+///
+/// Example: `(expression, expression, ..)`
+pub struct RowExpr<DB>(Vec<Box<dyn PushToQuery<DB>>>)
+where
+    DB: Database + Sync;
+
+impl<DB> RowExpr<DB>
+where
+    DB: Database + Sync,
+{
+    #[must_use]
+    pub const fn new(values: Vec<Box<dyn PushToQuery<DB>>>) -> Self {
+        Self(values)
+    }
+}
+
+impl<DB> PushToQuery<DB> for RowExpr<DB>
+where
+    DB: Database + Sync,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
+        builder.push("(");
+        self.0.iter().enumerate().for_each(|(i, e)| {
+            if i > 0 {
+                builder.push(", ");
+            }
+            e.push_to(builder);
+        });
+        builder.push(")");
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.0.iter().for_each(|e| e.push_binds(builder));
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        for e in &self.0 {
+            e.push_tables(tables);
+        }
+    }
+}
+
+/// Quote `ident` as an SQL identifier, e.g. a table name only known at runtime.
+#[cfg(feature = "exec")]
+pub(crate) fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// The maximum number of bind parameters of a single statement on database `DB`.
+///
+/// This is 65535 on postgres and mysql. Sqlite allows 32766 since version 3.32, but only 999 before
+/// it, which is assumed, since an older system library can be linked with `sqlite-unbundled`. Any
+/// other database, e.g. the [`Any`](sqlx::Any) driver, gets the lowest limit as well.
+#[must_use]
+pub fn max_bind_parameters<DB>() -> usize
+where
+    DB: Database,
+{
+    match DB::NAME {
+        "PostgreSQL" | "MySQL" => 65_535,
+        _ => 999,
+    }
+}
+
+/// The number of values [`InListExpr`] puts into a single list by default.
+pub const IN_LIST_CHUNK_SIZE: usize = 1000;
+
+/// A check whether an expression occurs in a list of values.
+///
+/// Example: `left-side IN ($1, $2, ..)`
+///
+/// An empty list renders as a condition that never holds (or always holds, in the case of
+/// `NOT IN`).
+///
+/// Since some databases reject overly long lists, lists longer than the chunk size are split into
+/// several lists, which are OR-ed together (or AND-ed, in the case of `NOT IN`). Note that this
+/// doesn't reduce the number of bind parameters of the query. Lists beyond the parameter limit of
+/// the database should be matched against a single array parameter instead, e.g. with
+/// [`AnyColumn::eq_any`](crate::entity::column::array::AnyColumn::eq_any) on postgres.
+pub struct InListExpr<T, V, DB>
+where
+    T: PushToQuery<DB>,
+    V: PushToQuery<DB>,
+    DB: Database + Sync,
+{
+    left: T,
+    values: Vec<V>,
+    negated: bool,
+    chunk_size: usize,
+    marker: PhantomData<DB>,
+}
+
+impl<T, V, DB> InListExpr<T, V, DB>
+where
+    T: PushToQuery<DB>,
+    V: PushToQuery<DB>,
+    DB: Database + Sync,
+{
+    /// Check whether `left` occurs in `values`, or does _not_ occur in them if `negated` is set.
+    pub const fn new(left: T, values: Vec<V>, negated: bool) -> Self {
+        Self {
+            left,
+            values,
+            negated,
+            chunk_size: IN_LIST_CHUNK_SIZE,
+            marker: PhantomData,
+        }
+    }
+
+    /// Put at most `chunk_size` values into a single list.
+    #[must_use]
+    pub const fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = if chunk_size == 0 { 1 } else { chunk_size };
+        self
+    }
+
+    fn push_list(&self, values: &[V], builder: &mut QueryBuilder<'_, DB>) {
+        let operand = if self.negated {
+            BinaryExprOperand::NotIn
+        } else {
+            BinaryExprOperand::In
+        };
+
+        self.left.push_to(builder);
+        builder.push(format_args!(" {operand} ("));
+        values.iter().enumerate().for_each(|(i, e)| {
+            if i > 0 {
+                builder.push(", ");
+            }
+            e.push_to(builder);
+        });
+        builder.push(")");
+    }
+}
+
+impl<T, V, DB> PushToQuery<DB> for InListExpr<T, V, DB>
+where
+    T: PushToQuery<DB>,
+    V: PushToQuery<DB>,
+    DB: Database + Sync,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
+        // `IN ()` is not valid SQL on most databases.
+        if self.values.is_empty() {
+            builder.push(if self.negated { "1 = 1" } else { "1 = 0" });
+            return;
+        }
+
+        if self.values.len() <= self.chunk_size {
+            self.push_list(&self.values, builder);
+            return;
+        }
+
+        let glue = if self.negated { " AND " } else { " OR " };
+
+        builder.push("(");
+        self.values
+            .chunks(self.chunk_size)
+            .enumerate()
+            .for_each(|(i, e)| {
+                if i > 0 {
+                    builder.push(glue);
+                }
+                self.push_list(e, builder);
+            });
+        builder.push(")");
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        // Every list repeats the left side, see `push_to`.
+        self.values.chunks(self.chunk_size).for_each(|e| {
+            self.left.push_binds(builder);
+            for e in e {
+                e.push_binds(builder);
+            }
+        });
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        self.left.push_tables(tables);
+        for e in &self.values {
+            e.push_tables(tables);
+        }
+    }
+}
+
+/// The postfix operator of a [`SingletonExpr`].
+///
+/// Operators not covered here can be expressed using [`Custom`](Self::Custom).
+#[non_exhaustive]
+pub enum SingletonExprOperand {
     IsNull,
     IsNotNull,
+    /// Any other postfix operator, pushed into the query verbatim.
+    Custom(&'static str),
 }
 
 impl Display for SingletonExprOperand {
@@ -201,12 +578,16 @@ impl Display for SingletonExprOperand {
             match self {
                 Self::IsNull => "IS NULL",
                 Self::IsNotNull => "IS NOT NULL",
+                Self::Custom(e) => e,
             }
         )
     }
 }
 
-pub(crate) struct SingletonExpr<T, DB>
+/// An expression followed by a postfix operator.
+///
+/// Example: `expression [operator]`
+pub struct SingletonExpr<T, DB>
 where
     T: PushToQuery<DB>,
     DB: Database + Sync,
@@ -239,6 +620,14 @@ where
         self.inner.push_to(builder);
         builder.push(format_args!(" {}", self.operand));
     }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.inner.push_binds(builder);
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        self.inner.push_tables(tables);
+    }
 }
 
 impl<DB> PushToQuery<DB> for String
@@ -248,4 +637,6 @@ where
     fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
         builder.push(self);
     }
+
+    fn push_binds(&self, _builder: &mut QueryBuilder<'_, DB>) {}
 }
@@ -0,0 +1,266 @@
+//! Filtering entries by aggregates over their children, e.g. the users with more than 5 orders, or
+//! with an order over 100:
+//!
+//! ```ignore
+//! user::Entity::find().where_aggregate(Aggregate::<order::Entity>::count().gt(5));
+//! user::Entity::find().where_aggregate(order::columns::Total::max().gt(100));
+//! ```
+//!
+//! Every condition passed to [`Select::where_aggregate`](super::select::Select::where_aggregate)
+//! becomes a correlated subquery over the children of the current entry, so that it can be
+//! combined with any other condition, and with pagination, without grouping the outer query:
+//!
+//! `(SELECT COUNT(*) > $1 FROM "order" WHERE "order"."user_id" = "user"."id")`
+//!
+//! An entry without children has a count of 0, while all other aggregates are `NULL` for it, so it
+//! never matches conditions on them.
+
+use std::{fmt::Display, marker::PhantomData};
+
+use sqlx::{Database, Encode, QueryBuilder, Type};
+
+use crate::entity::{
+    Entity,
+    column::{Column, ColumnName, EntityConditionExpr},
+};
+
+use super::{BinaryExpr, BinaryExprOperand, PushToQuery, QueryVariable};
+
+/// The function computing an [`Aggregate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateFunction {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Display for AggregateFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Count => "COUNT",
+                Self::Sum => "SUM",
+                Self::Avg => "AVG",
+                Self::Min => "MIN",
+                Self::Max => "MAX",
+            }
+        )
+    }
+}
+
+/// An aggregate over the rows of entity `E`, whose values are compared as type `T`.
+///
+/// Aggregates over a column are built via [`AggregateColumn`], while counting rows is done with
+/// [`Aggregate::count`].
+pub struct Aggregate<E, T = i64>
+where
+    E: Entity,
+{
+    function: AggregateFunction,
+    /// The aggregated column, or [`None`] for all rows, i.e. `COUNT(*)`.
+    column: Option<ColumnName>,
+    marker: PhantomData<(E, T)>,
+}
+
+impl<E> Aggregate<E>
+where
+    E: Entity,
+{
+    /// The number of rows.
+    ///
+    /// SQL: `COUNT(*)`
+    #[must_use]
+    pub const fn count() -> Self {
+        Self {
+            function: AggregateFunction::Count,
+            column: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<E, T> Aggregate<E, T>
+where
+    E: Entity,
+    T: for<'a> Encode<'a, E::Database> + Type<E::Database> + Send + Sync + 'static,
+{
+    /// Compare this aggregate against a bound value using the given operator.
+    ///
+    /// SQL: `aggregate [operator] $1`
+    #[must_use]
+    pub fn binary_op(
+        self,
+        operand: BinaryExprOperand,
+        other: T,
+    ) -> EntityConditionExpr<impl PushToQuery<E::Database>, E> {
+        BinaryExpr::new(self, QueryVariable::new(other), operand).into()
+    }
+
+    /// Check whether this aggregate equals `other`.
+    #[must_use]
+    pub fn eq(self, other: T) -> EntityConditionExpr<impl PushToQuery<E::Database>, E> {
+        self.binary_op(BinaryExprOperand::Equals, other)
+    }
+
+    /// Check whether this aggregate does _not_ equal `other`.
+    #[must_use]
+    pub fn not_eq(self, other: T) -> EntityConditionExpr<impl PushToQuery<E::Database>, E> {
+        self.binary_op(BinaryExprOperand::DoesNotEqual, other)
+    }
+
+    /// Check whether this aggregate is greater than `other`.
+    #[must_use]
+    pub fn gt(self, other: T) -> EntityConditionExpr<impl PushToQuery<E::Database>, E> {
+        self.binary_op(BinaryExprOperand::Gt, other)
+    }
+
+    /// Check whether this aggregate is less than `other`.
+    #[must_use]
+    pub fn lt(self, other: T) -> EntityConditionExpr<impl PushToQuery<E::Database>, E> {
+        self.binary_op(BinaryExprOperand::Lt, other)
+    }
+
+    /// Check whether this aggregate is greater than or equal to `other`.
+    #[must_use]
+    pub fn geq(self, other: T) -> EntityConditionExpr<impl PushToQuery<E::Database>, E> {
+        self.binary_op(BinaryExprOperand::Geq, other)
+    }
+
+    /// Check whether this aggregate is less than or equal to `other`.
+    #[must_use]
+    pub fn leq(self, other: T) -> EntityConditionExpr<impl PushToQuery<E::Database>, E> {
+        self.binary_op(BinaryExprOperand::Leq, other)
+    }
+}
+
+impl<E, T> PushToQuery<E::Database> for Aggregate<E, T>
+where
+    E: Entity,
+    T: Send + Sync,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, E::Database>) {
+        builder.push(format_args!("{}(", self.function));
+        match &self.column {
+            Some(column) => column.push_to(builder),
+            None => {
+                builder.push("*");
+            }
+        }
+        builder.push(")");
+    }
+
+    fn push_binds(&self, _builder: &mut QueryBuilder<'_, E::Database>) {}
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        if let Some(column) = &self.column {
+            PushToQuery::<E::Database>::push_tables(column, tables);
+        }
+    }
+}
+
+/// Aggregates over a column, implemented for all columns.
+pub trait AggregateColumn: Column + Sized {
+    /// The number of rows where this column is not `NULL`.
+    ///
+    /// SQL: `COUNT(column)`
+    #[must_use]
+    fn count() -> Aggregate<Self::Entity> {
+        aggregate::<Self, _>(AggregateFunction::Count)
+    }
+
+    /// The sum of this column.
+    ///
+    /// SQL: `SUM(column)`
+    #[must_use]
+    fn sum() -> Aggregate<Self::Entity, Self::Type> {
+        aggregate::<Self, _>(AggregateFunction::Sum)
+    }
+
+    /// The average of this column.
+    ///
+    /// SQL: `AVG(column)`
+    #[must_use]
+    fn avg() -> Aggregate<Self::Entity, Self::Type> {
+        aggregate::<Self, _>(AggregateFunction::Avg)
+    }
+
+    /// The smallest value of this column.
+    ///
+    /// SQL: `MIN(column)`
+    #[must_use]
+    fn min() -> Aggregate<Self::Entity, Self::Type> {
+        aggregate::<Self, _>(AggregateFunction::Min)
+    }
+
+    /// The largest value of this column.
+    ///
+    /// SQL: `MAX(column)`
+    #[must_use]
+    fn max() -> Aggregate<Self::Entity, Self::Type> {
+        aggregate::<Self, _>(AggregateFunction::Max)
+    }
+}
+
+impl<T> AggregateColumn for T where T: Column {}
+
+fn aggregate<C, T>(function: AggregateFunction) -> Aggregate<C::Entity, T>
+where
+    C: Column,
+{
+    Aggregate {
+        function,
+        column: Some(C::full_column_name()),
+        marker: PhantomData,
+    }
+}
+
+/// A condition on aggregates over the entries of `R` referring to the current row of the outer
+/// query.
+///
+/// SQL: `(SELECT condition FROM child WHERE "child"."parent_id" = "parent"."id")`
+pub(crate) struct AggregateSubquery<Q, R>
+where
+    Q: PushToQuery<R::Database>,
+    R: Entity,
+{
+    pub(crate) condition: EntityConditionExpr<Q, R>,
+    pub(crate) foreign_key: ColumnName,
+    pub(crate) primary_key: ColumnName,
+    pub(crate) scope: Option<Box<dyn PushToQuery<R::Database>>>,
+}
+
+impl<Q, R> PushToQuery<R::Database> for AggregateSubquery<Q, R>
+where
+    Q: PushToQuery<R::Database>,
+    R: Entity,
+    R::Database: Database + Sync,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, R::Database>) {
+        builder.push("(SELECT ");
+        self.condition.push_to(builder);
+        builder.push(" FROM ");
+        builder.push(R::QUALIFIED_TABLE_NAME);
+        builder.push(" WHERE ");
+        self.foreign_key.push_to(builder);
+        builder.push(" = ");
+        self.primary_key.push_to(builder);
+        // Children outside of their scope, e.g. soft deleted ones, are not aggregated.
+        if let Some(scope) = &self.scope {
+            builder.push(" AND (");
+            scope.push_to(builder);
+            builder.push(")");
+        }
+        builder.push(")");
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, R::Database>) {
+        self.condition.push_binds(builder);
+        if let Some(scope) = &self.scope {
+            scope.push_binds(builder);
+        }
+    }
+}
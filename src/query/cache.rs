@@ -0,0 +1,150 @@
+//! Caching the SQL of queries that are run over and over again with different parameters.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use sqlx::{Database, QueryBuilder};
+
+use super::PushToQuery;
+use crate::Error;
+
+/// A cache of rendered SQL, keyed by the shape of a query.
+///
+/// Running the same query with different parameters, e.g. a lookup by ID, normally builds the
+/// same SQL string every time. With a cache, it is built once per key. Later runs only bind their
+/// parameters (see [`PushToQuery::push_binds`]) and pass the same SQL to sqlx, so they always
+/// reuse the prepared statement cached by the connection.
+///
+/// The key is chosen by the caller, and must determine the SQL completely: queries with the same
+/// key may only differ in their parameters. E.g. a query comparing a column to a list of values
+/// needs the length of the list in its key, since every value is a parameter of its own. Running a
+/// query with a different number of parameters than the one cached under its key fails with
+/// [`Error::CachedQueryMismatch`]. Other differences can only be found by rendering the query
+/// anyway, which debug builds do, panicking if the SQL differs.
+///
+/// ```ignore
+/// static CACHE: LazyLock<QueryCache<&'static str>> = LazyLock::new(QueryCache::new);
+///
+/// user::Entity::find()
+///     .filter(user::columns::Email::eq(email))
+///     .cached(&CACHE, "user_by_email")
+///     .one(&mut conn)
+///     .await?;
+/// ```
+pub struct QueryCache<K> {
+    queries: RwLock<HashMap<K, CachedQuery>>,
+}
+
+/// The SQL cached for a key, along with the number of parameters it was rendered with.
+#[derive(Clone)]
+struct CachedQuery {
+    sql: Arc<str>,
+    parameters: usize,
+}
+
+impl<K> Default for QueryCache<K> {
+    fn default() -> Self {
+        Self {
+            queries: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K> QueryCache<K>
+where
+    K: Hash + Eq,
+{
+    /// An empty cache.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of cached queries.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.queries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Whether no query has been cached yet.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached queries.
+    pub fn clear(&self) {
+        self.queries
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+
+    /// The SQL cached for `key`, rendering `query` if there is none yet. `parameters` is the
+    /// number of parameters bound for `query` (see [`PushToQuery::push_binds`]).
+    ///
+    /// # Errors
+    ///
+    /// If the SQL cached for `key` was rendered with a different number of parameters.
+    pub(crate) fn get_or_render<Q, DB>(
+        &self,
+        key: K,
+        query: &Q,
+        parameters: usize,
+    ) -> Result<Arc<str>, Error>
+    where
+        Q: PushToQuery<DB>,
+        DB: Database + Sync,
+    {
+        let cached = self
+            .queries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+            .cloned();
+
+        // The read lock is released at this point, so that the write lock can be taken.
+        let cached = cached.unwrap_or_else(|| {
+            self.queries
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(key)
+                .or_insert_with(|| CachedQuery {
+                    sql: render(query).into(),
+                    parameters,
+                })
+                .clone()
+        });
+
+        if cached.parameters != parameters {
+            return Err(Error::CachedQueryMismatch {
+                expected: cached.parameters,
+                actual: parameters,
+            });
+        }
+
+        debug_assert_eq!(
+            *cached.sql,
+            render(query),
+            "queries cached under the same key must have the same SQL"
+        );
+
+        Ok(cached.sql)
+    }
+}
+
+fn render<Q, DB>(query: &Q) -> String
+where
+    Q: PushToQuery<DB>,
+    DB: Database + Sync,
+{
+    let mut builder = QueryBuilder::new("");
+    query.push_to(&mut builder);
+    builder.into_sql()
+}
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use sqlx::QueryBuilder;
+
+use crate::entity::{Entity, column::EntityConditionExpr};
+
+use super::PushToQuery;
+
+/// A condition on entity `E` composed at runtime, e.g. from the filters of a search form.
+///
+/// Unlike an [`EntityConditionExpr`], the type of a condition doesn't depend on the conditions it
+/// is made of, so conditions can be built in loops or stored in collections:
+///
+/// ```ignore
+/// let condition = tags
+///     .into_iter()
+///     .fold(Cond::any(), |acc, tag| acc.add_or(post::columns::Tag::eq(tag)));
+///
+/// post::Entity::find().filter(condition.into_expr());
+/// ```
+///
+/// Every part of an [`All`](Self::All) or [`Any`](Self::Any) is wrapped in `()` brackets, so that
+/// the precedence of `AND` and `OR` doesn't matter.
+pub enum Cond<E>
+where
+    E: Entity,
+{
+    /// Holds if all of the conditions hold, or if there are none.
+    All(Vec<Self>),
+    /// Holds if any of the conditions holds. Never holds if there are none.
+    Any(Vec<Self>),
+    /// A single condition.
+    Expr(Arc<dyn PushToQuery<E::Database>>),
+}
+
+impl<E> Cond<E>
+where
+    E: Entity,
+{
+    /// A condition that holds if all conditions added with [`add_and`](Self::add_and) hold.
+    #[must_use]
+    pub const fn all() -> Self {
+        Self::All(vec![])
+    }
+
+    /// A condition that holds if any condition added with [`add_or`](Self::add_or) holds.
+    #[must_use]
+    pub const fn any() -> Self {
+        Self::Any(vec![])
+    }
+
+    /// Chain another condition using an `AND` statement.
+    #[must_use]
+    pub fn add_and(self, other: impl Into<Self>) -> Self {
+        match self {
+            Self::All(mut conditions) => {
+                conditions.push(other.into());
+                Self::All(conditions)
+            }
+            e => Self::All(vec![e, other.into()]),
+        }
+    }
+
+    /// Chain another condition using an `OR` statement.
+    #[must_use]
+    pub fn add_or(self, other: impl Into<Self>) -> Self {
+        match self {
+            Self::Any(mut conditions) => {
+                conditions.push(other.into());
+                Self::Any(conditions)
+            }
+            e => Self::Any(vec![e, other.into()]),
+        }
+    }
+
+    /// Turn this condition into an [`EntityConditionExpr`], e.g. to pass it to
+    /// [`Select::filter`](super::select::Select::filter).
+    #[must_use]
+    pub fn into_expr(self) -> EntityConditionExpr<Self, E> {
+        self.into()
+    }
+}
+
+impl<Q, E> From<EntityConditionExpr<Q, E>> for Cond<E>
+where
+    Q: PushToQuery<E::Database> + 'static,
+    E: Entity + 'static,
+{
+    fn from(value: EntityConditionExpr<Q, E>) -> Self {
+        Self::Expr(Arc::new(value))
+    }
+}
+
+impl<E> PushToQuery<E::Database> for Cond<E>
+where
+    E: Entity,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, E::Database>) {
+        let (conditions, glue, empty) = match self {
+            Self::All(conditions) => (conditions, " AND ", "1 = 1"),
+            Self::Any(conditions) => (conditions, " OR ", "1 = 0"),
+            Self::Expr(e) => {
+                e.push_to(builder);
+                return;
+            }
+        };
+
+        if conditions.is_empty() {
+            builder.push(empty);
+        }
+
+        conditions.iter().enumerate().for_each(|(i, e)| {
+            if i > 0 {
+                builder.push(glue);
+            }
+            builder.push("(");
+            e.push_to(builder);
+            builder.push(")");
+        });
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, E::Database>) {
+        match self {
+            Self::All(conditions) | Self::Any(conditions) => {
+                for e in conditions {
+                    e.push_binds(builder);
+                }
+            }
+            Self::Expr(e) => e.push_binds(builder),
+        }
+    }
+
+    fn push_tables<'s>(&'s self, tables: &mut Vec<&'s str>) {
+        match self {
+            Self::All(conditions) | Self::Any(conditions) => {
+                for e in conditions {
+                    e.push_tables(tables);
+                }
+            }
+            Self::Expr(e) => e.push_tables(tables),
+        }
+    }
+}
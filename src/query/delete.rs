@@ -0,0 +1,192 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use sqlx::QueryBuilder;
+#[cfg(feature = "exec")]
+use sqlx::{Connection, Database, Executor, IntoArguments};
+
+#[cfg(feature = "exec")]
+use std::time::Duration;
+
+#[cfg(feature = "exec")]
+use super::{
+    exec_result::{ExecResult, IntoExecResult},
+    with_timeout,
+};
+#[cfg(feature = "exec")]
+use crate::router::DatabaseRouter;
+
+#[cfg(feature = "exec")]
+use crate::entity::column::ColumnName;
+use crate::entity::{Entity, column::EntityConditionExpr};
+
+#[cfg(feature = "statement")]
+use super::statement::Statement;
+#[cfg(feature = "exec")]
+use super::{BinaryExpr, BinaryExprOperand};
+use super::{BracketsExpr, PushToQuery};
+
+/// A `DELETE` statement, removing all rows of entity `T` that match its conditions.
+///
+/// Without any condition, every row of the table is deleted.
+pub struct Delete<T>
+where
+    T: Entity,
+{
+    marker: PhantomData<T>,
+    scope: Option<Arc<dyn PushToQuery<T::Database>>>,
+    conditions: Vec<Arc<dyn PushToQuery<T::Database>>>,
+    #[cfg(feature = "exec")]
+    timeout: Option<Duration>,
+}
+
+impl<T> Delete<T>
+where
+    T: Entity,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            marker: PhantomData,
+            scope: T::implicit_filter().map(Arc::from),
+            conditions: vec![],
+            #[cfg(feature = "exec")]
+            timeout: None,
+        }
+    }
+
+    /// Append a new `WHERE` condition using an `AND` statement as glue. The passed condition is
+    /// wrapped in `()` brackets.
+    #[must_use]
+    pub fn filter<Q>(mut self, condition: EntityConditionExpr<Q, T>) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'static,
+        T: 'static,
+    {
+        self.conditions.push(Arc::new(condition));
+        self
+    }
+
+    /// Skip the [implicit filter](Entity::implicit_filter) of `T`, e.g. to delete the rows of all
+    /// tenants.
+    #[must_use]
+    pub fn unscoped(mut self) -> Self {
+        self.scope = None;
+        self
+    }
+
+    /// Only delete rows whose column equals the given value, e.g. a primary key taken from the
+    /// values of an active model.
+    #[cfg(feature = "exec")]
+    pub(crate) fn filter_column_value<V>(mut self, (name, value): (&'static str, V)) -> Self
+    where
+        V: PushToQuery<T::Database> + 'static,
+    {
+        self.conditions.push(Arc::new(BinaryExpr::new(
+            ColumnName::new_with_table_or_alias(T::TABLE_NAME.to_string(), name.to_string()),
+            value,
+            BinaryExprOperand::Equals,
+        )));
+        self
+    }
+
+    /// Return the raw SQL query of this statement. Note that the returned query is
+    /// backend-agnostic, e.g. query parameters will be substituted with `?` instead of `$1` (in
+    /// the case of postgres).
+    ///
+    /// This is mainly useful for debugging purposes, and not intended to produce queries to be run
+    /// on an actual database.
+    #[must_use]
+    pub fn query(&self) -> String {
+        let mut builder = QueryBuilder::new("");
+        self.push_to(&mut builder);
+        builder.into_sql()
+    }
+
+    /// Render the statement into a [`Statement`], to be run by a driver outside of sqlx.
+    ///
+    /// # Errors
+    ///
+    /// If one of the statement's parameters failed to encode.
+    #[cfg(feature = "statement")]
+    pub fn statement(&self) -> Result<Statement, sqlx::Error>
+    where
+        T: Entity<Database = sqlx::Any>,
+    {
+        Statement::build(self)
+    }
+
+    /// Fail with [`Error::Timeout`](crate::Error::Timeout) if executing the statement takes longer
+    /// than `timeout`. See [`Select::timeout`](super::select::Select::timeout).
+    #[cfg(feature = "exec")]
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Execute the statement, returning the number of affected rows and, on some databases, the ID
+    /// of the last inserted row.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or if the statement violates a
+    /// constraint. See [`Error`](crate::Error) for more information.
+    #[cfg(feature = "exec")]
+    pub async fn exec<'c, C>(self, connection: &'c mut C) -> crate::Result<ExecResult>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        C: Connection<Database = T::Database>,
+        &'c mut C: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        let mut builder = QueryBuilder::new("");
+        self.push_to(&mut builder);
+
+        let timeout = self.timeout;
+        drop(self);
+
+        let result =
+            with_timeout(T::TABLE_NAME, timeout, connection.execute(builder.build())).await??;
+
+        Ok(result.into_exec_result())
+    }
+
+    /// Execute the statement on the primary database of `router`. See [`exec`](Self::exec).
+    ///
+    /// # Errors
+    ///
+    /// If no connection could be acquired, or if the statement failed. See
+    /// [`Error`](crate::Error) for more information.
+    #[cfg(feature = "exec")]
+    pub async fn exec_routed(
+        self,
+        router: &DatabaseRouter<T::Database>,
+    ) -> crate::Result<ExecResult>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let mut connection = router.writer().await?;
+
+        self.exec(&mut *connection).await
+    }
+}
+
+impl<T> PushToQuery<T::Database> for Delete<T>
+where
+    T: Entity,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, T::Database>) {
+        builder.push("DELETE FROM ");
+        builder.push(T::QUALIFIED_TABLE_NAME);
+
+        self.scope
+            .iter()
+            .chain(&self.conditions)
+            .enumerate()
+            .for_each(|(i, e)| {
+                builder.push(if i == 0 { " WHERE " } else { " AND " });
+                BracketsExpr::new(e.clone()).push_to(builder);
+            });
+    }
+}
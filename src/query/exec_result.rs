@@ -0,0 +1,68 @@
+//! The outcome of executing an `INSERT`, `UPDATE` or `DELETE` statement, in the same shape for all
+//! database backends.
+
+/// What a statement did, as reported by the database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecResult {
+    /// The number of rows inserted, updated or deleted.
+    pub rows_affected: u64,
+    /// The ID generated for the last inserted row, on databases reporting it without a
+    /// `RETURNING` clause, i.e. mysql (`LAST_INSERT_ID()`) and sqlite (the row ID). Always
+    /// [`None`] on postgres.
+    ///
+    /// This is the value last generated on the connection, so it is only meaningful after an
+    /// `INSERT`.
+    pub last_insert_id: Option<i64>,
+}
+
+/// A backend-specific query result of sqlx, that can be converted into an [`ExecResult`].
+///
+/// Implemented for the query results of all databases enabled by features.
+pub trait IntoExecResult {
+    /// Convert this query result.
+    fn into_exec_result(self) -> ExecResult;
+}
+
+#[cfg(feature = "postgres")]
+impl IntoExecResult for sqlx::postgres::PgQueryResult {
+    fn into_exec_result(self) -> ExecResult {
+        ExecResult {
+            rows_affected: self.rows_affected(),
+            last_insert_id: None,
+        }
+    }
+}
+
+#[cfg(feature = "mysql")]
+impl IntoExecResult for sqlx::mysql::MySqlQueryResult {
+    fn into_exec_result(self) -> ExecResult {
+        ExecResult {
+            rows_affected: self.rows_affected(),
+            // Zero if the statement did not generate an ID.
+            last_insert_id: Some(self.last_insert_id())
+                .filter(|e| *e != 0)
+                .and_then(|e| i64::try_from(e).ok()),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl IntoExecResult for sqlx::sqlite::SqliteQueryResult {
+    fn into_exec_result(self) -> ExecResult {
+        ExecResult {
+            rows_affected: self.rows_affected(),
+            // Zero if no row has been inserted on the connection yet.
+            last_insert_id: Some(self.last_insert_rowid()).filter(|e| *e != 0),
+        }
+    }
+}
+
+#[cfg(feature = "statement")]
+impl IntoExecResult for sqlx::any::AnyQueryResult {
+    fn into_exec_result(self) -> ExecResult {
+        ExecResult {
+            rows_affected: self.rows_affected(),
+            last_insert_id: self.last_insert_id(),
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Finding the entry matching a condition, or inserting it if there is none:
+//!
+//! ```ignore
+//! let (tag, created) = tag::Entity::find_or_create(tag::columns::Name::eq(name.clone()), || {
+//!     let mut tag = tag::ActiveModel::default();
+//!     tag.set_name(name);
+//!     tag
+//! })
+//! .exec(&mut *conn)
+//! .await?;
+//! ```
+//!
+//! If another connection inserts the same entry between the lookup and the insert, the insert
+//! fails with a unique violation, and the entry inserted by the other connection is looked up
+//! instead. This requires a unique constraint covering the condition, otherwise both entries are
+//! inserted.
+
+use std::{sync::Arc, time::Duration};
+
+use sqlx::{Connection, Database, Executor, IntoArguments};
+
+use crate::{
+    Error, Result,
+    entity::{Entity, column::EntityConditionExpr, model::ActiveModel},
+    router::DatabaseRouter,
+};
+
+use super::{PushToQuery, exec_result::IntoExecResult, select::Select};
+
+/// A lookup of the entry of entity `T` matching a condition, inserting the active model returned
+/// by `F` if there is none. See the [module documentation](self).
+pub struct FindOrCreate<'a, T, F>
+where
+    T: Entity,
+{
+    condition: Arc<dyn PushToQuery<T::Database> + 'a>,
+    create: F,
+    timeout: Option<Duration>,
+}
+
+impl<'a, T, F, A> FindOrCreate<'a, T, F>
+where
+    T: Entity + 'a,
+    F: FnOnce() -> A,
+    A: ActiveModel<Model = T::Model>,
+{
+    pub(crate) fn new<Q>(condition: EntityConditionExpr<Q, T>, create: F) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+    {
+        Self {
+            condition: Arc::new(condition),
+            create,
+            timeout: None,
+        }
+    }
+
+    /// Fail with [`Error::Timeout`] if executing any of the statements takes longer than
+    /// `timeout`. See [`Select::timeout`].
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    fn select(&self) -> Select<'a, T> {
+        let select = T::find().filter(EntityConditionExpr::from(Arc::clone(&self.condition)));
+
+        match self.timeout {
+            Some(timeout) => select.timeout(timeout),
+            None => select,
+        }
+    }
+
+    /// Execute the lookup, returning the entry and whether it has been inserted. The inserted
+    /// entry is selected again after the insert, so it has to match the condition.
+    ///
+    /// The insert runs inside a transaction, or a savepoint if `connection` is already inside one,
+    /// so that a failed insert can be rolled back without aborting the outer transaction.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or if the insert violates a
+    /// constraint other than a unique one covering the condition. See [`Error`] for more
+    /// information.
+    pub async fn exec<C>(self, connection: &mut C) -> Result<(T::Model, bool)>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        C: Connection<Database = T::Database>,
+        for<'e> &'e mut C: Executor<'e, Database = T::Database>,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        match self.select().one(&mut *connection).await {
+            Ok(model) => return Ok((model, false)),
+            Err(Error::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+
+        let select = self.select();
+        let active_model = (self.create)();
+
+        let mut insert = T::insert().row(&active_model);
+        if let Some(timeout) = self.timeout {
+            insert = insert.timeout(timeout);
+        }
+
+        let mut transaction = connection.begin().await?;
+
+        match insert.exec(&mut *transaction).await {
+            Ok(_) => {
+                let model = select.one(&mut *transaction).await?;
+                transaction.commit().await?;
+
+                Ok((model, true))
+            }
+            // Another connection inserted the entry in the meantime.
+            Err(e @ Error::UniqueViolation { .. }) => {
+                transaction.rollback().await?;
+
+                match select.one(&mut *connection).await {
+                    Ok(model) => Ok((model, false)),
+                    // The violated constraint does not cover the condition.
+                    Err(Error::NotFound { .. }) => Err(e),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Execute the lookup on the primary database of `router`. See [`exec`](Self::exec).
+    ///
+    /// # Errors
+    ///
+    /// If no connection could be acquired, or if a statement failed. See [`Error`] for more
+    /// information.
+    pub async fn exec_routed(self, router: &DatabaseRouter<T::Database>) -> Result<(T::Model, bool)>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let mut connection = router.writer().await?;
+
+        self.exec(&mut *connection).await
+    }
+}
@@ -0,0 +1,117 @@
+//! Hints for the query planner of a specific database, for queries it gets wrong despite up to
+//! date statistics:
+//!
+//! ```ignore
+//! let orders = order::Entity::find()
+//!     .filter(order::columns::Status::eq("open"))
+//!     .hint(QueryHint::Index(IndexHint::Force, "order_status_idx"))
+//!     .hint(QueryHint::Disable(PlannerMethod::SeqScan))
+//!     .all(&mut *conn)
+//!     .await?;
+//! ```
+//!
+//! Every hint applies to one backend only, and is ignored on all others, so that a query can carry
+//! the hints for each of the backends it runs on:
+//!
+//! - On Postgres, [`QueryHint::Disable`] runs `SET LOCAL enable_seqscan = off` (or the setting
+//!   of another [`PlannerMethod`]) before the query, inside a transaction (or a savepoint) that
+//!   only spans the query. Releasing a savepoint keeps such settings in effect for the rest of
+//!   the outer transaction, so the previous value is restored after the query.
+//! - On mysql, [`QueryHint::Index`] is rendered after the table of the query, e.g.
+//!   `FROM "order" FORCE INDEX ("order_status_idx")`, and [`QueryHint::Optimizer`] right after
+//!   `SELECT`, e.g. `SELECT /*+ MAX_EXECUTION_TIME(1000) */ ..`.
+//!
+//! Hints are a last resort: they keep the planner from adapting to the data as it changes.
+
+use std::fmt::Display;
+
+/// A method of the Postgres planner, which can be disabled for a query with
+/// [`QueryHint::Disable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlannerMethod {
+    SeqScan,
+    IndexScan,
+    IndexOnlyScan,
+    BitmapScan,
+    NestLoop,
+    HashJoin,
+    MergeJoin,
+    HashAgg,
+    Sort,
+    Material,
+}
+
+impl PlannerMethod {
+    /// The name of the setting enabling this method, e.g. `enable_seqscan`.
+    #[must_use]
+    pub const fn setting(self) -> &'static str {
+        match self {
+            Self::SeqScan => "enable_seqscan",
+            Self::IndexScan => "enable_indexscan",
+            Self::IndexOnlyScan => "enable_indexonlyscan",
+            Self::BitmapScan => "enable_bitmapscan",
+            Self::NestLoop => "enable_nestloop",
+            Self::HashJoin => "enable_hashjoin",
+            Self::MergeJoin => "enable_mergejoin",
+            Self::HashAgg => "enable_hashagg",
+            Self::Sort => "enable_sort",
+            Self::Material => "enable_material",
+        }
+    }
+}
+
+/// How a mysql index hint restricts the indexes considered for a query, see [`QueryHint::Index`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexHint {
+    /// Only consider the index, unless a full table scan is cheaper.
+    Use,
+    /// Only consider the index, and only scan the whole table if it cannot be used at all.
+    Force,
+    /// Never consider the index.
+    Ignore,
+}
+
+impl Display for IndexHint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Use => "USE INDEX",
+                Self::Force => "FORCE INDEX",
+                Self::Ignore => "IGNORE INDEX",
+            }
+        )
+    }
+}
+
+/// A hint for the planner of one backend, ignored by all others. See the
+/// [module documentation](self).
+///
+/// Names and hints are pushed into the query verbatim, which is why they have to be `'static`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryHint {
+    /// For Postgres, disable a method of the planner for the query.
+    ///
+    /// SQL: `SET LOCAL enable_seqscan = off`
+    Disable(PlannerMethod),
+    /// For mysql, restrict the indexes considered for the table of the query to the named one.
+    ///
+    /// SQL: `FROM "table" FORCE INDEX ("index")`
+    Index(IndexHint, &'static str),
+    /// For mysql, an optimizer hint, e.g. `MAX_EXECUTION_TIME(1000)`.
+    ///
+    /// SQL: `SELECT /*+ MAX_EXECUTION_TIME(1000) */ ..`
+    Optimizer(&'static str),
+}
+
+impl QueryHint {
+    /// Whether this hint applies to the backend named `database`, see
+    /// [`Database::NAME`](sqlx::Database::NAME).
+    pub(crate) fn applies_to(&self, database: &str) -> bool {
+        match self {
+            Self::Disable(_) => database == "PostgreSQL",
+            Self::Index(..) | Self::Optimizer(_) => database == "MySQL",
+        }
+    }
+}
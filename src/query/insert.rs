@@ -0,0 +1,471 @@
+use std::marker::PhantomData;
+
+#[cfg(feature = "exec")]
+use sqlx::{Connection, Executor, IntoArguments};
+use sqlx::{Database, QueryBuilder};
+
+#[cfg(feature = "exec")]
+use std::time::Duration;
+
+#[cfg(feature = "exec")]
+use super::{
+    exec_result::{ExecResult, IntoExecResult},
+    with_timeout,
+};
+#[cfg(feature = "exec")]
+use crate::router::DatabaseRouter;
+
+use crate::entity::{
+    Entity,
+    column::{ColumnAssignment, ColumnName},
+    model::{ActiveModel, ColumnValue},
+};
+
+#[cfg(feature = "statement")]
+use super::statement::Statement;
+use super::{PushToQuery, max_bind_parameters};
+
+/// An `INSERT` statement, inserting one or more rows into the table of entity `T`.
+///
+/// Only columns that are set on each [`ActiveModel`] (or given via [`values`](Self::values)) are
+/// inserted. If rows differ in which columns they set, the missing values are filled in with
+/// `DEFAULT`.
+///
+/// Rows that cannot be inserted by a single statement are inserted by several, inside a
+/// transaction (see [`exec`](Self::exec)):
+///
+/// - Rows setting no column at all are inserted with one `DEFAULT VALUES` statement each, or
+///   `() VALUES ()` on mysql.
+/// - Rows beyond the bind parameter limit of the database (see [`max_bind_parameters`]) are
+///   inserted by another statement.
+/// - Sqlite does not support `DEFAULT` in a list of values, so consecutive rows setting the same
+///   columns are inserted by a statement of their own instead.
+pub struct Insert<T>
+where
+    T: Entity,
+{
+    marker: PhantomData<T>,
+    rows: Vec<Vec<ColumnValue<T::Database>>>,
+    #[cfg(feature = "exec")]
+    timeout: Option<Duration>,
+}
+
+impl<T> Insert<T>
+where
+    T: Entity,
+{
+    pub(crate) const fn new() -> Self {
+        Self {
+            marker: PhantomData,
+            rows: vec![],
+            #[cfg(feature = "exec")]
+            timeout: None,
+        }
+    }
+
+    /// Add another row to be inserted.
+    #[must_use]
+    pub fn row<A>(mut self, active_model: &A) -> Self
+    where
+        A: ActiveModel<Model = T::Model>,
+    {
+        self.rows.push(active_model.values());
+        self
+    }
+
+    /// Add another row to be inserted, given as column values, e.g.
+    /// `values([columns::Name::value(name), columns::Status::value("draft")])`.
+    #[must_use]
+    pub fn values<I>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = ColumnAssignment<T>>,
+    {
+        self.rows.push(
+            values
+                .into_iter()
+                .map(ColumnAssignment::into_column_value)
+                .collect(),
+        );
+        self
+    }
+
+    /// Add multiple rows to be inserted.
+    #[must_use]
+    pub fn rows<'a, A, I>(self, active_models: I) -> Self
+    where
+        A: ActiveModel<Model = T::Model> + 'a,
+        I: IntoIterator<Item = &'a A>,
+    {
+        active_models.into_iter().fold(self, Self::row)
+    }
+
+    /// The number of rows to be inserted.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether there are no rows to be inserted.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The names of all columns set in any of `rows`, in order of their first occurrence.
+    fn column_names<'r, I>(rows: I) -> Vec<&'static str>
+    where
+        I: IntoIterator<Item = &'r Vec<ColumnValue<T::Database>>>,
+        T::Database: 'r,
+    {
+        rows.into_iter()
+            .flatten()
+            .map(|(name, _)| *name)
+            .fold(vec![], |mut acc, curr| {
+                if !acc.contains(&curr) {
+                    acc.push(curr);
+                }
+                acc
+            })
+    }
+
+    /// The rows of each statement needed to insert all rows, see the [type documentation](Self).
+    fn statement_rows(&self) -> Vec<&[Vec<ColumnValue<T::Database>>]> {
+        let columns_of = |row: &Vec<ColumnValue<T::Database>>| {
+            let mut columns = row.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+            columns.sort_unstable();
+            columns
+        };
+
+        let max_parameters = max_bind_parameters::<T::Database>();
+        let mut statements: Vec<&[_]> = vec![];
+        let mut start = 0;
+        let mut parameters = 0;
+
+        // Rows without any value can only be inserted with `DEFAULT VALUES`, one per statement.
+        for end in 1..=self.rows.len() {
+            parameters += self.rows[end - 1].len();
+
+            let split = end == self.rows.len()
+                || self.rows[end].is_empty()
+                || self.rows[end - 1].is_empty()
+                || parameters + self.rows[end].len() > max_parameters
+                || (<T::Database as Database>::NAME == "SQLite"
+                    && columns_of(&self.rows[end]) != columns_of(&self.rows[end - 1]));
+
+            if split {
+                statements.push(&self.rows[start..end]);
+                start = end;
+                parameters = 0;
+            }
+        }
+
+        statements
+    }
+
+    /// Push the statement inserting `rows`, none of which may be empty unless it is the only one.
+    fn push_statement(
+        builder: &mut QueryBuilder<'_, T::Database>,
+        rows: &[Vec<ColumnValue<T::Database>>],
+    ) {
+        builder.push("INSERT INTO ");
+        builder.push(T::QUALIFIED_TABLE_NAME);
+
+        let column_names = Self::column_names(rows);
+
+        if column_names.is_empty() {
+            // mysql does not support `DEFAULT VALUES`, but an empty list of columns and values.
+            builder.push(if <T::Database as Database>::NAME == "MySQL" {
+                " () VALUES ()"
+            } else {
+                " DEFAULT VALUES"
+            });
+            return;
+        }
+
+        builder.push(" (");
+        column_names.iter().enumerate().for_each(|(i, e)| {
+            if i > 0 {
+                builder.push(", ");
+            }
+            ColumnName::new(e.to_string()).push_to(builder);
+        });
+        builder.push(") VALUES ");
+
+        rows.iter().enumerate().for_each(|(i, row)| {
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder.push("(");
+            column_names.iter().enumerate().for_each(|(i, name)| {
+                if i > 0 {
+                    builder.push(", ");
+                }
+                match row.iter().find(|(e, _)| e == name) {
+                    Some((_, value)) => value.push_to(builder),
+                    None => {
+                        builder.push("DEFAULT");
+                    }
+                }
+            });
+            builder.push(")");
+        });
+    }
+
+    /// Return the raw SQL query of this statement. Note that the returned query is
+    /// backend-agnostic, e.g. query parameters will be substituted with `?` instead of `$1` (in
+    /// the case of postgres).
+    ///
+    /// This is mainly useful for debugging purposes, and not intended to produce queries to be run
+    /// on an actual database.
+    #[must_use]
+    pub fn query(&self) -> String {
+        let mut builder = QueryBuilder::new("");
+        self.push_to(&mut builder);
+        builder.into_sql()
+    }
+
+    /// Render the statement into a [`Statement`], to be run by a driver outside of sqlx.
+    ///
+    /// # Errors
+    ///
+    /// If one of the statement's parameters failed to encode.
+    #[cfg(feature = "statement")]
+    pub fn statement(&self) -> Result<Statement, sqlx::Error>
+    where
+        T: Entity<Database = sqlx::Any>,
+    {
+        Statement::build(self)
+    }
+
+    /// Fail with [`Error::Timeout`](crate::Error::Timeout) if executing the statement takes longer
+    /// than `timeout`. See [`Select::timeout`](super::select::Select::timeout).
+    #[cfg(feature = "exec")]
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Execute the statement, returning the number of affected rows and, on some databases, the ID
+    /// of the last inserted row.
+    ///
+    /// If the rows need more than one statement (see the [type documentation](Self)), all of them
+    /// are run inside a transaction, or a savepoint if the connection is inside one already.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or if the statement violates a
+    /// constraint. See [`Error`](crate::Error) for more information.
+    #[cfg(feature = "exec")]
+    pub async fn exec<C>(self, connection: &mut C) -> crate::Result<ExecResult>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        C: Connection<Database = T::Database>,
+        for<'e> &'e mut C: Executor<'e, Database = T::Database>,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let statements = self.statement_rows();
+
+        if statements.is_empty() {
+            return Ok(ExecResult::default());
+        }
+
+        if let [rows] = statements.as_slice() {
+            let mut builder = QueryBuilder::new("");
+            Self::push_statement(&mut builder, rows);
+
+            let result = with_timeout(
+                T::TABLE_NAME,
+                self.timeout,
+                (&mut *connection).execute(builder.build()),
+            )
+            .await??;
+
+            return Ok(result.into_exec_result());
+        }
+
+        let mut transaction = connection.begin().await?;
+        let mut result = ExecResult::default();
+
+        for rows in statements {
+            let mut builder = QueryBuilder::new("");
+            Self::push_statement(&mut builder, rows);
+
+            let statement_result = with_timeout(
+                T::TABLE_NAME,
+                self.timeout,
+                (&mut *transaction).execute(builder.build()),
+            )
+            .await??
+            .into_exec_result();
+
+            result.rows_affected += statement_result.rows_affected;
+            result.last_insert_id = statement_result.last_insert_id;
+        }
+
+        transaction.commit().await?;
+
+        Ok(result)
+    }
+
+    /// Execute the statement on the primary database of `router`. See [`exec`](Self::exec).
+    ///
+    /// # Errors
+    ///
+    /// If no connection could be acquired, or if the statement failed. See
+    /// [`Error`](crate::Error) for more information.
+    #[cfg(feature = "exec")]
+    pub async fn exec_routed(
+        self,
+        router: &DatabaseRouter<T::Database>,
+    ) -> crate::Result<ExecResult>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let mut connection = router.writer().await?;
+
+        self.exec(&mut *connection).await
+    }
+}
+
+/// Statements inserting rows that need more than one statement are separated by `; `. An insert
+/// without any rows renders nothing, since [`exec`](Insert::exec) does not write anything for it
+/// either.
+impl<T> PushToQuery<T::Database> for Insert<T>
+where
+    T: Entity,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, T::Database>) {
+        self.statement_rows()
+            .iter()
+            .enumerate()
+            .for_each(|(i, rows)| {
+                if i > 0 {
+                    builder.push("; ");
+                }
+                Self::push_statement(builder, rows);
+            });
+    }
+}
+
+// The entities of these tests are declared for the databases of these features.
+#[cfg(all(test, any(feature = "postgres", feature = "sqlite")))]
+mod test {
+    use crate::entity::Entity;
+
+    #[cfg(feature = "sqlite")]
+    mod sqlite_item {
+        use crate::DatabaseModel;
+
+        #[derive(DatabaseModel)]
+        #[sky_orm(primary_key = id, table = "item", database = sqlx::Sqlite)]
+        pub struct Model {
+            pub id: i64,
+            pub name: String,
+            pub qty: i64,
+        }
+    }
+
+    #[cfg(feature = "postgres")]
+    mod postgres_item {
+        use crate::DatabaseModel;
+
+        #[derive(DatabaseModel)]
+        #[sky_orm(primary_key = id, table = "item")]
+        pub struct Model {
+            pub id: i64,
+            pub name: String,
+            pub qty: i64,
+        }
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_split_by_bind_parameters() {
+        let rows = (0..400)
+            .map(|i| {
+                let mut row = sqlite_item::ActiveModel::default();
+                row.set_id(i);
+                row.set_name(i.to_string());
+                row.set_qty(i);
+                row
+            })
+            .collect::<Vec<_>>();
+
+        let query = sqlite_item::Entity::insert().rows(&rows).query();
+        let statements = query.split("; ").collect::<Vec<_>>();
+
+        // 999 bind parameters fit 333 rows of 3 values each.
+        assert_eq!(statements.len(), 2);
+        assert_eq!(statements[0].matches("(?, ?, ?)").count(), 333);
+        assert_eq!(statements[1].matches("(?, ?, ?)").count(), 67);
+        assert!(
+            statements
+                .iter()
+                .all(|e| e.starts_with(r#"INSERT INTO "item" ("id", "name", "qty") VALUES "#))
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_split_by_empty_rows() {
+        let mut row = sqlite_item::ActiveModel::default();
+        row.set_name("a".to_string());
+
+        let query = sqlite_item::Entity::insert()
+            .rows([&row, &sqlite_item::ActiveModel::default(), &row])
+            .query();
+
+        assert_eq!(
+            query,
+            r#"INSERT INTO "item" ("name") VALUES (?); INSERT INTO "item" DEFAULT VALUES; INSERT INTO "item" ("name") VALUES (?)"#
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn test_split_by_columns_on_sqlite() {
+        let mut name = sqlite_item::ActiveModel::default();
+        name.set_name("a".to_string());
+        let mut qty = sqlite_item::ActiveModel::default();
+        qty.set_qty(1);
+
+        let query = sqlite_item::Entity::insert()
+            .rows([&name, &name, &qty])
+            .query();
+
+        assert_eq!(
+            query,
+            r#"INSERT INTO "item" ("name") VALUES (?), (?); INSERT INTO "item" ("qty") VALUES (?)"#
+        );
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    fn test_default_for_missing_columns() {
+        let mut name = postgres_item::ActiveModel::default();
+        name.set_name("a".to_string());
+        let mut qty = postgres_item::ActiveModel::default();
+        qty.set_qty(1);
+
+        let query = postgres_item::Entity::insert()
+            .rows([&name, &name, &qty])
+            .query();
+
+        assert_eq!(
+            query,
+            r#"INSERT INTO "item" ("name", "qty") VALUES ($1, DEFAULT), ($2, DEFAULT), (DEFAULT, $3)"#
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        #[cfg(feature = "postgres")]
+        assert_eq!(postgres_item::Entity::insert().query(), "");
+        #[cfg(feature = "sqlite")]
+        assert_eq!(sqlite_item::Entity::insert().query(), "");
+    }
+}
@@ -12,3 +12,51 @@ where
     /// On parse failure. See [`sqlx::Error`] for more information.
     fn parse_from_row(row: &<DB as Database>::Row) -> Result<Self, sqlx::Error>;
 }
+
+/// A struct parsed from some of the columns of an entity's table.
+///
+/// Used to select only those columns via
+/// [`Select::into_model`](crate::query::select::Select::into_model). Derived by
+/// [`FromSqlxRow`](crate::FromSqlxRow) for structs without flattened fields.
+pub trait Projection {
+    /// The names of the columns this struct is parsed from.
+    const COLUMN_NAMES: &'static [&'static str];
+}
+
+/// Whether `name` is one of `names`, to verify the columns of a [`Projection`] at compile time.
+#[doc(hidden)]
+#[must_use]
+pub const fn contains_column(names: &[&str], name: &str) -> bool {
+    let mut i = 0;
+
+    while i < names.len() {
+        if str_eq(names[i], name) {
+            return true;
+        }
+
+        i += 1;
+    }
+
+    false
+}
+
+/// String comparison, which is not available in const contexts yet.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+
+        i += 1;
+    }
+
+    true
+}
@@ -1,35 +1,154 @@
-use std::{marker::PhantomData, sync::Arc};
+#[cfg(all(feature = "postgres", feature = "exec"))]
+pub mod children;
 
+use std::{fmt::Display, marker::PhantomData};
+#[cfg(feature = "exec")]
+use std::{hash::Hash, time::Duration};
+
+#[cfg(feature = "exec")]
 use futures::StreamExt;
 use itertools::Itertools;
-use sqlx::{Connection, Database, Executor, IntoArguments, QueryBuilder};
+#[cfg(feature = "exec")]
+use sqlx::{Arguments, Connection, Execute, Executor, IntoArguments};
+use sqlx::{Database, Encode, QueryBuilder};
 
 use crate::entity::{
-    Entity,
-    column::{Column, EntityConditionExpr},
-    relation::{InverseRelated, Related},
+    Entity, HasPrimaryKey,
+    column::{
+        Aliased, Column, ColumnByNameError, ColumnEnum, ColumnName, ColumnTuple, ComparableColumn,
+        EntityConditionExpr, TableAlias, UnknownColumn,
+    },
+    relation::{
+        CompositeRelated, ForeignKey, Forward, InverseRelated, Joinable, PrimaryKeyType, Related,
+    },
 };
 
-use super::{BinaryExpr, BinaryExprOperand, BracketsExpr, PushToQuery, parse::ParseFromRow};
+use super::aggregate::AggregateSubquery;
+use super::hint::QueryHint;
+use super::parse::Projection;
+#[cfg(feature = "statement")]
+use super::statement::Statement;
+use super::{BinaryExpr, BinaryExprOperand, PushToQuery, QueryVariable};
+#[cfg(feature = "exec")]
+use super::{cache::QueryCache, parse::ParseFromRow, with_timeout};
+#[cfg(feature = "exec")]
+use crate::{Error, Result, router::DatabaseRouter};
+
+/// The direction to sort the results of a query in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl Display for Order {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Asc => write!(f, "ASC"),
+            Self::Desc => write!(f, "DESC"),
+        }
+    }
+}
+
+/// A table joined with `INNER JOIN`, see [`Select::where_related_through`].
+struct JoinClause {
+    table: String,
+    left: ColumnName,
+    right: ColumnName,
+}
+
+/// An expression of the `ORDER BY` clause, along with its direction.
+struct OrderByExpr<'a, DB>
+where
+    DB: Database + Sync,
+{
+    expr: Box<dyn PushToQuery<DB> + 'a>,
+    order: Order,
+}
+
+impl<DB> PushToQuery<DB> for OrderByExpr<'_, DB>
+where
+    DB: Database + Sync,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.expr.push_to(builder);
+        builder.push(format_args!(" {}", self.order));
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, DB>) {
+        self.expr.push_binds(builder);
+    }
+}
+
+/// SQL pushed by a closure, see [`Select::append_sql`].
+struct AppendedSql<F>(F);
+
+impl<F, DB> PushToQuery<DB> for AppendedSql<F>
+where
+    F: Fn(&mut QueryBuilder<'_, DB>) + Send + Sync,
+    DB: Database + Sync,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, DB>) {
+        (self.0)(builder);
+    }
+}
+
+/// An expression selected in addition to the columns, along with the name it is selected as.
+struct SelectedExpr<'a, DB>
+where
+    DB: Database + Sync,
+{
+    expr: Box<dyn PushToQuery<DB> + 'a>,
+    name: &'static str,
+}
 
-pub struct Select<T>
+pub struct Select<'a, T>
 where
-    T: Entity + 'static,
+    T: Entity,
 {
     marker: PhantomData<T>,
-    conditions: Vec<Arc<dyn PushToQuery<T::Database>>>,
+    /// The columns to select instead of all columns of `T`, see [`Select::into_model`].
+    columns: Option<&'static [&'static str]>,
+    /// Expressions selected after the columns, see [`SelectedExpr`].
+    expressions: Vec<SelectedExpr<'a, T::Database>>,
+    scope: Option<Box<dyn PushToQuery<T::Database> + 'a>>,
+    conditions: Vec<Box<dyn PushToQuery<T::Database> + 'a>>,
     additional_tables: Vec<String>,
+    joins: Vec<JoinClause>,
+    order_by: Vec<OrderByExpr<'a, T::Database>>,
+    limit: Option<u64>,
+    /// Raw SQL pushed after everything else, see [`Select::append_sql`].
+    appended_sql: Vec<Box<dyn PushToQuery<T::Database> + 'a>>,
+    /// Planner hints, see [`Select::hint`]. The ones that are not rendered into the query are
+    /// applied by [`Hinted`] when executing it.
+    hints: Vec<QueryHint>,
+    #[cfg(feature = "exec")]
+    on_primary: bool,
+    #[cfg(feature = "exec")]
+    timeout: Option<Duration>,
 }
 
-impl<T> Select<T>
+impl<'a, T> Select<'a, T>
 where
-    T: Entity + 'static,
+    T: Entity + 'a,
 {
     pub(crate) fn new() -> Self {
         Self {
             marker: PhantomData,
+            columns: None,
+            expressions: vec![],
+            scope: T::implicit_filter(),
             conditions: vec![],
             additional_tables: vec![],
+            joins: vec![],
+            order_by: vec![],
+            limit: None,
+            appended_sql: vec![],
+            hints: vec![],
+            #[cfg(feature = "exec")]
+            on_primary: false,
+            #[cfg(feature = "exec")]
+            timeout: None,
         }
     }
 
@@ -38,56 +157,530 @@ where
     #[must_use]
     pub fn filter<Q>(mut self, condition: EntityConditionExpr<Q, T>) -> Self
     where
-        Q: PushToQuery<T::Database> + 'static,
+        Q: PushToQuery<T::Database> + 'a,
     {
-        self.conditions.push(Arc::new(condition));
+        self.conditions.push(Box::new(condition));
+        self
+    }
+
+    /// Skip the [implicit filter](Entity::implicit_filter) of `T`, e.g. to query the rows of all
+    /// tenants.
+    #[must_use]
+    pub fn unscoped(mut self) -> Self {
+        self.scope = None;
         self
     }
 
+    /// Like [`filter`](Self::filter), but only if a condition is given.
+    #[must_use]
+    pub fn filter_opt<Q>(self, condition: Option<EntityConditionExpr<Q, T>>) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+    {
+        match condition {
+            Some(condition) => self.filter(condition),
+            None => self,
+        }
+    }
+
+    /// Apply `f` to this query if `value` is present, e.g. to filter by optional parameters of a
+    /// request:
+    ///
+    /// ```ignore
+    /// user::Entity::find()
+    ///     .apply_if(params.name, |q, name| q.filter(user::columns::Name::eq(name)))
+    ///     .apply_if(params.limit, Select::limit)
+    /// ```
+    #[must_use]
+    pub fn apply_if<V, F>(self, value: Option<V>, f: F) -> Self
+    where
+        F: FnOnce(Self, V) -> Self,
+    {
+        match value {
+            Some(value) => f(self, value),
+            None => self,
+        }
+    }
+
     /// Append a new `WHERE` condition using an `AND` statement as glue, allowing to filter the
     /// columns of a related entity (the foreign key is on `R`). The passed condition is wrapped
     /// in `()` brackets.
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T` and `R`, e.g.
+    /// by converting the condition of another entity with [`From`].
     #[must_use]
     pub fn where_relation<C, Q, R>(mut self, condition: EntityConditionExpr<Q, R>) -> Self
     where
-        Q: PushToQuery<T::Database> + 'static,
+        Q: PushToQuery<T::Database> + 'a,
         R: Related<T, C, Database = T::Database> + 'static,
-        T: InverseRelated<R, C>,
-        C: Column<Entity = R, Type = <T::PrimaryKeyColumn as Column>::Type>,
-        <T::PrimaryKeyColumn as Column>::Type: PartialEq,
+        T: InverseRelated<R, C> + HasPrimaryKey,
+        C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T>>>,
     {
-        self.conditions.push(Arc::new(condition));
-        self.conditions.push(Arc::new(BinaryExpr::new(
+        assert_condition_tables::<T, _>(&condition, &[R::TABLE_NAME]);
+        self.conditions.push(Box::new(condition));
+        self.conditions.push(Box::new(BinaryExpr::new(
             C::full_column_name(),
-            <T::PrimaryKeyColumn as Column>::full_column_name(),
+            <<T as HasPrimaryKey>::PrimaryKeyColumn as Column>::full_column_name(),
             BinaryExprOperand::Equals,
         )));
-        self.additional_tables.push(R::TABLE_NAME.to_string());
+        self.push_table(R::QUALIFIED_TABLE_NAME.to_string());
         self
     }
 
     /// Append a new `WHERE` condition using an `AND` statement as glue, allowing to filter the
     /// columns of an inversely related entity (the foreign key is on `T`). The passed condition is
     /// wrapped in `()` brackets.
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T` and `R`, e.g.
+    /// by converting the condition of another entity with [`From`].
     #[must_use]
     pub fn where_inverse_relation<C, Q, R>(mut self, condition: EntityConditionExpr<Q, R>) -> Self
     where
-        Q: PushToQuery<T::Database> + 'static,
-        R: InverseRelated<T, C, Database = T::Database> + 'static,
+        Q: PushToQuery<T::Database> + 'a,
+        R: InverseRelated<T, C, Database = T::Database> + HasPrimaryKey + 'static,
+        T: Related<R, C>,
+        C: ComparableColumn<Entity = T, Type: ForeignKey<PrimaryKeyType<R>>>,
+    {
+        assert_condition_tables::<T, _>(&condition, &[R::TABLE_NAME]);
+        self.conditions.push(Box::new(condition));
+        self.conditions.push(Box::new(BinaryExpr::new(
+            C::full_column_name(),
+            <<R as HasPrimaryKey>::PrimaryKeyColumn as Column>::full_column_name(),
+            BinaryExprOperand::Equals,
+        )));
+        self.push_table(R::QUALIFIED_TABLE_NAME.to_string());
+        self
+    }
+
+    /// Like [`where_relation`](Self::where_relation), but selecting the relation by its foreign key
+    /// column `via` instead of a type parameter, for entities that are related to `T` more than
+    /// once, e.g. `where_relation_via(message::columns::SenderId, condition)`.
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T` and `R`, see
+    /// [`where_relation`](Self::where_relation).
+    #[must_use]
+    pub fn where_relation_via<C, Q, R>(self, _via: C, condition: EntityConditionExpr<Q, R>) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+        R: Related<T, C, Database = T::Database> + 'static,
+        T: InverseRelated<R, C> + HasPrimaryKey,
+        C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T>>>,
+    {
+        self.where_relation::<C, Q, R>(condition)
+    }
+
+    /// Like [`where_inverse_relation`](Self::where_inverse_relation), but selecting the relation by
+    /// its foreign key column `via` instead of a type parameter.
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T` and `R`, see
+    /// [`where_inverse_relation`](Self::where_inverse_relation).
+    #[must_use]
+    pub fn where_inverse_relation_via<C, Q, R>(
+        self,
+        _via: C,
+        condition: EntityConditionExpr<Q, R>,
+    ) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+        R: InverseRelated<T, C, Database = T::Database> + HasPrimaryKey + 'static,
+        T: Related<R, C>,
+        C: ComparableColumn<Entity = T, Type: ForeignKey<PrimaryKeyType<R>>>,
+    {
+        self.where_inverse_relation::<C, Q, R>(condition)
+    }
+
+    /// Like [`where_relation`](Self::where_relation), but joining `R` under the alias `A`, so that
+    /// its table can be joined more than once, e.g. through two different relations. The passed
+    /// condition should refer to the columns of `R` via [`Aliased`]:
+    ///
+    /// ```ignore
+    /// post::Entity::find().where_relation_as::<Reply, reply::columns::PostId, _, _>(
+    ///     Aliased::<reply::columns::Body, Reply>::eq(body),
+    /// )
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the one of `T`, and the alias
+    /// `A`.
+    #[must_use]
+    pub fn where_relation_as<A, C, Q, R>(mut self, condition: EntityConditionExpr<Q, R>) -> Self
+    where
+        A: TableAlias,
+        Q: PushToQuery<T::Database> + 'a,
+        R: Related<T, C, Database = T::Database> + 'static,
+        T: InverseRelated<R, C> + HasPrimaryKey,
+        C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T>>>,
+    {
+        assert_condition_tables::<T, _>(&condition, &[A::ALIAS]);
+        self.conditions.push(Box::new(condition));
+        self.conditions.push(Box::new(BinaryExpr::new(
+            Aliased::<C, A>::full_column_name(),
+            <<T as HasPrimaryKey>::PrimaryKeyColumn as Column>::full_column_name(),
+            BinaryExprOperand::Equals,
+        )));
+        self.push_table(aliased_table::<R, A>());
+        self
+    }
+
+    /// Like [`where_inverse_relation`](Self::where_inverse_relation), but joining `R` under the
+    /// alias `A`, e.g. to filter messages by both their sender and their recipient account. The
+    /// passed condition should refer to the columns of `R` via [`Aliased`].
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the one of `T`, and the alias
+    /// `A`.
+    #[must_use]
+    pub fn where_inverse_relation_as<A, C, Q, R>(
+        mut self,
+        condition: EntityConditionExpr<Q, R>,
+    ) -> Self
+    where
+        A: TableAlias,
+        Q: PushToQuery<T::Database> + 'a,
+        R: InverseRelated<T, C, Database = T::Database> + HasPrimaryKey + 'static,
         T: Related<R, C>,
-        C: Column<Entity = T, Type = <R::PrimaryKeyColumn as Column>::Type>,
-        <R::PrimaryKeyColumn as Column>::Type: PartialEq,
+        C: ComparableColumn<Entity = T, Type: ForeignKey<PrimaryKeyType<R>>>,
     {
-        self.conditions.push(Arc::new(condition));
-        self.conditions.push(Arc::new(BinaryExpr::new(
+        assert_condition_tables::<T, _>(&condition, &[A::ALIAS]);
+        self.conditions.push(Box::new(condition));
+        self.conditions.push(Box::new(BinaryExpr::new(
             C::full_column_name(),
-            <R::PrimaryKeyColumn as Column>::full_column_name(),
+            Aliased::<<R as HasPrimaryKey>::PrimaryKeyColumn, A>::full_column_name(),
+            BinaryExprOperand::Equals,
+        )));
+        self.push_table(aliased_table::<R, A>());
+        self
+    }
+
+    /// Like [`where_relation`](Self::where_relation), but for a relation backed by a composite
+    /// foreign key on `R`, see [`CompositeRelated`]. The entries are related when the foreign key
+    /// columns `C` equal the columns of `T` they reference.
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T` and `R`, e.g.
+    /// by converting the condition of another entity with [`From`].
+    #[must_use]
+    pub fn where_composite_relation<C, Q, R>(mut self, condition: EntityConditionExpr<Q, R>) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+        R: CompositeRelated<T, C, Database = T::Database> + 'static,
+        C: ColumnTuple<Entity = R> + 'static,
+    {
+        assert_condition_tables::<T, _>(&condition, &[R::TABLE_NAME]);
+        self.conditions.push(Box::new(condition));
+        self.conditions.push(Box::new(BinaryExpr::new(
+            C::column_row(),
+            <R::ReferencedColumns as ColumnTuple>::column_row(),
+            BinaryExprOperand::Equals,
+        )));
+        self.push_table(R::QUALIFIED_TABLE_NAME.to_string());
+        self
+    }
+
+    /// Like [`where_inverse_relation`](Self::where_inverse_relation), but for a relation backed by
+    /// a composite foreign key on `T`, see [`CompositeRelated`].
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T` and `R`, e.g.
+    /// by converting the condition of another entity with [`From`].
+    #[must_use]
+    pub fn where_inverse_composite_relation<C, Q, R>(
+        mut self,
+        condition: EntityConditionExpr<Q, R>,
+    ) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+        R: Entity<Database = T::Database> + 'static,
+        T: CompositeRelated<R, C>,
+        C: ColumnTuple<Entity = T> + 'static,
+    {
+        assert_condition_tables::<T, _>(&condition, &[R::TABLE_NAME]);
+        self.conditions.push(Box::new(condition));
+        self.conditions.push(Box::new(BinaryExpr::new(
+            C::column_row(),
+            <T::ReferencedColumns as ColumnTuple>::column_row(),
             BinaryExprOperand::Equals,
         )));
-        self.additional_tables.push(R::TABLE_NAME.to_string());
+        self.push_table(R::QUALIFIED_TABLE_NAME.to_string());
+        self
+    }
+
+    /// Append a new `WHERE` condition using an `AND` statement as glue, allowing to filter by
+    /// aggregates over the children of each entry (the foreign key is on `R`), e.g.
+    /// `where_aggregate(order::columns::Total::max().gt(100))`. The condition should only refer to
+    /// [aggregates](crate::query::aggregate) of `R`, and is evaluated over the children within
+    /// their entity's [implicit filter](Entity::implicit_filter).
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T` and `R`, e.g.
+    /// by converting the condition of another entity with [`From`].
+    #[must_use]
+    pub fn where_aggregate<C, Q, R>(mut self, condition: EntityConditionExpr<Q, R>) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+        R: Related<T, C, Database = T::Database> + 'static,
+        T: InverseRelated<R, C> + HasPrimaryKey,
+        C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T>>>,
+    {
+        assert_condition_tables::<T, _>(&condition, &[R::TABLE_NAME]);
+        let (foreign_key, primary_key) = <R as Joinable<T, Forward<C>>>::join_columns();
+
+        self.conditions.push(Box::new(AggregateSubquery {
+            condition,
+            foreign_key,
+            primary_key,
+            scope: R::implicit_filter(),
+        }));
+        self
+    }
+
+    /// Like [`where_aggregate`](Self::where_aggregate), but selecting the relation by its foreign
+    /// key column `via` instead of a type parameter, for entities that are related to `T` more than
+    /// once.
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T` and `R`, see
+    /// [`where_aggregate`](Self::where_aggregate).
+    #[must_use]
+    pub fn where_aggregate_via<C, Q, R>(self, _via: C, condition: EntityConditionExpr<Q, R>) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+        R: Related<T, C, Database = T::Database> + 'static,
+        T: InverseRelated<R, C> + HasPrimaryKey,
+        C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T>>>,
+    {
+        self.where_aggregate::<C, Q, R>(condition)
+    }
+
+    /// Append a new `WHERE` condition using an `AND` statement as glue, only keeping the entries
+    /// without children in `R` (the foreign key is on `R`), e.g. the users without orders via
+    /// `where_missing_relation::<order::Entity, _>()`. Children outside of their entity's
+    /// [implicit filter](Entity::implicit_filter) are not counted.
+    ///
+    /// SQL: `NOT EXISTS (SELECT 1 FROM child WHERE "child"."parent_id" = "parent"."id")`
+    #[must_use]
+    pub fn where_missing_relation<R, C>(mut self) -> Self
+    where
+        R: Related<T, C, Database = T::Database> + 'static,
+        T: InverseRelated<R, C> + HasPrimaryKey,
+        C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T>>>,
+    {
+        let (foreign_key, primary_key) = <R as Joinable<T, Forward<C>>>::join_columns();
+
+        self.conditions.push(Box::new(MissingRelation::<R> {
+            foreign_key,
+            primary_key,
+            scope: R::implicit_filter(),
+        }));
+        self
+    }
+
+    /// Like [`where_missing_relation`](Self::where_missing_relation), but selecting the relation by
+    /// its foreign key column `via` instead of a type parameter, for entities that are related to
+    /// `T` more than once.
+    #[must_use]
+    pub fn where_missing_relation_via<R, C>(self, _via: C) -> Self
+    where
+        R: Related<T, C, Database = T::Database> + 'static,
+        T: InverseRelated<R, C> + HasPrimaryKey,
+        C: ComparableColumn<Entity = R, Type: ForeignKey<PrimaryKeyType<T>>>,
+    {
+        self.where_missing_relation::<R, C>()
+    }
+
+    /// Append a new `WHERE` condition using an `AND` statement as glue, allowing to filter the
+    /// columns of an entity two relations away, joined through the intermediate entity `M`. Each
+    /// hop is a [`Forward`](crate::entity::relation::Forward) or
+    /// [`Inverse`](crate::entity::relation::Inverse) relation, and can usually be inferred, e.g.
+    /// `where_related_through::<order::Entity, _, _, _, _>(product::columns::Name::eq(name))`.
+    /// The passed condition is wrapped in `()` brackets.
+    ///
+    /// SQL: `FROM t INNER JOIN m ON .. INNER JOIN r ON .. WHERE (condition)`
+    ///
+    /// # Panics
+    ///
+    /// If the condition refers to the columns of a table other than the ones of `T`, `M` and `R`.
+    #[must_use]
+    pub fn where_related_through<M, H1, H2, Q, R>(
+        mut self,
+        condition: EntityConditionExpr<Q, R>,
+    ) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+        T: Joinable<M, H1>,
+        M: Joinable<R, H2> + Entity<Database = T::Database>,
+        R: Entity<Database = T::Database> + 'static,
+    {
+        assert_condition_tables::<T, _>(&condition, &[M::TABLE_NAME, R::TABLE_NAME]);
+        let (left, right) = T::join_columns();
+        let (middle_left, middle_right) = M::join_columns();
+
+        self.conditions.push(Box::new(condition));
+        // The join of `R` refers to `M`, so it can only be joined if `M` is as well.
+        if self.push_join(M::QUALIFIED_TABLE_NAME.to_string(), left, right) {
+            self.push_join(
+                R::QUALIFIED_TABLE_NAME.to_string(),
+                middle_left,
+                middle_right,
+            );
+        } else {
+            self.push_table(R::QUALIFIED_TABLE_NAME.to_string());
+            self.conditions.push(Box::new(BinaryExpr::new(
+                middle_left,
+                middle_right,
+                BinaryExprOperand::Equals,
+            )));
+        }
+        self
+    }
+
+    /// Whether `table` is already part of the `FROM` clause, either listed or joined.
+    fn has_table(&self, table: &str) -> bool {
+        self.additional_tables.iter().any(|e| e == table)
+            || self.joins.iter().any(|e| e.table == table)
+    }
+
+    /// Add `table` to the tables listed in the `FROM` clause, unless it is part of it already.
+    fn push_table(&mut self, table: String) {
+        if !self.has_table(&table) {
+            self.additional_tables.push(table);
+        }
+    }
+
+    /// Join `table` on `left = right`, returning whether it was joined. A table that is already
+    /// part of the `FROM` clause is not joined again, and the equality becomes a condition instead,
+    /// since listed tables follow the joins, which an `ON` clause must not refer to.
+    fn push_join(&mut self, table: String, left: ColumnName, right: ColumnName) -> bool {
+        if self.has_table(&table) {
+            self.conditions.push(Box::new(BinaryExpr::new(
+                left,
+                right,
+                BinaryExprOperand::Equals,
+            )));
+            return false;
+        }
+
+        self.joins.push(JoinClause { table, left, right });
+        true
+    }
+
+    /// Append an expression to the `ORDER BY` clause. Results are sorted by all expressions in the
+    /// order they were added. To sort by a plain column, use
+    /// [`ColumnExt::column`](crate::entity::column::ColumnExt::column).
+    #[must_use]
+    pub fn order_by<Q>(mut self, expr: EntityConditionExpr<Q, T>, order: Order) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'a,
+    {
+        self.order_by.push(OrderByExpr {
+            expr: Box::new(expr),
+            order,
+        });
+        self
+    }
+
+    /// Sort by the column called `name` (its name in the database), e.g. as requested in a query
+    /// string. See [`order_by`](Self::order_by).
+    ///
+    /// # Errors
+    ///
+    /// If `T` has no column called `name`.
+    pub fn order_by_name(self, name: &str, order: Order) -> Result<Self, UnknownColumn> {
+        let column = name.parse::<T::Col>()?;
+
+        Ok(self.order_by(column.full_column_name().into(), order))
+    }
+
+    /// Append a condition comparing the column called `name` (its name in the database) against
+    /// `value` using `operand`, e.g. as requested in a query string. See [`filter`](Self::filter).
+    ///
+    /// SQL: `column [operand] $1`
+    ///
+    /// # Errors
+    ///
+    /// If `T` has no column called `name`, or if it cannot be compared with values of type `V`, see
+    /// [`ColumnEnum::accepts`].
+    pub fn filter_by_name<V>(
+        self,
+        name: &str,
+        operand: BinaryExprOperand,
+        value: V,
+    ) -> Result<Self, ColumnByNameError>
+    where
+        V: for<'q> Encode<'q, T::Database> + sqlx::Type<T::Database> + Send + Sync + 'static,
+    {
+        let column = name.parse::<T::Col>()?;
+
+        if !column.accepts::<V>() {
+            return Err(ColumnByNameError::MismatchedType {
+                entity: T::TABLE_NAME,
+                column: column.name(),
+                expected: column.type_name(),
+                found: std::any::type_name::<V>(),
+            });
+        }
+
+        let variable = QueryVariable::new(value);
+        let variable = match column.cast() {
+            Some(sql_type) => variable.cast(sql_type),
+            None => variable,
+        };
+
+        Ok(self.filter(BinaryExpr::new(column.full_column_name(), variable, operand).into()))
+    }
+
+    /// Return at most `limit` results.
+    #[must_use]
+    pub const fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Append raw SQL to the end of the query, pushed by `f`, e.g. a clause that sky-orm does not
+    /// support:
+    ///
+    /// ```ignore
+    /// event::Entity::find().append_sql(|builder| {
+    ///     builder.push(" SETTINGS max_threads = ");
+    ///     builder.push_bind(8);
+    /// })
+    /// ```
+    ///
+    /// This is an escape hatch, use it with care. Nothing checks that the result is valid SQL,
+    /// and the SQL is pushed verbatim, so it must never contain user input. Values have to be
+    /// bound with [`QueryBuilder::push_bind`] instead. `f` is called every time the query is
+    /// rendered, and has to push the same SQL and binds every time, since the binds of a query may
+    /// be pushed separately from its SQL, e.g. for [cached](Self::cached) queries.
+    #[must_use]
+    pub fn append_sql<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut QueryBuilder<'_, T::Database>) + Send + Sync + 'a,
+    {
+        self.appended_sql.push(Box::new(AppendedSql(f)));
         self
     }
 
+    /// Give the planner of one backend a hint on how to run this query, ignored by all other
+    /// backends. See [`QueryHint`].
+    #[must_use]
+    pub fn hint(self, hint: QueryHint) -> Hinted<'a, T> {
+        Hinted { select: self }.hint(hint)
+    }
+
     /// Return the raw SQL query of this statement. Note that the returned query is
     /// backend-agnostic, e.g. query parameters will be substituted with `?` instead of `$1` (in
     /// the case of postgres).
@@ -101,35 +694,196 @@ where
         builder.into_sql()
     }
 
+    /// Only select the columns of projection `P`, returning instances of it instead of `T`'s
+    /// model, e.g. to list entries without loading all of their columns:
+    ///
+    /// ```ignore
+    /// #[derive(FromSqlxRow)]
+    /// #[sky_orm(entity = user::Entity)]
+    /// struct UserListItem {
+    ///     id: i64,
+    ///     name: String,
+    /// }
+    ///
+    /// let users = user::Entity::find()
+    ///     .into_model::<UserListItem>()
+    ///     .all(&mut conn)
+    ///     .await?;
+    /// ```
+    #[must_use]
+    pub const fn into_model<P>(mut self) -> Projected<'a, T, P>
+    where
+        P: Projection,
+    {
+        self.columns = Some(P::COLUMN_NAMES);
+
+        Projected {
+            select: self,
+            marker: PhantomData,
+        }
+    }
+
+    /// Only select column `C`, returning its values instead of `T`'s models, e.g.
+    /// `user::Entity::find().pluck::<user::columns::Id>().all(&mut conn)` for a `Vec<i64>`.
+    #[must_use]
+    pub const fn pluck<C>(mut self) -> Plucked<'a, T, (C,), C::Type>
+    where
+        C: Column<Entity = T>,
+        C::Type: 'static,
+    {
+        self.columns = Some(<(C,) as ColumnTuple>::NAMES);
+
+        Plucked {
+            select: self,
+            #[cfg(feature = "exec")]
+            convert: |(value,)| value,
+            marker: PhantomData,
+        }
+    }
+
+    /// Only select the columns `C`, returning tuples of their values instead of `T`'s models, e.g.
+    /// `pluck2::<(user::columns::Id, user::columns::Name)>()` for `(i64, String)`s.
+    #[must_use]
+    pub const fn pluck2<C>(mut self) -> Plucked<'a, T, C, C::Type>
+    where
+        C: ColumnTuple<Entity = T>,
+    {
+        self.columns = Some(C::NAMES);
+
+        Plucked {
+            select: self,
+            #[cfg(feature = "exec")]
+            convert: |values| values,
+            marker: PhantomData,
+        }
+    }
+
+    /// Run this query with the SQL cached in `cache` under `key`, rendering it only if it hasn't
+    /// been cached yet. See [`QueryCache`] for how to choose the key.
+    #[cfg(feature = "exec")]
+    #[must_use]
+    pub const fn cached<K>(self, cache: &QueryCache<K>, key: K) -> CachedSelect<'_, 'a, T, K>
+    where
+        K: Hash + Eq,
+    {
+        CachedSelect {
+            select: self,
+            cache,
+            key,
+        }
+    }
+
+    /// Render the query into a [`Statement`], to be run by a driver outside of sqlx.
+    ///
+    /// # Errors
+    ///
+    /// If one of the query's parameters failed to encode.
+    #[cfg(feature = "statement")]
+    pub fn statement(&self) -> Result<Statement, sqlx::Error>
+    where
+        T: Entity<Database = sqlx::Any>,
+    {
+        Statement::build(self)
+    }
+
     /// Execute the query, returning a single result.
     ///
     /// # Errors
     ///
-    /// If no entry could be found, or if there's been a problem communicating with the database.
-    /// See [`sqlx::Error`] for more information.
-    pub async fn one<'c, C>(self, connection: &'c mut C) -> Result<T::Model, sqlx::Error>
+    /// If no entry could be found ([`Error::NotFound`]), or if there's been a problem communicating
+    /// with the database. See [`Error`] for more information.
+    #[cfg(feature = "exec")]
+    pub async fn one<'c, C>(self, connection: &'c mut C) -> Result<T::Model>
     where
         C: Connection<Database = T::Database>,
         &'c mut C: Executor<'c, Database = T::Database>,
         for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
     {
-        let mut builder = QueryBuilder::new("");
-        self.push_to(&mut builder);
+        self.one_as::<C, T::Model>(connection).await
+    }
 
-        drop(self);
+    /// Execute the query, returning all results.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`] for more
+    /// information.
+    #[cfg(feature = "exec")]
+    pub async fn all<'c, C>(self, connection: &'c mut C) -> Result<Vec<T::Model>>
+    where
+        C: Connection<Database = T::Database>,
+        &'c mut C: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        self.all_as::<C, T::Model>(connection).await
+    }
 
-        let result = connection.fetch_one(builder.build()).await?;
-        <T::Model as ParseFromRow<T::Database>>::parse_from_row(&result)
+    /// Fail with [`Error::Timeout`] if executing the query takes longer than `timeout`, including
+    /// the time it takes to fetch all rows. Only the wait for the query is cut short: the database
+    /// may keep running it until it notices that the connection has been dropped. Requires one of
+    /// the runtime features of sqlx.
+    #[cfg(feature = "exec")]
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
     }
 
-    /// Execute the query, returning all results.
+    /// Run this query on the primary database when [routed](Self::one_routed), instead of the
+    /// replica, e.g. to read rows that were just written and may not have been replicated yet.
+    #[cfg(feature = "exec")]
+    #[must_use]
+    pub const fn on_primary(mut self) -> Self {
+        self.on_primary = true;
+        self
+    }
+
+    /// Execute the query on the replica of `router` (or its primary, see
+    /// [`on_primary`](Self::on_primary)), returning a single result. See [`one`](Self::one).
+    ///
+    /// # Errors
+    ///
+    /// If no connection could be acquired, or if the query failed. See [`Error`] for more
+    /// information.
+    #[cfg(feature = "exec")]
+    pub async fn one_routed(self, router: &DatabaseRouter<T::Database>) -> Result<T::Model>
+    where
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let mut connection = router.reader(self.on_primary).await?;
+
+        self.one(&mut *connection).await
+    }
+
+    /// Execute the query on the replica of `router` (or its primary, see
+    /// [`on_primary`](Self::on_primary)), returning all results. See [`all`](Self::all).
     ///
     /// # Errors
     ///
-    /// If there's been a problem communicating with the database. See [`sqlx::Error`] for more
+    /// If no connection could be acquired, or if the query failed. See [`Error`] for more
     /// information.
-    pub async fn all<'c, C>(self, connection: &'c mut C) -> Result<Vec<T::Model>, sqlx::Error>
+    #[cfg(feature = "exec")]
+    pub async fn all_routed(self, router: &DatabaseRouter<T::Database>) -> Result<Vec<T::Model>>
+    where
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let mut connection = router.reader(self.on_primary).await?;
+
+        self.all(&mut *connection).await
+    }
+}
+
+impl<T> Select<'_, T>
+where
+    T: Entity,
+{
+    /// Execute the query, parsing a single result as `M`.
+    #[cfg(feature = "exec")]
+    async fn one_as<'c, C, M>(self, connection: &'c mut C) -> Result<M>
     where
+        M: ParseFromRow<T::Database>,
         C: Connection<Database = T::Database>,
         &'c mut C: Executor<'c, Database = T::Database>,
         for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
@@ -137,66 +891,632 @@ where
         let mut builder = QueryBuilder::new("");
         self.push_to(&mut builder);
 
+        let timeout = self.timeout;
         drop(self);
 
-        let result = connection
-            .fetch(builder.build())
-            .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .collect::<Result<Vec<_>, _>>()?;
+        // Kept for the error, since the arguments borrow the builder for as long as the
+        // connection.
+        let filter = builder.sql().to_string();
+
+        let result = match with_timeout(
+            T::TABLE_NAME,
+            timeout,
+            connection.fetch_one(builder.build()),
+        )
+        .await?
+        {
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(Error::NotFound {
+                    entity: T::TABLE_NAME,
+                    filter,
+                });
+            }
+            result => result?,
+        };
 
-        result
+        Ok(<M as ParseFromRow<T::Database>>::parse_from_row(&result)?)
+    }
+
+    /// Execute the query, parsing all results as `M`.
+    #[cfg(feature = "exec")]
+    async fn all_as<'c, C, M>(self, connection: &'c mut C) -> Result<Vec<M>>
+    where
+        M: ParseFromRow<T::Database>,
+        C: Connection<Database = T::Database>,
+        &'c mut C: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        let mut builder = QueryBuilder::new("");
+        self.push_to(&mut builder);
+
+        let timeout = self.timeout;
+        drop(self);
+
+        let result = with_timeout(
+            T::TABLE_NAME,
+            timeout,
+            connection.fetch(builder.build()).collect::<Vec<_>>(),
+        )
+        .await?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(result
             .iter()
-            .map(<T::Model as ParseFromRow<T::Database>>::parse_from_row)
-            .collect::<Result<Vec<_>, _>>()
+            .map(<M as ParseFromRow<T::Database>>::parse_from_row)
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// The conditions in the order they are rendered in, i.e. the implicit filter, then the last
+    /// two conditions, followed by the remaining ones in the order they were added.
+    fn ordered_conditions(&self) -> impl Iterator<Item = &dyn PushToQuery<T::Database>> {
+        let (rest, last) = self
+            .conditions
+            .split_at(self.conditions.len().saturating_sub(2));
+
+        self.scope
+            .iter()
+            .chain(last.iter().rev())
+            .chain(rest)
+            .map(AsRef::as_ref)
+    }
+}
+
+/// Check that `condition` only refers to the columns of `T` and of `tables`, since the type of a
+/// condition does not prevent it from referring to the columns of other entities, e.g. when
+/// converting a condition of one entity into one of another with [`From`].
+fn assert_condition_tables<T, Q>(condition: &Q, tables: &[&str])
+where
+    T: Entity,
+    Q: PushToQuery<T::Database>,
+{
+    let mut referred = vec![];
+    condition.push_tables(&mut referred);
+
+    if let Some(table) = referred
+        .into_iter()
+        .find(|e| *e != T::TABLE_NAME && !tables.contains(e))
+    {
+        panic!(
+            "Condition refers to the columns of table \"{table}\", which is not part of the query"
+        );
+    }
+}
+
+/// Push the columns of `E` from `table` (usually the one of `E`), each aliased the way models
+/// look their values up, i.e. `"table"."column" AS "__c0"`, see [`Column::select_alias`].
+pub(crate) fn push_aliased_columns<E>(builder: &mut QueryBuilder<'_, E::Database>, table: &str)
+where
+    E: Entity,
+{
+    E::COLUMN_NAMES.iter().enumerate().for_each(|(i, e)| {
+        if i > 0 {
+            builder.push(", ");
+        }
+        builder.push(format_args!("\"{table}\".\"{e}\" AS \"__c{i}\""));
+    });
+}
+
+/// The table of `E` joined under alias `A`, i.e. `"table" AS "alias"`.
+fn aliased_table<E, A>() -> String
+where
+    E: Entity,
+    A: TableAlias,
+{
+    format!("{} AS \"{}\"", E::QUALIFIED_TABLE_NAME, A::ALIAS)
+}
+
+/// The absence of entries of `R` referring to the current row of the outer query.
+struct MissingRelation<R>
+where
+    R: Entity,
+{
+    foreign_key: ColumnName,
+    primary_key: ColumnName,
+    scope: Option<Box<dyn PushToQuery<R::Database>>>,
+}
+
+impl<R> PushToQuery<R::Database> for MissingRelation<R>
+where
+    R: Entity,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, R::Database>) {
+        builder.push("NOT EXISTS (SELECT 1 FROM ");
+        builder.push(R::QUALIFIED_TABLE_NAME);
+        builder.push(" WHERE ");
+        self.foreign_key.push_to(builder);
+        builder.push(" = ");
+        self.primary_key.push_to(builder);
+        if let Some(scope) = &self.scope {
+            builder.push(" AND (");
+            scope.push_to(builder);
+            builder.push(")");
+        }
+        builder.push(")");
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, R::Database>) {
+        if let Some(scope) = &self.scope {
+            scope.push_binds(builder);
+        }
     }
 }
 
-impl<T> PushToQuery<T::Database> for Select<T>
+impl<T> PushToQuery<T::Database> for Select<'_, T>
 where
-    T: Entity + 'static,
+    T: Entity,
 {
-    // Unwraps are checked beforehand
-    #[allow(clippy::unwrap_used)]
     fn push_to(&self, builder: &mut sqlx::QueryBuilder<'_, T::Database>) {
-        builder.push("SELECT ");
+        let hints = self
+            .hints
+            .iter()
+            .filter(|e| e.applies_to(<T::Database as Database>::NAME))
+            .collect::<Vec<_>>();
 
-        T::COLUMN_NAMES.iter().enumerate().for_each(|(i, e)| {
-            if i > 0 {
+        if self.columns.is_none() && self.expressions.is_empty() && hints.is_empty() {
+            builder.push(T::BASE_SELECT);
+        } else {
+            builder.push("SELECT ");
+            let optimizer_hints = hints
+                .iter()
+                .filter_map(|e| match e {
+                    QueryHint::Optimizer(hint) => Some(hint),
+                    _ => None,
+                })
+                .join(" ");
+            if !optimizer_hints.is_empty() {
+                builder.push(format_args!("/*+ {optimizer_hints} */ "));
+            }
+            // Whole models are parsed by the aliases of their columns, see `Column::select_alias`,
+            // while projections are parsed by the plain names of theirs.
+            match self.columns {
+                Some(columns) => columns.iter().enumerate().for_each(|(i, e)| {
+                    if i > 0 {
+                        builder.push(", ");
+                    }
+                    builder.push(format_args!("\"{}\".\"{e}\"", T::TABLE_NAME));
+                }),
+                None => push_aliased_columns::<T>(builder, T::TABLE_NAME),
+            }
+            self.expressions.iter().for_each(|e| {
                 builder.push(", ");
+                e.expr.push_to(builder);
+                builder.push(format_args!(" AS \"{}\"", e.name));
+            });
+            builder.push(" FROM ");
+            builder.push(T::QUALIFIED_TABLE_NAME);
+            for hint in &hints {
+                if let QueryHint::Index(kind, index) = hint {
+                    builder.push(format_args!(" {kind} (\"{index}\")"));
+                }
             }
-            builder.push(format_args!("\"{}\".\"{}\"", T::TABLE_NAME, e));
+        }
+        self.joins.iter().for_each(|e| {
+            builder.push(format_args!(
+                " INNER JOIN {} ON {} = {}",
+                e.table, e.left, e.right
+            ));
         });
-
-        builder.push(" FROM ");
-        builder.push(T::TABLE_NAME);
-        self.additional_tables.iter().unique().for_each(|e| {
+        self.additional_tables.iter().for_each(|e| {
             builder.push(", ");
             builder.push(e);
         });
 
-        if !self.conditions.is_empty() {
-            let mut conditions = self.conditions.clone();
-
-            builder.push(" WHERE ");
-            if self.conditions.len() == 1 {
-                BracketsExpr::new(conditions.pop().unwrap()).push_to(builder);
-            } else {
-                let left: Box<dyn PushToQuery<T::Database>> =
-                    Box::new(BracketsExpr::new(conditions.pop().unwrap()));
-                let right: Box<dyn PushToQuery<T::Database>> =
-                    Box::new(BracketsExpr::new(conditions.pop().unwrap()));
-                let init = BinaryExpr::new(left, right, BinaryExprOperand::And);
-                let cond = conditions.into_iter().fold(init, |acc, curr| {
-                    BinaryExpr::new(
-                        Box::new(acc),
-                        Box::new(BracketsExpr::new(curr)),
-                        BinaryExprOperand::And,
-                    )
+        self.ordered_conditions().enumerate().for_each(|(i, e)| {
+            builder.push(if i == 0 { " WHERE (" } else { " AND (" });
+            e.push_to(builder);
+            builder.push(")");
+        });
+
+        if !self.order_by.is_empty() {
+            builder.push(" ORDER BY ");
+            self.order_by.iter().enumerate().for_each(|(i, e)| {
+                if i > 0 {
+                    builder.push(", ");
+                }
+                e.push_to(builder);
+            });
+        }
+
+        if let Some(limit) = self.limit {
+            builder.push(format_args!(" LIMIT {limit}"));
+        }
+
+        self.appended_sql.iter().for_each(|e| e.push_to(builder));
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, T::Database>) {
+        self.expressions
+            .iter()
+            .for_each(|e| e.expr.push_binds(builder));
+
+        self.ordered_conditions()
+            .for_each(|e| e.push_binds(builder));
+
+        self.order_by.iter().for_each(|e| e.push_binds(builder));
+
+        self.appended_sql.iter().for_each(|e| e.push_binds(builder));
+    }
+}
+
+/// A [`Select`] of only the columns of projection `P`, see [`Select::into_model`].
+pub struct Projected<'a, T, P>
+where
+    T: Entity,
+{
+    select: Select<'a, T>,
+    marker: PhantomData<P>,
+}
+
+impl<T, P> Projected<'_, T, P>
+where
+    T: Entity,
+{
+    /// Return the raw SQL query of this statement. See [`Select::query`].
+    #[must_use]
+    pub fn query(&self) -> String {
+        self.select.query()
+    }
+
+    /// Execute the query, returning a single result. See [`Select::one`].
+    ///
+    /// # Errors
+    ///
+    /// If no entry could be found ([`Error::NotFound`]), or if there's been a problem communicating
+    /// with the database. See [`Error`] for more information.
+    #[cfg(feature = "exec")]
+    pub async fn one<'c, C>(self, connection: &'c mut C) -> Result<P>
+    where
+        P: ParseFromRow<T::Database>,
+        C: Connection<Database = T::Database>,
+        &'c mut C: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        self.select.one_as::<C, P>(connection).await
+    }
+
+    /// Execute the query, returning all results. See [`Select::all`].
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`] for more
+    /// information.
+    #[cfg(feature = "exec")]
+    pub async fn all<'c, C>(self, connection: &'c mut C) -> Result<Vec<P>>
+    where
+        P: ParseFromRow<T::Database>,
+        C: Connection<Database = T::Database>,
+        &'c mut C: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        self.select.all_as::<C, P>(connection).await
+    }
+}
+
+/// A [`Select`] of only the columns `C`, returning their values as `V`, see [`Select::pluck`] and
+/// [`Select::pluck2`].
+pub struct Plucked<'a, T, C, V>
+where
+    T: Entity,
+    C: ColumnTuple,
+{
+    select: Select<'a, T>,
+    #[cfg(feature = "exec")]
+    convert: fn(C::Type) -> V,
+    marker: PhantomData<fn() -> (C, V)>,
+}
+
+impl<T, C, V> Plucked<'_, T, C, V>
+where
+    T: Entity,
+    C: ColumnTuple<Entity = T>,
+{
+    /// Return the raw SQL query of this statement. See [`Select::query`].
+    #[must_use]
+    pub fn query(&self) -> String {
+        self.select.query()
+    }
+
+    /// Execute the query, returning the values of a single result. See [`Select::one`].
+    ///
+    /// # Errors
+    ///
+    /// If no entry could be found ([`Error::NotFound`]), or if there's been a problem communicating
+    /// with the database. See [`Error`] for more information.
+    #[cfg(feature = "exec")]
+    pub async fn one<'c, DC>(self, connection: &'c mut DC) -> Result<V>
+    where
+        usize: sqlx::ColumnIndex<<T::Database as Database>::Row>,
+        DC: Connection<Database = T::Database>,
+        &'c mut DC: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        let convert = self.convert;
+
+        self.select
+            .one_as::<DC, PluckedRow<C>>(connection)
+            .await
+            .map(|e| convert(e.0))
+    }
+
+    /// Execute the query, returning the values of all results. See [`Select::all`].
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`] for more
+    /// information.
+    #[cfg(feature = "exec")]
+    pub async fn all<'c, DC>(self, connection: &'c mut DC) -> Result<Vec<V>>
+    where
+        usize: sqlx::ColumnIndex<<T::Database as Database>::Row>,
+        DC: Connection<Database = T::Database>,
+        &'c mut DC: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        let convert = self.convert;
+
+        Ok(self
+            .select
+            .all_as::<DC, PluckedRow<C>>(connection)
+            .await?
+            .into_iter()
+            .map(|e| convert(e.0))
+            .collect())
+    }
+}
+
+/// The values of the columns `C`, parsed from a row by position.
+#[cfg(feature = "exec")]
+struct PluckedRow<C>(C::Type)
+where
+    C: ColumnTuple;
+
+#[cfg(feature = "exec")]
+impl<C> ParseFromRow<<C::Entity as Entity>::Database> for PluckedRow<C>
+where
+    C: ColumnTuple,
+    usize: sqlx::ColumnIndex<<<C::Entity as Entity>::Database as Database>::Row>,
+{
+    fn parse_from_row(
+        row: &<<C::Entity as Entity>::Database as Database>::Row,
+    ) -> Result<Self, sqlx::Error> {
+        C::values_from_row(row).map(Self)
+    }
+}
+
+/// A [`Select`] run with SQL from a [`QueryCache`], see [`Select::cached`].
+#[cfg(feature = "exec")]
+pub struct CachedSelect<'c, 'a, T, K>
+where
+    T: Entity,
+{
+    select: Select<'a, T>,
+    cache: &'c QueryCache<K>,
+    key: K,
+}
+
+#[cfg(feature = "exec")]
+impl<T, K> CachedSelect<'_, '_, T, K>
+where
+    T: Entity,
+    K: Hash + Eq + Send + Sync,
+{
+    /// Execute the query, returning a single result. See [`Select::one`].
+    ///
+    /// # Errors
+    ///
+    /// If no entry could be found ([`Error::NotFound`]), or if there's been a problem communicating
+    /// with the database. See [`Error`] for more information.
+    pub async fn one<'c, C>(self, connection: &'c mut C) -> Result<T::Model>
+    where
+        C: Connection<Database = T::Database>,
+        &'c mut C: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        let mut binds = QueryBuilder::new("");
+        self.select.push_binds(&mut binds);
+
+        let arguments = binds
+            .build()
+            .take_arguments()
+            .map_err(sqlx::Error::Encode)?
+            .unwrap_or_default();
+
+        let sql = self
+            .cache
+            .get_or_render(self.key, &self.select, arguments.len())?;
+
+        let result = match with_timeout(
+            T::TABLE_NAME,
+            self.select.timeout,
+            connection.fetch_one(sqlx::query_with(&sql, arguments)),
+        )
+        .await?
+        {
+            Err(sqlx::Error::RowNotFound) => {
+                return Err(Error::NotFound {
+                    entity: T::TABLE_NAME,
+                    filter: sql.to_string(),
                 });
-                cond.push_to(builder);
-            };
+            }
+            result => result?,
+        };
+
+        Ok(<T::Model as ParseFromRow<T::Database>>::parse_from_row(
+            &result,
+        )?)
+    }
+
+    /// Execute the query, returning all results. See [`Select::all`].
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`] for more
+    /// information.
+    pub async fn all<'c, C>(self, connection: &'c mut C) -> Result<Vec<T::Model>>
+    where
+        C: Connection<Database = T::Database>,
+        &'c mut C: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        let mut binds = QueryBuilder::new("");
+        self.select.push_binds(&mut binds);
+
+        let arguments = binds
+            .build()
+            .take_arguments()
+            .map_err(sqlx::Error::Encode)?
+            .unwrap_or_default();
+
+        let sql = self
+            .cache
+            .get_or_render(self.key, &self.select, arguments.len())?;
+
+        let result = with_timeout(
+            T::TABLE_NAME,
+            self.select.timeout,
+            connection
+                .fetch(sqlx::query_with(&sql, arguments))
+                .collect::<Vec<_>>(),
+        )
+        .await?
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(result
+            .iter()
+            .map(<T::Model as ParseFromRow<T::Database>>::parse_from_row)
+            .collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+/// A [`Select`] with planner hints, see [`Select::hint`].
+pub struct Hinted<'a, T>
+where
+    T: Entity,
+{
+    select: Select<'a, T>,
+}
+
+impl<'a, T> Hinted<'a, T>
+where
+    T: Entity + 'a,
+{
+    /// Give the planner another hint. See [`Select::hint`].
+    #[must_use]
+    pub fn hint(mut self, hint: QueryHint) -> Self {
+        self.select.hints.push(hint);
+        self
+    }
+
+    /// Return the raw SQL query of this statement, with the hints rendered for the backend of `T`.
+    /// Hints applied by setting up the transaction of the query, e.g. [`QueryHint::Disable`], are
+    /// not part of it. See [`Select::query`].
+    #[must_use]
+    pub fn query(&self) -> String {
+        self.select.query()
+    }
+
+    /// The statements setting up the transaction of the query for its hints, e.g.
+    /// `SET LOCAL enable_seqscan = off`, and the ones restoring the previous settings afterwards,
+    /// or [`None`] if the query runs on its own.
+    ///
+    /// Releasing a savepoint keeps the settings of `SET LOCAL` in effect for the rest of the outer
+    /// transaction, which is why the previous values are kept in variables of their own, e.g.
+    /// `sky_orm.previous_enable_seqscan`, to be restored. Rolling back reverts them anyway.
+    #[cfg(feature = "exec")]
+    fn setup(&self) -> Option<(String, String)> {
+        let settings = self
+            .select
+            .hints
+            .iter()
+            .filter(|e| e.applies_to(<T::Database as Database>::NAME))
+            .filter_map(|e| match e {
+                QueryHint::Disable(method) => Some(method.setting()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if settings.is_empty() {
+            return None;
         }
+
+        let setup = settings
+            .iter()
+            .map(|e| {
+                format!(
+                    "SELECT set_config('sky_orm.previous_{e}', current_setting('{e}'), true); \
+                     SET LOCAL {e} = off"
+                )
+            })
+            .join("; ");
+        let teardown = settings
+            .iter()
+            .map(|e| {
+                format!("SELECT set_config('{e}', current_setting('sky_orm.previous_{e}'), true)")
+            })
+            .join("; ");
+
+        Some((setup, teardown))
+    }
+
+    /// Execute the query, returning a single result. If one of the hints has to be applied by
+    /// setting up the transaction of the query, it runs inside a transaction, or a savepoint if
+    /// `connection` is already inside one. See [`Select::one`].
+    ///
+    /// # Errors
+    ///
+    /// If no entry could be found ([`Error::NotFound`]), or if there's been a problem communicating
+    /// with the database. See [`Error`] for more information.
+    #[cfg(feature = "exec")]
+    pub async fn one<C>(self, connection: &mut C) -> Result<T::Model>
+    where
+        C: Connection<Database = T::Database>,
+        for<'e> &'e mut C: Executor<'e, Database = T::Database>,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let Some((setup, teardown)) = self.setup() else {
+            return self.select.one(connection).await;
+        };
+
+        let mut transaction = connection.begin().await?;
+        transaction.execute(setup.as_str()).await?;
+
+        let model = self.select.one(&mut *transaction).await?;
+
+        transaction.execute(teardown.as_str()).await?;
+        transaction.commit().await?;
+
+        Ok(model)
+    }
+
+    /// Execute the query, returning all results. See [`one`](Self::one) and [`Select::all`].
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`] for more
+    /// information.
+    #[cfg(feature = "exec")]
+    pub async fn all<C>(self, connection: &mut C) -> Result<Vec<T::Model>>
+    where
+        C: Connection<Database = T::Database>,
+        for<'e> &'e mut C: Executor<'e, Database = T::Database>,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let Some((setup, teardown)) = self.setup() else {
+            return self.select.all(connection).await;
+        };
+
+        let mut transaction = connection.begin().await?;
+        transaction.execute(setup.as_str()).await?;
+
+        let models = self.select.all(&mut *transaction).await?;
+
+        transaction.execute(teardown.as_str()).await?;
+        transaction.commit().await?;
+
+        Ok(models)
     }
 }
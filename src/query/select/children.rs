@@ -0,0 +1,202 @@
+//! Fetching entries along with their children in a single query, by aggregating the children into
+//! a json column.
+//!
+//! This trades the second query of [`LoadInverse`](crate::entity::relation::LoadInverse) for
+//! a correlated subquery per parent, which is worth it when the round trip to the database costs
+//! more than the aggregation:
+//!
+//! ```ignore
+//! #[derive(Deserialize)]
+//! struct Post {
+//!     id: i64,
+//!     title: String,
+//! }
+//!
+//! let users: Vec<(user::Model, Vec<Post>)> = user::Entity::find()
+//!     .with_children::<post::columns::UserId, Post>()
+//!     .all(&mut conn)
+//!     .await?;
+//! ```
+//!
+//! The children are decoded with serde instead of sqlx, from objects with their column names as
+//! keys, so values are in their json representation, e.g. timestamps as strings.
+
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use sqlx::{
+    Connection, Database, Executor, IntoArguments, Postgres, QueryBuilder, Row, types::Json,
+};
+
+use super::{Select, SelectedExpr};
+use crate::{
+    Result,
+    entity::{
+        Entity, HasPrimaryKey,
+        column::{ColumnName, ComparableColumn},
+        relation::{ForeignKey, Forward, Joinable, PrimaryKeyType, Related},
+    },
+    query::{PushToQuery, parse::ParseFromRow},
+};
+
+/// The name the aggregated children are selected as.
+const CHILDREN_COLUMN: &str = "sky_orm_children";
+
+/// The children of `R` referring to the current row of the outer query, as a json array of objects.
+///
+/// SQL: `COALESCE((SELECT json_agg(jsonb_build_object('id', "child"."id", ..)) FROM child WHERE "child"."parent_id" = "parent"."id"), '[]')`
+struct JsonChildren<R>
+where
+    R: Entity<Database = Postgres>,
+{
+    foreign_key: ColumnName,
+    primary_key: ColumnName,
+    scope: Option<Box<dyn PushToQuery<Postgres>>>,
+    marker: PhantomData<R>,
+}
+
+impl<R> PushToQuery<Postgres> for JsonChildren<R>
+where
+    R: Entity<Database = Postgres>,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        builder.push("COALESCE((SELECT json_agg(jsonb_build_object(");
+        R::COLUMN_NAMES.iter().enumerate().for_each(|(i, e)| {
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder.push(format_args!("'{e}', \"{}\".\"{e}\"", R::TABLE_NAME));
+        });
+        builder.push(")) FROM ");
+        builder.push(R::QUALIFIED_TABLE_NAME);
+        builder.push(" WHERE ");
+        self.foreign_key.push_to(builder);
+        builder.push(" = ");
+        self.primary_key.push_to(builder);
+        if let Some(scope) = &self.scope {
+            builder.push(" AND (");
+            scope.push_to(builder);
+            builder.push(")");
+        }
+        builder.push("), '[]')");
+    }
+
+    fn push_binds(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        if let Some(scope) = &self.scope {
+            scope.push_binds(builder);
+        }
+    }
+}
+
+impl<'a, T> Select<'a, T>
+where
+    T: HasPrimaryKey<Database = Postgres> + 'a,
+{
+    /// Also select the children of each entry, i.e. the entries of the entity owning foreign key
+    /// column `C`, decoded as `D`. The children are filtered by their entity's
+    /// [implicit filter](Entity::implicit_filter), and are in no particular order.
+    #[must_use]
+    pub fn with_children<C, D>(mut self) -> WithChildren<'a, T, D>
+    where
+        C: ComparableColumn<Type: ForeignKey<PrimaryKeyType<T>>> + 'static,
+        C::Entity: Related<T, C> + Entity<Database = Postgres>,
+        D: DeserializeOwned,
+    {
+        let (foreign_key, primary_key) = <C::Entity as Joinable<T, Forward<C>>>::join_columns();
+
+        self.expressions.push(SelectedExpr {
+            expr: Box::new(JsonChildren::<C::Entity> {
+                foreign_key,
+                primary_key,
+                scope: <C::Entity as Entity>::implicit_filter(),
+                marker: PhantomData,
+            }),
+            name: CHILDREN_COLUMN,
+        });
+
+        WithChildren {
+            select: self,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// A [`Select`] of entries along with their children, see [`Select::with_children`].
+pub struct WithChildren<'a, T, D>
+where
+    T: Entity,
+{
+    select: Select<'a, T>,
+    marker: PhantomData<D>,
+}
+
+impl<T, D> WithChildren<'_, T, D>
+where
+    T: Entity<Database = Postgres>,
+    D: DeserializeOwned,
+{
+    /// Return the raw SQL query of this statement. See [`Select::query`].
+    #[must_use]
+    pub fn query(&self) -> String {
+        self.select.query()
+    }
+
+    /// Execute the query, returning a single result along with its children. See [`Select::one`].
+    ///
+    /// # Errors
+    ///
+    /// If no entry could be found ([`Error::NotFound`](crate::Error::NotFound)), or if there's
+    /// been a problem communicating with the database. See [`Error`](crate::Error) for more
+    /// information.
+    pub async fn one<'c, C>(self, connection: &'c mut C) -> Result<(T::Model, Vec<D>)>
+    where
+        C: Connection<Database = Postgres>,
+        &'c mut C: Executor<'c, Database = Postgres>,
+        for<'q> <Postgres as Database>::Arguments<'q>: IntoArguments<'q, Postgres> + 'c,
+    {
+        self.select
+            .one_as::<C, WithChildrenRow<T::Model, D>>(connection)
+            .await
+            .map(|e| (e.model, e.children))
+    }
+
+    /// Execute the query, returning all results along with their children. See [`Select::all`].
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`](crate::Error) for
+    /// more information.
+    pub async fn all<'c, C>(self, connection: &'c mut C) -> Result<Vec<(T::Model, Vec<D>)>>
+    where
+        C: Connection<Database = Postgres>,
+        &'c mut C: Executor<'c, Database = Postgres>,
+        for<'q> <Postgres as Database>::Arguments<'q>: IntoArguments<'q, Postgres> + 'c,
+    {
+        Ok(self
+            .select
+            .all_as::<C, WithChildrenRow<T::Model, D>>(connection)
+            .await?
+            .into_iter()
+            .map(|e| (e.model, e.children))
+            .collect())
+    }
+}
+
+/// A model parsed from a row, along with the children aggregated into it.
+struct WithChildrenRow<M, D> {
+    model: M,
+    children: Vec<D>,
+}
+
+impl<M, D> ParseFromRow<Postgres> for WithChildrenRow<M, D>
+where
+    M: ParseFromRow<Postgres>,
+    D: DeserializeOwned,
+{
+    fn parse_from_row(row: &<Postgres as Database>::Row) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            model: M::parse_from_row(row)?,
+            children: row.try_get::<Json<Vec<D>>, _>(CHILDREN_COLUMN)?.0,
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use serde::Serialize;
+use sqlx::{Any, Execute, QueryBuilder, any::AnyArguments};
+use sqlx_core::any::AnyValueKind;
+
+use super::PushToQuery;
+
+/// A bind parameter of a [`Statement`], in a backend-agnostic representation.
+///
+/// Serializes into the plain JSON value it holds, so a list of binds can be passed as-is to HTTP
+/// based drivers like Cloudflare D1.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BindValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl TryFrom<AnyValueKind<'_>> for BindValue {
+    type Error = sqlx::Error;
+
+    fn try_from(value: AnyValueKind<'_>) -> Result<Self, Self::Error> {
+        Ok(match value {
+            AnyValueKind::Null(_) => Self::Null,
+            AnyValueKind::Bool(e) => Self::Bool(e),
+            AnyValueKind::SmallInt(e) => Self::Integer(e.into()),
+            AnyValueKind::Integer(e) => Self::Integer(e.into()),
+            AnyValueKind::BigInt(e) => Self::Integer(e),
+            AnyValueKind::Real(e) => Self::Real(e.into()),
+            AnyValueKind::Double(e) => Self::Real(e),
+            AnyValueKind::Text(e) => Self::Text(e.into_owned()),
+            AnyValueKind::Blob(e) => Self::Blob(e.into_owned()),
+            e => {
+                return Err(sqlx::Error::Encode(
+                    format!("unsupported bind value {e:?}").into(),
+                ));
+            }
+        })
+    }
+}
+
+/// A rendered SQL statement along with its bind parameters, for execution by drivers outside of
+/// sqlx, e.g. on wasm32 targets.
+///
+/// Statements are rendered for entities using [`sqlx::Any`] as their database, with bind
+/// parameters substituted by `?`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Statement {
+    pub sql: String,
+    pub binds: Vec<BindValue>,
+}
+
+impl Statement {
+    /// Render a query into a statement.
+    ///
+    /// # Errors
+    ///
+    /// If one of the query's parameters failed to encode.
+    pub fn build<Q>(query: &Q) -> Result<Self, sqlx::Error>
+    where
+        Q: PushToQuery<Any>,
+    {
+        let mut builder = QueryBuilder::new("");
+        query.push_to(&mut builder);
+
+        let sql = builder.sql().to_string();
+        let binds = builder
+            .build()
+            .take_arguments()
+            .map_err(sqlx::Error::Encode)?
+            .map(|e: AnyArguments<'_>| {
+                e.values
+                    .0
+                    .into_iter()
+                    .map(BindValue::try_from)
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(Self { sql, binds })
+    }
+}
@@ -0,0 +1,539 @@
+use std::{marker::PhantomData, sync::Arc};
+
+#[cfg(feature = "exec")]
+use sqlx::{Connection, Executor, IntoArguments};
+use sqlx::{Database, QueryBuilder};
+
+#[cfg(feature = "exec")]
+use std::time::Duration;
+
+#[cfg(feature = "exec")]
+use super::{
+    exec_result::{ExecResult, IntoExecResult},
+    with_timeout,
+};
+#[cfg(feature = "exec")]
+use crate::router::DatabaseRouter;
+
+use crate::entity::{
+    Entity, HasPrimaryKey,
+    column::{Column, ColumnAssignment, ColumnName, EntityConditionExpr},
+    model::{ActiveModel, ColumnValue},
+};
+
+#[cfg(feature = "statement")]
+use super::statement::Statement;
+use super::{BinaryExpr, BinaryExprOperand, BracketsExpr, PushToQuery, max_bind_parameters};
+
+/// An `UPDATE` statement, changing columns of all rows of entity `T` that match its conditions.
+///
+/// Without any condition, every row of the table is updated.
+pub struct Update<T>
+where
+    T: Entity,
+{
+    marker: PhantomData<T>,
+    values: Vec<ColumnValue<T::Database>>,
+    scope: Option<Arc<dyn PushToQuery<T::Database>>>,
+    conditions: Vec<Arc<dyn PushToQuery<T::Database>>>,
+    #[cfg(feature = "exec")]
+    timeout: Option<Duration>,
+}
+
+impl<T> Update<T>
+where
+    T: Entity,
+{
+    pub(crate) fn new() -> Self {
+        Self {
+            marker: PhantomData,
+            values: vec![],
+            scope: T::implicit_filter().map(Arc::from),
+            conditions: vec![],
+            #[cfg(feature = "exec")]
+            timeout: None,
+        }
+    }
+
+    /// Set a column to a new value, e.g. `set(columns::Status::value("archived"))`. Setting the
+    /// same column twice replaces the previous value.
+    #[must_use]
+    pub fn set(self, assignment: ColumnAssignment<T>) -> Self {
+        self.set_column_value(assignment.into_column_value())
+    }
+
+    /// Set a column to a value taken from the values of an active model.
+    pub(crate) fn set_column_value(mut self, (name, value): ColumnValue<T::Database>) -> Self {
+        self.values.retain(|(e, _)| *e != name);
+        self.values.push((name, value));
+        self
+    }
+
+    /// Skip the [implicit filter](Entity::implicit_filter) of `T`, e.g. to update the rows of all
+    /// tenants.
+    #[must_use]
+    pub fn unscoped(mut self) -> Self {
+        self.scope = None;
+        self
+    }
+
+    /// Only update rows whose column equals the given value, e.g. a primary key taken from the
+    /// values of an active model.
+    #[cfg(feature = "exec")]
+    pub(crate) fn filter_column_value<V>(mut self, (name, value): (&'static str, V)) -> Self
+    where
+        V: PushToQuery<T::Database> + 'static,
+    {
+        self.conditions.push(Arc::new(BinaryExpr::new(
+            ColumnName::new_with_table_or_alias(T::TABLE_NAME.to_string(), name.to_string()),
+            value,
+            BinaryExprOperand::Equals,
+        )));
+        self
+    }
+
+    /// Append a new `WHERE` condition using an `AND` statement as glue. The passed condition is
+    /// wrapped in `()` brackets.
+    #[must_use]
+    pub fn filter<Q>(mut self, condition: EntityConditionExpr<Q, T>) -> Self
+    where
+        Q: PushToQuery<T::Database> + 'static,
+        T: 'static,
+    {
+        self.conditions.push(Arc::new(condition));
+        self
+    }
+
+    /// Whether no column is set, in which case the statement is not valid SQL. Such statements are
+    /// skipped by [`exec`](Self::exec).
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Return the raw SQL query of this statement. Note that the returned query is
+    /// backend-agnostic, e.g. query parameters will be substituted with `?` instead of `$1` (in
+    /// the case of postgres).
+    ///
+    /// This is mainly useful for debugging purposes, and not intended to produce queries to be run
+    /// on an actual database.
+    #[must_use]
+    pub fn query(&self) -> String {
+        let mut builder = QueryBuilder::new("");
+        self.push_to(&mut builder);
+        builder.into_sql()
+    }
+
+    /// Render the statement into a [`Statement`], to be run by a driver outside of sqlx.
+    ///
+    /// # Errors
+    ///
+    /// If one of the statement's parameters failed to encode.
+    #[cfg(feature = "statement")]
+    pub fn statement(&self) -> Result<Statement, sqlx::Error>
+    where
+        T: Entity<Database = sqlx::Any>,
+    {
+        Statement::build(self)
+    }
+
+    /// Fail with [`Error::Timeout`](crate::Error::Timeout) if executing the statement takes longer
+    /// than `timeout`. See [`Select::timeout`](super::select::Select::timeout).
+    #[cfg(feature = "exec")]
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Execute the statement, returning the number of affected rows and, on some databases, the ID
+    /// of the last inserted row. Statements without any column to set are not sent to the
+    /// database, and affect no rows.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or if the statement violates a
+    /// constraint. See [`Error`](crate::Error) for more information.
+    #[cfg(feature = "exec")]
+    pub async fn exec<'c, C>(self, connection: &'c mut C) -> crate::Result<ExecResult>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        C: Connection<Database = T::Database>,
+        &'c mut C: Executor<'c, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'c,
+    {
+        if self.is_empty() {
+            return Ok(ExecResult::default());
+        }
+
+        let mut builder = QueryBuilder::new("");
+        self.push_to(&mut builder);
+
+        let timeout = self.timeout;
+        drop(self);
+
+        let result =
+            with_timeout(T::TABLE_NAME, timeout, connection.execute(builder.build())).await??;
+
+        Ok(result.into_exec_result())
+    }
+
+    /// Execute the statement on the primary database of `router`. See [`exec`](Self::exec).
+    ///
+    /// # Errors
+    ///
+    /// If no connection could be acquired, or if the statement failed. See
+    /// [`Error`](crate::Error) for more information.
+    #[cfg(feature = "exec")]
+    pub async fn exec_routed(
+        self,
+        router: &DatabaseRouter<T::Database>,
+    ) -> crate::Result<ExecResult>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let mut connection = router.writer().await?;
+
+        self.exec(&mut *connection).await
+    }
+}
+
+impl<T> PushToQuery<T::Database> for Update<T>
+where
+    T: Entity,
+{
+    fn push_to(&self, builder: &mut QueryBuilder<'_, T::Database>) {
+        builder.push("UPDATE ");
+        builder.push(T::QUALIFIED_TABLE_NAME);
+        builder.push(" SET ");
+
+        self.values
+            .iter()
+            .enumerate()
+            .for_each(|(i, (name, value))| {
+                if i > 0 {
+                    builder.push(", ");
+                }
+                ColumnName::new(name.to_string()).push_to(builder);
+                builder.push(" = ");
+                value.push_to(builder);
+            });
+
+        self.scope
+            .iter()
+            .chain(&self.conditions)
+            .enumerate()
+            .for_each(|(i, e)| {
+                builder.push(if i == 0 { " WHERE " } else { " AND " });
+                BracketsExpr::new(e.clone()).push_to(builder);
+            });
+    }
+}
+
+/// The name the list of values is joined as by [`UpdateFrom`].
+const VALUES_ALIAS: &str = "__values";
+
+/// The primary key and the changed values of a model updated by [`UpdateFrom`].
+struct ChangedRow<DB>
+where
+    DB: Database + Sync,
+{
+    primary_key: ColumnValue<DB>,
+    values: Vec<ColumnValue<DB>>,
+}
+
+/// An `UPDATE` statement writing the changed columns of many active models of entity `T`, each to
+/// its own row, see [`HasPrimaryKey::update_many_from`].
+///
+/// On postgres, models changing the same columns are updated with a single statement, joining the
+/// table with a list of their values:
+///
+/// `UPDATE "user" SET "name" = "__values"."name" FROM (VALUES ($1, $2), ($3, $4)) AS "__values" ("id", "name") WHERE "user"."id" = "__values"."id"`
+///
+/// Models beyond the bind parameter limit of postgres are split off into further statements. Other
+/// databases update every model with its own statement. Either way, all models are updated inside
+/// a single transaction.
+pub struct UpdateFrom<T>
+where
+    T: HasPrimaryKey,
+{
+    /// Every model that has changes.
+    rows: Vec<ChangedRow<T::Database>>,
+    scope: Option<Arc<dyn PushToQuery<T::Database>>>,
+    #[cfg(feature = "exec")]
+    timeout: Option<Duration>,
+}
+
+impl<T> UpdateFrom<T>
+where
+    T: HasPrimaryKey,
+{
+    /// Models without changes, or without a value for their primary key, are skipped. A primary
+    /// key that is set identifies the row to update (e.g. `ActiveModel::default()` followed by
+    /// `set_id`), so it is not counted as a change.
+    pub(crate) fn new<'a, A, I>(active_models: I) -> Self
+    where
+        A: ActiveModel<Model = T::Model> + 'a,
+        I: IntoIterator<Item = &'a A>,
+    {
+        Self {
+            rows: active_models
+                .into_iter()
+                .filter_map(|e| {
+                    let changed = e
+                        .changed_values()
+                        .into_iter()
+                        .filter(|(name, _)| *name != T::PrimaryKeyColumn::NAME)
+                        .collect::<Vec<_>>();
+
+                    if changed.is_empty() {
+                        return None;
+                    }
+
+                    let primary_key = e
+                        .values()
+                        .into_iter()
+                        .find(|(name, _)| *name == T::PrimaryKeyColumn::NAME)?;
+
+                    Some(ChangedRow {
+                        primary_key,
+                        values: changed,
+                    })
+                })
+                .collect(),
+            scope: T::implicit_filter().map(Arc::from),
+            #[cfg(feature = "exec")]
+            timeout: None,
+        }
+    }
+
+    /// Skip the [implicit filter](Entity::implicit_filter) of `T`, e.g. to update the rows of all
+    /// tenants.
+    #[must_use]
+    pub fn unscoped(mut self) -> Self {
+        self.scope = None;
+        self
+    }
+
+    /// The number of models to be updated, i.e. those with changes.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Whether there are no models to be updated.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The indices of the rows, grouped by the columns they change, in order of first occurrence.
+    /// Groups beyond the [bind parameter limit](max_bind_parameters) of the database are split into
+    /// several, each updated by a statement of its own. A tenth of the limit is left to the
+    /// parameters of the implicit filter.
+    fn groups(&self) -> Vec<(Vec<&'static str>, Vec<usize>)> {
+        let mut groups: Vec<(Vec<_>, Vec<_>)> = vec![];
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let columns = row.values.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+
+            match groups.iter_mut().find(|(e, _)| *e == columns) {
+                Some((_, rows)) => rows.push(i),
+                None => groups.push((columns, vec![i])),
+            }
+        }
+
+        let max_parameters = max_bind_parameters::<T::Database>() / 10 * 9;
+
+        groups
+            .into_iter()
+            .flat_map(|(columns, rows)| {
+                // Every row binds its primary key and the values of `columns`.
+                let chunk_size = (max_parameters / (columns.len() + 1)).max(1);
+
+                rows.chunks(chunk_size)
+                    .map(|e| (columns.clone(), e.to_vec()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Push the statement updating `rows`, which all change `columns`, joined with a list of their
+    /// values.
+    fn push_values_update(
+        &self,
+        builder: &mut QueryBuilder<'_, T::Database>,
+        columns: &[&'static str],
+        rows: &[usize],
+    ) {
+        let primary_key = T::PrimaryKeyColumn::NAME;
+
+        builder.push("UPDATE ");
+        builder.push(T::QUALIFIED_TABLE_NAME);
+        builder.push(" SET ");
+
+        columns.iter().enumerate().for_each(|(i, e)| {
+            if i > 0 {
+                builder.push(", ");
+            }
+            ColumnName::new(e.to_string()).push_to(builder);
+            builder.push(" = ");
+            ColumnName::new_with_table_or_alias(VALUES_ALIAS.to_string(), e.to_string())
+                .push_to(builder);
+        });
+
+        builder.push(" FROM (VALUES ");
+
+        rows.iter().enumerate().for_each(|(i, row)| {
+            let row = &self.rows[*row];
+
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder.push("(");
+            row.primary_key.1.push_to(builder);
+            for (_, value) in &row.values {
+                builder.push(", ");
+                value.push_to(builder);
+            }
+            builder.push(")");
+        });
+
+        builder.push(format_args!(") AS \"{VALUES_ALIAS}\" ("));
+        T::PrimaryKeyColumn::column_name().push_to(builder);
+        for e in columns {
+            builder.push(", ");
+            ColumnName::new(e.to_string()).push_to(builder);
+        }
+        builder.push(") WHERE ");
+        BinaryExpr::new(
+            T::PrimaryKeyColumn::full_column_name(),
+            ColumnName::new_with_table_or_alias(VALUES_ALIAS.to_string(), primary_key.to_string()),
+            BinaryExprOperand::Equals,
+        )
+        .push_to(builder);
+
+        if let Some(scope) = &self.scope {
+            builder.push(" AND ");
+            BracketsExpr::new(scope.clone()).push_to(builder);
+        }
+    }
+
+    /// Return the raw SQL of the statements run on postgres, separated by `;`. Note that the
+    /// returned query is backend-agnostic, see [`Update::query`].
+    ///
+    /// This is mainly useful for debugging purposes, and not intended to produce queries to be run
+    /// on an actual database.
+    #[must_use]
+    pub fn query(&self) -> String {
+        let mut builder = QueryBuilder::new("");
+
+        self.groups()
+            .iter()
+            .enumerate()
+            .for_each(|(i, (columns, rows))| {
+                if i > 0 {
+                    builder.push("; ");
+                }
+                self.push_values_update(&mut builder, columns, rows);
+            });
+
+        builder.into_sql()
+    }
+
+    /// Fail with [`Error::Timeout`](crate::Error::Timeout) if executing any of the statements takes
+    /// longer than `timeout`. See [`Select::timeout`](super::select::Select::timeout).
+    #[cfg(feature = "exec")]
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Execute the statements inside a transaction, returning the total number of affected rows.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or if a statement violates a
+    /// constraint, in which case none of the models are updated. See [`Error`](crate::Error) for
+    /// more information.
+    #[cfg(feature = "exec")]
+    pub async fn exec<C>(self, connection: &mut C) -> crate::Result<ExecResult>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        C: Connection<Database = T::Database>,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        if self.rows.is_empty() {
+            return Ok(ExecResult::default());
+        }
+
+        let mut transaction = connection.begin().await?;
+        let mut rows_affected = 0;
+
+        if <T::Database as Database>::NAME == "PostgreSQL" {
+            for (columns, rows) in self.groups() {
+                let mut builder = QueryBuilder::new("");
+                self.push_values_update(&mut builder, &columns, &rows);
+
+                let result = with_timeout(
+                    T::TABLE_NAME,
+                    self.timeout,
+                    (&mut *transaction).execute(builder.build()),
+                )
+                .await??;
+
+                rows_affected += result.into_exec_result().rows_affected;
+            }
+        } else {
+            let Self {
+                rows,
+                scope,
+                timeout,
+            } = self;
+
+            for row in rows {
+                let mut update = row
+                    .values
+                    .into_iter()
+                    .fold(Update::<T>::new(), Update::set_column_value)
+                    .filter_column_value(row.primary_key);
+                update.scope.clone_from(&scope);
+                update.timeout = timeout;
+
+                rows_affected += update.exec(&mut *transaction).await?.rows_affected;
+            }
+        }
+
+        transaction.commit().await?;
+
+        Ok(ExecResult {
+            rows_affected,
+            last_insert_id: None,
+        })
+    }
+
+    /// Execute the statements on the primary database of `router`. See [`exec`](Self::exec).
+    ///
+    /// # Errors
+    ///
+    /// If no connection could be acquired, or if a statement failed. See [`Error`](crate::Error)
+    /// for more information.
+    #[cfg(feature = "exec")]
+    pub async fn exec_routed(
+        self,
+        router: &DatabaseRouter<T::Database>,
+    ) -> crate::Result<ExecResult>
+    where
+        <T::Database as Database>::QueryResult: IntoExecResult,
+        for<'e> &'e mut <T::Database as Database>::Connection: Executor<'e, Database = T::Database>,
+        for<'q> <T::Database as Database>::Arguments<'q>: IntoArguments<'q, T::Database> + 'static,
+    {
+        let mut connection = router.writer().await?;
+
+        self.exec(&mut *connection).await
+    }
+}
@@ -0,0 +1,108 @@
+//! Feeding Postgres' row-level security policies from the application, by running queries inside a
+//! transaction with session variables set for it:
+//!
+//! ```ignore
+//! let context = RlsContext::new()
+//!     .set("app.current_user_id", user_id)
+//!     .set("app.current_role", "editor");
+//!
+//! let posts = context
+//!     .run(&mut *conn, async |conn| post::Entity::find().all(conn).await)
+//!     .await?;
+//! ```
+//!
+//! The variables are read by the policies with `current_setting`, e.g.
+//! `USING (author_id = current_setting('app.current_user_id')::bigint)`. They are set with
+//! `set_config(.., true)`, which is the same as `SET LOCAL` but takes its values as parameters, so
+//! they are reset once the transaction ends and never leak to the next user of a pooled
+//! connection.
+//!
+//! Transactions begun by the execution APIs inside [`run`](RlsContext::run), e.g. by
+//! [`Session::flush`](crate::session::Session::flush), become savepoints of its transaction, so
+//! their statements see the variables as well.
+
+use std::fmt::Display;
+
+use sqlx::{Connection, PgConnection, Postgres, QueryBuilder, postgres::PgExecutor};
+
+use crate::Result;
+
+/// The session variables to set for the queries of a transaction. See the
+/// [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RlsContext {
+    settings: Vec<(String, String)>,
+}
+
+impl RlsContext {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { settings: vec![] }
+    }
+
+    /// Set the variable `name` to `value`. Custom variables have to be namespaced with a dot, e.g.
+    /// `app.current_user_id`.
+    #[must_use]
+    pub fn set(mut self, name: impl Into<String>, value: impl Display) -> Self {
+        self.settings.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// Set the variables for the rest of the transaction `connection` is inside of. Outside of a
+    /// transaction, they would only apply to this very statement, so use [`run`](Self::run)
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or if a name is not a valid
+    /// variable name.
+    pub async fn apply<'c, E>(&self, executor: E) -> Result<()>
+    where
+        E: PgExecutor<'c>,
+    {
+        if self.settings.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT ");
+        for (i, (name, value)) in self.settings.iter().enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder.push("set_config(");
+            builder.push_bind(name);
+            builder.push(", ");
+            builder.push_bind(value);
+            builder.push(", true)");
+        }
+
+        builder.build().execute(executor).await?;
+
+        Ok(())
+    }
+
+    /// Run `f` with a connection inside a transaction with the variables set, committing the
+    /// transaction if `f` succeeds, and rolling it back otherwise. If `connection` is inside a
+    /// transaction already, a savepoint is used, and the variables stay set until the outer
+    /// transaction ends.
+    ///
+    /// # Errors
+    ///
+    /// If the transaction could not be begun or committed, if the variables could not be set, or
+    /// if `f` failed. See [`Error`](crate::Error) for more information.
+    pub async fn run<C, F, R>(&self, connection: &mut C, f: F) -> Result<R>
+    where
+        C: Connection<Database = Postgres>,
+        F: AsyncFnOnce(&mut PgConnection) -> Result<R>,
+    {
+        let mut transaction = connection.begin().await?;
+
+        self.apply(&mut *transaction).await?;
+
+        let result = f(&mut transaction).await?;
+
+        transaction.commit().await?;
+
+        Ok(result)
+    }
+}
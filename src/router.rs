@@ -0,0 +1,79 @@
+//! Routing queries between a primary database and a read replica.
+//!
+//! With a [`DatabaseRouter`], reads (i.e. [`Select`](crate::query::select::Select)s) are sent to
+//! the replica, while writes always go to the primary:
+//!
+//! ```ignore
+//! let router = DatabaseRouter::new(primary_pool, replica_pool);
+//!
+//! user::Entity::insert_values([user::columns::Name::value(name)])
+//!     .exec_routed(&router)
+//!     .await?;
+//!
+//! // Replicas lag behind the primary, so reads that need to see the write above have to opt out.
+//! let users = user::Entity::find().on_primary().all_routed(&router).await?;
+//! ```
+
+use sqlx::{Database, Pool, pool::PoolConnection};
+
+use crate::Result;
+
+/// The connection pools of a primary database and its read replica.
+pub struct DatabaseRouter<DB>
+where
+    DB: Database,
+{
+    primary: Pool<DB>,
+    replica: Pool<DB>,
+}
+
+impl<DB> Clone for DatabaseRouter<DB>
+where
+    DB: Database,
+{
+    fn clone(&self) -> Self {
+        Self {
+            primary: self.primary.clone(),
+            replica: self.replica.clone(),
+        }
+    }
+}
+
+impl<DB> DatabaseRouter<DB>
+where
+    DB: Database,
+{
+    /// A router sending writes to `primary`, and reads to `replica`.
+    #[must_use]
+    pub const fn new(primary: Pool<DB>, replica: Pool<DB>) -> Self {
+        Self { primary, replica }
+    }
+
+    /// The pool of the primary database, e.g. to begin a transaction on it.
+    #[must_use]
+    pub const fn primary(&self) -> &Pool<DB> {
+        &self.primary
+    }
+
+    /// The pool of the read replica.
+    #[must_use]
+    pub const fn replica(&self) -> &Pool<DB> {
+        &self.replica
+    }
+
+    /// A connection to the replica, or to the primary if `on_primary` is set.
+    pub(crate) async fn reader(&self, on_primary: bool) -> Result<PoolConnection<DB>> {
+        let pool = if on_primary {
+            &self.primary
+        } else {
+            &self.replica
+        };
+
+        Ok(pool.acquire().await?)
+    }
+
+    /// A connection to the primary.
+    pub(crate) async fn writer(&self) -> Result<PoolConnection<DB>> {
+        Ok(self.primary.acquire().await?)
+    }
+}
@@ -0,0 +1,628 @@
+//! A unit of work, collecting the changes to several entities and writing all of them at once.
+
+use std::{
+    any::Any,
+    marker::PhantomData,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use serde_json::Value;
+use sqlx::{
+    ColumnIndex, Connection, Database, Decode, Executor, IntoArguments, QueryBuilder, Row, Type,
+};
+
+use crate::{
+    Result,
+    audit::{AuditColumn, AuditRecord, AuditSink, ChangeOperation},
+    entity::{
+        HasPrimaryKey,
+        column::Column,
+        model::{ActiveModel, AuditedModel, IntoActiveModel, Model},
+    },
+    query::{PushToQuery, exec_result::IntoExecResult, quote_ident, update::Update},
+};
+
+/// The id of the next session, to tell the handles of different sessions apart.
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// A handle to a model tracked by a [`Session`], to access its active model.
+pub struct Tracked<A> {
+    session: u64,
+    index: usize,
+    marker: PhantomData<A>,
+}
+
+impl<A> Clone for Tracked<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A> Copy for Tracked<A> {}
+
+/// What to write for a tracked model when the session is flushed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EntryState {
+    /// Insert the model as a new row.
+    New,
+    /// Update the columns that changed since the model was loaded.
+    Loaded,
+    /// Delete the model's row.
+    Deleted,
+    /// Write nothing, since the model was added and deleted again before being inserted.
+    Discarded,
+}
+
+/// An active model tracked by a session, with its type erased.
+trait TrackedModel<DB>: Send
+where
+    DB: Database + Sync,
+{
+    fn table_name(&self) -> &'static str;
+
+    fn referenced_tables(&self) -> &'static [&'static str];
+
+    fn primary_key_name(&self) -> &'static str;
+
+    /// Whether the writes of this model are recorded in the audit log.
+    fn audited(&self) -> bool;
+
+    fn audit_values(&self) -> Vec<(&'static str, Value)>;
+
+    /// The names of the columns that changed since the model was loaded.
+    fn changed_columns(&self) -> Vec<&'static str>;
+
+    /// Whether there is anything to write for this model.
+    fn has_changes(&self, state: EntryState) -> bool;
+
+    /// Run the hook before writing this model.
+    fn before_write(&mut self, state: EntryState) -> Result<(), sqlx::Error>;
+
+    /// Run the hook after writing this model.
+    fn after_write(&self, state: EntryState) -> Result<(), sqlx::Error>;
+
+    /// The value of the primary key, if it is set.
+    fn primary_key(&self) -> Option<Arc<dyn PushToQuery<DB>>>;
+
+    /// The statement writing this model, or [`None`] if nothing changed. Updates and deletes find
+    /// the model's row by `primary_key`, its value when the model was loaded.
+    fn statement(
+        &self,
+        state: EntryState,
+        primary_key: Option<&Arc<dyn PushToQuery<DB>>>,
+    ) -> Option<Box<dyn PushToQuery<DB>>>;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<A, E> TrackedModel<E::Database> for A
+where
+    A: ActiveModel + Send + 'static,
+    A::Model: Model<Entity = E>,
+    E: HasPrimaryKey<Model = A::Model> + 'static,
+{
+    fn table_name(&self) -> &'static str {
+        E::TABLE_NAME
+    }
+
+    fn referenced_tables(&self) -> &'static [&'static str] {
+        E::REFERENCED_TABLES
+    }
+
+    fn primary_key_name(&self) -> &'static str {
+        E::PrimaryKeyColumn::NAME
+    }
+
+    fn audited(&self) -> bool {
+        A::AUDITED
+    }
+
+    fn audit_values(&self) -> Vec<(&'static str, Value)> {
+        AuditedModel::audit_values(self)
+    }
+
+    fn changed_columns(&self) -> Vec<&'static str> {
+        self.changed_values()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    fn has_changes(&self, state: EntryState) -> bool {
+        match state {
+            EntryState::New | EntryState::Deleted => true,
+            EntryState::Loaded => !self.changed_values().is_empty(),
+            EntryState::Discarded => false,
+        }
+    }
+
+    fn before_write(&mut self, state: EntryState) -> Result<(), sqlx::Error> {
+        match state {
+            EntryState::New => self.before_insert(),
+            EntryState::Loaded => self.before_update(),
+            EntryState::Deleted => self.before_delete(),
+            EntryState::Discarded => Ok(()),
+        }
+    }
+
+    fn after_write(&self, state: EntryState) -> Result<(), sqlx::Error> {
+        match state {
+            EntryState::New => self.after_insert(),
+            EntryState::Loaded => self.after_update(),
+            EntryState::Deleted => self.after_delete(),
+            EntryState::Discarded => Ok(()),
+        }
+    }
+
+    fn primary_key(&self) -> Option<Arc<dyn PushToQuery<E::Database>>> {
+        self.values()
+            .into_iter()
+            .find_map(|(name, value)| (name == E::PrimaryKeyColumn::NAME).then(|| value.into()))
+    }
+
+    fn statement(
+        &self,
+        state: EntryState,
+        primary_key: Option<&Arc<dyn PushToQuery<E::Database>>>,
+    ) -> Option<Box<dyn PushToQuery<E::Database>>> {
+        let primary_key = || Some((E::PrimaryKeyColumn::NAME, Arc::clone(primary_key?)));
+
+        match state {
+            EntryState::New => Some(Box::new(E::insert().row(self))),
+            EntryState::Loaded => {
+                let changed = self.changed_values();
+
+                if changed.is_empty() {
+                    return None;
+                }
+
+                let update = changed
+                    .into_iter()
+                    .fold(E::update_many(), Update::set_column_value);
+
+                Some(Box::new(update.filter_column_value(primary_key()?)))
+            }
+            EntryState::Deleted => Some(Box::new(
+                E::delete_many().filter_column_value(primary_key()?),
+            )),
+            EntryState::Discarded => None,
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+struct Entry<DB>
+where
+    DB: Database + Sync,
+{
+    model: Box<dyn TrackedModel<DB>>,
+    state: EntryState,
+    /// The primary key of the model when it was added to the session, to find its row even if the
+    /// primary key is changed.
+    primary_key: Option<Arc<dyn PushToQuery<DB>>>,
+    /// The audit values of the model when it was added to the session.
+    original: Vec<(&'static str, Value)>,
+}
+
+impl<DB> Entry<DB>
+where
+    DB: Database + Sync,
+{
+    /// The audit record of writing this entry, or [`None`] if its model is not audited.
+    /// `generated_key` is the primary key generated by the database when inserting the model.
+    fn audit_record(&self, generated_key: Option<Value>) -> Option<AuditRecord> {
+        if !self.model.audited() {
+            return None;
+        }
+
+        let current = self.model.audit_values();
+        let value = |values: &[(&'static str, Value)], name| {
+            values
+                .iter()
+                .find(|(e, _)| *e == name)
+                .map(|(_, e)| e.clone())
+        };
+
+        let (operation, columns) = match self.state {
+            EntryState::New => (
+                ChangeOperation::Insert,
+                current
+                    .iter()
+                    .map(|(name, e)| AuditColumn {
+                        name,
+                        old: None,
+                        new: Some(e.clone()),
+                    })
+                    .collect(),
+            ),
+            EntryState::Loaded => (
+                ChangeOperation::Update,
+                self.model
+                    .changed_columns()
+                    .into_iter()
+                    .map(|name| AuditColumn {
+                        name,
+                        old: value(&self.original, name),
+                        new: value(&current, name),
+                    })
+                    .collect(),
+            ),
+            EntryState::Deleted => (
+                ChangeOperation::Delete,
+                self.original
+                    .iter()
+                    .map(|(name, e)| AuditColumn {
+                        name,
+                        old: Some(e.clone()),
+                        new: None,
+                    })
+                    .collect(),
+            ),
+            EntryState::Discarded => return None,
+        };
+
+        let primary_key_name = self.model.primary_key_name();
+
+        Some(AuditRecord {
+            table: self.model.table_name(),
+            operation,
+            primary_key: value(&current, primary_key_name)
+                .or_else(|| value(&self.original, primary_key_name))
+                .or(generated_key)
+                .unwrap_or_default(),
+            columns,
+        })
+    }
+}
+
+/// A primary key returned as text, as a JSON number if it is one (e.g. of a serial column), like
+/// the primary keys that are known before inserting are recorded.
+fn key_value(key: String) -> Value {
+    serde_json::from_str::<Value>(&key)
+        .ok()
+        .filter(Value::is_number)
+        .unwrap_or(Value::String(key))
+}
+
+/// A unit of work on database `DB`.
+///
+/// A session tracks loaded models and records the changes made to their active models. All pending
+/// inserts, updates and deletes are written at once in a single transaction when the session is
+/// [flushed](Self::flush).
+///
+/// Writes are ordered by the dependencies between entities (see
+/// [`Entity::REFERENCED_TABLES`]), so that rows are inserted after the rows they refer to, and
+/// deleted before them.
+pub struct Session<DB>
+where
+    DB: Database + Sync,
+{
+    id: u64,
+    entries: Vec<Entry<DB>>,
+}
+
+impl<DB> Default for Session<DB>
+where
+    DB: Database + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<DB> Session<DB>
+where
+    DB: Database + Sync,
+{
+    /// Create an empty session.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            id: NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed),
+            entries: vec![],
+        }
+    }
+
+    /// The entry of `tracked`.
+    ///
+    /// # Panics
+    ///
+    /// If `tracked` was handed out by a different session.
+    fn entry<A>(&self, tracked: Tracked<A>) -> &Entry<DB> {
+        assert_eq!(
+            tracked.session, self.id,
+            "Tracked model belongs to a different session"
+        );
+
+        &self.entries[tracked.index]
+    }
+
+    /// The entry of `tracked`, see [`entry`](Self::entry).
+    fn entry_mut<A>(&mut self, tracked: Tracked<A>) -> &mut Entry<DB> {
+        assert_eq!(
+            tracked.session, self.id,
+            "Tracked model belongs to a different session"
+        );
+
+        &mut self.entries[tracked.index]
+    }
+
+    fn push<A>(&mut self, active_model: A, state: EntryState) -> Tracked<A>
+    where
+        A: ActiveModel + Send + 'static,
+        <A::Model as Model>::Entity: HasPrimaryKey<Database = DB, Model = A::Model> + 'static,
+    {
+        self.entries.push(Entry {
+            original: AuditedModel::audit_values(&active_model),
+            primary_key: TrackedModel::primary_key(&active_model),
+            model: Box::new(active_model),
+            state,
+        });
+
+        Tracked {
+            session: self.id,
+            index: self.entries.len() - 1,
+            marker: PhantomData,
+        }
+    }
+
+    /// Track a model loaded from the database. Columns changed on its active model (see
+    /// [`get_mut`](Self::get_mut)) are updated when the session is flushed.
+    pub fn track<M>(&mut self, model: M) -> Tracked<M::ActiveModel>
+    where
+        M: IntoActiveModel,
+        M::ActiveModel: Send + 'static,
+        M::Entity: HasPrimaryKey<Database = DB, Model = M> + 'static,
+    {
+        self.push(model.into_active(), EntryState::Loaded)
+    }
+
+    /// Add a new model, to be inserted when the session is flushed.
+    pub fn add<A>(&mut self, active_model: A) -> Tracked<A>
+    where
+        A: ActiveModel + Send + 'static,
+        <A::Model as Model>::Entity: HasPrimaryKey<Database = DB, Model = A::Model> + 'static,
+    {
+        self.push(active_model, EntryState::New)
+    }
+
+    /// Mark a tracked model for deletion. Models that were added to the session, but not yet
+    /// inserted, are simply discarded.
+    ///
+    /// # Panics
+    ///
+    /// If `tracked` was handed out by a different session.
+    pub fn delete<A>(&mut self, tracked: Tracked<A>) {
+        let entry = self.entry_mut(tracked);
+
+        entry.state = match entry.state {
+            EntryState::Loaded | EntryState::Deleted => EntryState::Deleted,
+            EntryState::New | EntryState::Discarded => EntryState::Discarded,
+        };
+    }
+
+    /// The active model of a tracked model.
+    ///
+    /// # Panics
+    ///
+    /// If `tracked` was handed out by a different session.
+    #[must_use]
+    pub fn get<A>(&self, tracked: Tracked<A>) -> &A
+    where
+        A: 'static,
+    {
+        self.entry(tracked)
+            .model
+            .as_any()
+            .downcast_ref()
+            .unwrap_or_else(|| panic!("Tracked model belongs to a different session"))
+    }
+
+    /// The active model of a tracked model, to record changes on it.
+    ///
+    /// # Panics
+    ///
+    /// If `tracked` was handed out by a different session.
+    #[must_use]
+    pub fn get_mut<A>(&mut self, tracked: Tracked<A>) -> &mut A
+    where
+        A: 'static,
+    {
+        self.entry_mut(tracked)
+            .model
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap_or_else(|| panic!("Tracked model belongs to a different session"))
+    }
+
+    /// The indices of all entries, in the order they will be written.
+    fn write_order(&self) -> Vec<usize> {
+        let (mut deletes, mut writes): (Vec<_>, Vec<_>) =
+            (0..self.entries.len()).partition(|e| self.entries[*e].state == EntryState::Deleted);
+
+        writes.sort_by_cached_key(|e| self.depth(self.entries[*e].model.table_name()));
+        deletes.sort_by_cached_key(|e| {
+            std::cmp::Reverse(self.depth(self.entries[*e].model.table_name()))
+        });
+
+        writes.into_iter().chain(deletes).collect()
+    }
+
+    /// The length of the longest chain of references from `table` to other tables in this
+    /// session. Cycles and references to the table itself are ignored.
+    fn depth(&self, table: &'static str) -> usize {
+        fn visit<DB>(
+            entries: &[Entry<DB>],
+            table: &'static str,
+            path: &mut Vec<&'static str>,
+        ) -> usize
+        where
+            DB: Database + Sync,
+        {
+            path.push(table);
+
+            // Collected first, since visiting the references needs to modify `path`.
+            #[allow(clippy::needless_collect)]
+            let referenced = entries
+                .iter()
+                .filter(|e| e.model.table_name() == table)
+                .flat_map(|e| e.model.referenced_tables())
+                .filter(|e| !path.contains(e))
+                .filter(|e| entries.iter().any(|o| o.model.table_name() == **e))
+                .collect::<Vec<_>>();
+
+            let depth = referenced
+                .into_iter()
+                .map(|e| visit(entries, e, path) + 1)
+                .max()
+                .unwrap_or(0);
+
+            path.pop();
+
+            depth
+        }
+
+        visit(&self.entries, table, &mut vec![])
+    }
+
+    /// The SQL of all pending statements, in the order they will be run. Hooks are not run, so the
+    /// statements may still change when flushing.
+    ///
+    /// This is mainly useful for debugging purposes, see
+    /// [`Select::query`](crate::query::select::Select::query).
+    #[must_use]
+    pub fn queries(&self) -> Vec<String> {
+        self.write_order()
+            .into_iter()
+            .filter_map(|e| {
+                let entry = &self.entries[e];
+
+                entry
+                    .model
+                    .statement(entry.state, entry.primary_key.as_ref())
+            })
+            .map(|e| {
+                let mut builder = QueryBuilder::new("");
+                e.push_to(&mut builder);
+                builder.into_sql()
+            })
+            .collect()
+    }
+
+    /// Write all pending changes inside a single transaction (or savepoint, if the connection is
+    /// inside one already), consuming the session.
+    ///
+    /// The [hooks](crate::entity::model::ActiveModelHooks) of each model are run around its write.
+    ///
+    /// # Errors
+    ///
+    /// If any of the statements or hooks failed, in which case none of the changes are written.
+    /// See [`Error`](crate::Error) for more information.
+    pub async fn flush<C>(self, connection: &mut C) -> Result<()>
+    where
+        C: Connection<Database = DB>,
+        DB::QueryResult: IntoExecResult,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+        for<'q> DB::Arguments<'q>: IntoArguments<'q, DB> + 'static,
+        usize: ColumnIndex<DB::Row>,
+        String: for<'d> Decode<'d, DB> + Type<DB>,
+    {
+        self.flush_audited(connection, &mut |_| Ok(())).await
+    }
+
+    /// Like [`flush`](Self::flush), but also pass the [audit records](crate::audit) of all writes
+    /// to `sink` before committing.
+    ///
+    /// The primary keys generated by the database for inserted models are recorded as well. They
+    /// are fetched with `RETURNING`, which sqlite supports since version 3.35, or read from
+    /// `LAST_INSERT_ID()` on mysql.
+    ///
+    /// # Errors
+    ///
+    /// If any of the statements, hooks or the sink failed, in which case none of the changes are
+    /// written. See [`Error`](crate::Error) for more information.
+    pub async fn flush_audited<C, S>(mut self, connection: &mut C, sink: &mut S) -> Result<()>
+    where
+        C: Connection<Database = DB>,
+        S: AuditSink<DB>,
+        DB::QueryResult: IntoExecResult,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+        for<'q> DB::Arguments<'q>: IntoArguments<'q, DB> + 'static,
+        usize: ColumnIndex<DB::Row>,
+        String: for<'d> Decode<'d, DB> + Type<DB>,
+    {
+        let order = self.write_order();
+        let mut records = vec![];
+
+        let mut transaction = connection.begin().await?;
+
+        for index in order {
+            let Entry {
+                model,
+                state,
+                primary_key,
+                ..
+            } = &mut self.entries[index];
+
+            if !model.has_changes(*state) {
+                continue;
+            }
+
+            model.before_write(*state)?;
+
+            // Only audited inserts need the primary key the database generates.
+            let returns_key =
+                *state == EntryState::New && model.audited() && model.primary_key().is_none();
+            let mut generated_key = None;
+
+            if let Some(statement) = model.statement(*state, primary_key.as_ref()) {
+                let mut builder = QueryBuilder::new("");
+                statement.push_to(&mut builder);
+
+                if returns_key && DB::NAME != "MySQL" {
+                    builder.push(format_args!(
+                        " RETURNING CAST({} AS TEXT)",
+                        quote_ident(model.primary_key_name())
+                    ));
+
+                    let key: String = (&mut *transaction)
+                        .fetch_one(builder.build())
+                        .await?
+                        .try_get(0)?;
+
+                    generated_key = Some(key_value(key));
+                } else {
+                    let result = (&mut *transaction)
+                        .execute(builder.build())
+                        .await?
+                        .into_exec_result();
+
+                    generated_key = result
+                        .last_insert_id
+                        .filter(|_| returns_key)
+                        .map(Value::from);
+                }
+            }
+
+            model.after_write(*state)?;
+
+            records.extend(self.entries[index].audit_record(generated_key));
+        }
+
+        sink.record(records, &mut transaction).await?;
+
+        Ok(transaction.commit().await?)
+    }
+}
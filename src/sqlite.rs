@@ -0,0 +1,194 @@
+//! Opening sqlite connections with the pragmas most applications want, instead of setting them up
+//! by hand for every connection:
+//!
+//! ```ignore
+//! let pool = SqliteSetup::new().connect_pool("sqlite://app.db").await?;
+//! ```
+//!
+//! By default, connections use WAL mode with `synchronous = NORMAL`, wait up to 5 seconds for locks
+//! held by other connections (`busy_timeout`), and enforce foreign keys. Databases that do not
+//! exist yet are created.
+
+use std::{str::FromStr, time::Duration};
+
+use sqlx::{
+    ConnectOptions, Executor, Row,
+    sqlite::{
+        SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePool, SqliteSynchronous,
+    },
+};
+
+use crate::Result;
+
+/// The pragmas to open sqlite connections with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteSetup {
+    journal_mode: SqliteJournalMode,
+    synchronous: SqliteSynchronous,
+    busy_timeout: Duration,
+    foreign_keys: bool,
+    create_if_missing: bool,
+}
+
+impl Default for SqliteSetup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SqliteSetup {
+    /// Create a new setup with the defaults described in the [module documentation](self).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            journal_mode: SqliteJournalMode::Wal,
+            synchronous: SqliteSynchronous::Normal,
+            busy_timeout: Duration::from_secs(5),
+            foreign_keys: true,
+            create_if_missing: true,
+        }
+    }
+
+    /// Set the journal mode, [`SqliteJournalMode::Wal`] by default. In-memory databases cannot use
+    /// WAL mode, and silently fall back to [`SqliteJournalMode::Memory`].
+    #[must_use]
+    pub const fn journal_mode(mut self, journal_mode: SqliteJournalMode) -> Self {
+        self.journal_mode = journal_mode;
+        self
+    }
+
+    /// Set how often sqlite syncs to disk, [`SqliteSynchronous::Normal`] by default, which is safe
+    /// from corruption in WAL mode.
+    #[must_use]
+    pub const fn synchronous(mut self, synchronous: SqliteSynchronous) -> Self {
+        self.synchronous = synchronous;
+        self
+    }
+
+    /// Set how long to wait for a lock held by another connection before failing with
+    /// `SQLITE_BUSY`, 5 seconds by default.
+    #[must_use]
+    pub const fn busy_timeout(mut self, busy_timeout: Duration) -> Self {
+        self.busy_timeout = busy_timeout;
+        self
+    }
+
+    /// Set whether foreign key constraints are enforced, `true` by default.
+    #[must_use]
+    pub const fn foreign_keys(mut self, foreign_keys: bool) -> Self {
+        self.foreign_keys = foreign_keys;
+        self
+    }
+
+    /// Set whether to create the database file if it does not exist, `true` by default.
+    #[must_use]
+    pub const fn create_if_missing(mut self, create_if_missing: bool) -> Self {
+        self.create_if_missing = create_if_missing;
+        self
+    }
+
+    /// Apply the pragmas to `options`, e.g. to also change settings this setup does not cover.
+    #[must_use]
+    pub fn apply(&self, options: SqliteConnectOptions) -> SqliteConnectOptions {
+        options
+            .journal_mode(self.journal_mode)
+            .synchronous(self.synchronous)
+            .busy_timeout(self.busy_timeout)
+            .foreign_keys(self.foreign_keys)
+            .create_if_missing(self.create_if_missing)
+    }
+
+    /// The options for connecting to the database at `url`, with the pragmas applied.
+    ///
+    /// # Errors
+    ///
+    /// If `url` is not a valid sqlite URL.
+    pub fn options(&self, url: &str) -> Result<SqliteConnectOptions> {
+        Ok(self.apply(SqliteConnectOptions::from_str(url)?))
+    }
+
+    /// Open a single connection to the database at `url`.
+    ///
+    /// # Errors
+    ///
+    /// If `url` is not a valid sqlite URL, or the database could not be opened. See
+    /// [`Error`](crate::Error) for more information.
+    pub async fn connect(&self, url: &str) -> Result<SqliteConnection> {
+        Ok(self.options(url)?.connect().await?)
+    }
+
+    /// Open a pool of connections to the database at `url`. See [`connect`](Self::connect).
+    ///
+    /// # Errors
+    ///
+    /// If `url` is not a valid sqlite URL, or the database could not be opened. See
+    /// [`Error`](crate::Error) for more information.
+    pub async fn connect_pool(&self, url: &str) -> Result<SqlitePool> {
+        Ok(SqlitePool::connect_with(self.options(url)?).await?)
+    }
+
+    /// Set the pragmas on a connection that has already been opened, e.g. by a framework. Must not
+    /// be called inside a transaction, since sqlite cannot change the journal mode there.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database. See [`Error`](crate::Error) for
+    /// more information.
+    pub async fn apply_to(&self, connection: &mut SqliteConnection) -> Result<()> {
+        connection
+            .execute(
+                format!(
+                    "PRAGMA busy_timeout = {}; PRAGMA journal_mode = {}; PRAGMA synchronous = {}; PRAGMA foreign_keys = {};",
+                    self.busy_timeout.as_millis(),
+                    journal_mode_name(self.journal_mode),
+                    synchronous_name(self.synchronous),
+                    if self.foreign_keys { "ON" } else { "OFF" },
+                )
+                .as_str(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+// `as_str` of sqlx's enums is private, so their pragma values are spelled out here again.
+const fn journal_mode_name(journal_mode: SqliteJournalMode) -> &'static str {
+    match journal_mode {
+        SqliteJournalMode::Delete => "DELETE",
+        SqliteJournalMode::Truncate => "TRUNCATE",
+        SqliteJournalMode::Persist => "PERSIST",
+        SqliteJournalMode::Memory => "MEMORY",
+        SqliteJournalMode::Wal => "WAL",
+        SqliteJournalMode::Off => "OFF",
+    }
+}
+
+const fn synchronous_name(synchronous: SqliteSynchronous) -> &'static str {
+    match synchronous {
+        SqliteSynchronous::Off => "OFF",
+        SqliteSynchronous::Normal => "NORMAL",
+        SqliteSynchronous::Full => "FULL",
+        SqliteSynchronous::Extra => "EXTRA",
+    }
+}
+
+/// Move the contents of the write-ahead log into the database file and truncate the log, e.g.
+/// before copying the database file for a backup.
+///
+/// Returns whether the checkpoint completed, which it does not if other connections are still
+/// reading from or writing to the log.
+///
+/// # Errors
+///
+/// If there's been a problem communicating with the database. See [`Error`](crate::Error) for more
+/// information.
+pub async fn checkpoint(connection: &mut SqliteConnection) -> Result<bool> {
+    // The first column is 1 if the checkpoint was blocked by another connection.
+    let busy: i64 = connection
+        .fetch_one("PRAGMA wal_checkpoint(TRUNCATE)")
+        .await?
+        .try_get(0)?;
+
+    Ok(busy == 0)
+}
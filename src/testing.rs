@@ -0,0 +1,336 @@
+//! Helpers for integration tests against a real database.
+//!
+//! [`test_transaction`] runs a test inside a transaction that is always rolled back, so tests can
+//! share a database without truncating tables in between:
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn renames_user() {
+//!     let pool = PgPool::connect(&std::env::var("DATABASE_URL").unwrap()).await.unwrap();
+//!
+//!     test_transaction(&pool, async |conn| {
+//!         let mut user = user::ActiveModel::default();
+//!         user.set_name("someone".to_string());
+//!         user::Entity::insert().row(&user).exec(&mut *conn).await.unwrap();
+//!
+//!         assert_eq!(user::Entity::find().all(&mut *conn).await.unwrap().len(), 1);
+//!     })
+//!     .await
+//!     .unwrap();
+//! }
+//! ```
+//!
+//! Models marked `#[sky_orm(factory)]` get a `ModelFactory`, building models with a default value
+//! for every field that has not been set explicitly, so that tests only need to spell out the
+//! fields they care about:
+//!
+//! ```ignore
+//! let author = user::ModelFactory::new().create(&mut *conn).await?;
+//! let posts = post::ModelFactory::new()
+//!     .for_author(&author)
+//!     .published(true)
+//!     .create_many(3, &mut *conn)
+//!     .await?;
+//! ```
+//!
+//! The default values are given by [`FactoryDefault`], which can be implemented for custom field
+//! types, e.g. enums.
+
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde_json::Map;
+use sqlx::{
+    Connection, Database, Executor, IntoArguments, Pool,
+    types::{
+        Json, JsonValue,
+        chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc},
+    },
+};
+
+use crate::{
+    Result,
+    entity::{Entity, model::IntoActiveModel},
+    query::exec_result::IntoExecResult,
+};
+
+/// Run `test` with a connection inside a transaction, which is rolled back once `test` is done.
+///
+/// The connection can be passed to all execution APIs. Transactions begun by them, e.g. by
+/// [`Session::flush`](crate::session::Session::flush), become savepoints of the outer transaction,
+/// so they are rolled back as well, even if they are committed. If `test` panics, the transaction
+/// is rolled back when the connection is returned to the pool.
+///
+/// # Errors
+///
+/// If no connection could be acquired, or the transaction could not be begun or rolled back. See
+/// [`Error`](crate::Error) for more information.
+pub async fn test_transaction<DB, F, R>(pool: &Pool<DB>, test: F) -> Result<R>
+where
+    DB: Database,
+    F: AsyncFnOnce(&mut DB::Connection) -> R,
+{
+    let mut transaction = pool.begin().await?;
+
+    let result = test(&mut transaction).await;
+
+    transaction.rollback().await?;
+
+    Ok(result)
+}
+
+/// The next number of the sequence keeping the default values of models built by factories apart,
+/// shared by all factories. Starts at 1.
+#[must_use]
+pub fn next_sequence() -> u64 {
+    static SEQUENCE: AtomicU64 = AtomicU64::new(1);
+
+    SEQUENCE.fetch_add(1, Ordering::Relaxed)
+}
+
+/// A value for a field that has not been set on a factory.
+///
+/// Numbers take the value of the sequence, and strings are made up of the column's name and the
+/// sequence, so that primary keys and unique columns do not clash between models. Nullable
+/// fields are `NULL`, and timestamps are the current time.
+pub trait FactoryDefault {
+    /// The value for column `column` of the model numbered `sequence`.
+    fn factory_default(column: &str, sequence: u64) -> Self;
+}
+
+macro_rules! impl_factory_default_number {
+    ($($ty:ty),*) => {
+        $(
+            impl FactoryDefault for $ty {
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_possible_wrap,
+                    clippy::cast_precision_loss,
+                    clippy::cast_lossless
+                )]
+                fn factory_default(_column: &str, sequence: u64) -> Self {
+                    sequence as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_factory_default_number!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64);
+
+impl FactoryDefault for bool {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        false
+    }
+}
+
+impl FactoryDefault for String {
+    fn factory_default(column: &str, sequence: u64) -> Self {
+        format!("{column}-{sequence}")
+    }
+}
+
+impl<T> FactoryDefault for Option<T> {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        None
+    }
+}
+
+impl<T> FactoryDefault for Vec<T> {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        vec![]
+    }
+}
+
+impl FactoryDefault for DateTime<Utc> {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        Utc::now()
+    }
+}
+
+impl FactoryDefault for NaiveDateTime {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        Utc::now().naive_utc()
+    }
+}
+
+impl FactoryDefault for NaiveDate {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        Utc::now().date_naive()
+    }
+}
+
+impl FactoryDefault for NaiveTime {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        Utc::now().time()
+    }
+}
+
+impl FactoryDefault for JsonValue {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        Self::Object(Map::new())
+    }
+}
+
+impl<T> FactoryDefault for Json<T>
+where
+    T: FactoryDefault,
+{
+    fn factory_default(column: &str, sequence: u64) -> Self {
+        Self(T::factory_default(column, sequence))
+    }
+}
+
+impl FactoryDefault for IpAddr {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        Self::V4(Ipv4Addr::LOCALHOST)
+    }
+}
+
+/// The point at the origin, i.e. `POINT(0 0)` without an SRID.
+#[cfg(feature = "postgres")]
+impl FactoryDefault for crate::entity::column::geo::Wkb {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        // Little endian, geometry type 1 (point), followed by both coordinates.
+        let mut wkb = vec![1, 1, 0, 0, 0];
+        wkb.extend_from_slice(&[0; 16]);
+        Self(wkb)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl FactoryDefault for crate::entity::column::interval::Interval {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl FactoryDefault for sqlx::types::Uuid {
+    fn factory_default(_column: &str, sequence: u64) -> Self {
+        Self::from_u128(sequence.into())
+    }
+}
+
+#[cfg(feature = "rust_decimal")]
+impl FactoryDefault for sqlx::types::Decimal {
+    fn factory_default(_column: &str, sequence: u64) -> Self {
+        sequence.into()
+    }
+}
+
+#[cfg(feature = "bigdecimal")]
+impl FactoryDefault for sqlx::types::BigDecimal {
+    fn factory_default(_column: &str, sequence: u64) -> Self {
+        sequence.into()
+    }
+}
+
+#[cfg(feature = "ipnetwork")]
+impl FactoryDefault for sqlx::types::ipnetwork::IpNetwork {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        IpAddr::V4(Ipv4Addr::LOCALHOST).into()
+    }
+}
+
+#[cfg(feature = "mac_address")]
+impl FactoryDefault for sqlx::types::mac_address::MacAddress {
+    fn factory_default(_column: &str, _sequence: u64) -> Self {
+        Self::new([0; 6])
+    }
+}
+
+/// A builder of models of entity `Entity` with default values, for inserting test data.
+/// Implemented by the `ModelFactory` generated with `#[sky_orm(factory)]`.
+pub trait Factory {
+    type Entity: Entity<Model: IntoActiveModel + Clone>;
+
+    /// Build a model from the fields set on this factory, filling the others with their
+    /// [`FactoryDefault`] for `sequence`.
+    fn build_with(&self, sequence: u64) -> <Self::Entity as Entity>::Model;
+
+    /// Build a model, numbered with the [next sequence](next_sequence). See
+    /// [`build_with`](Self::build_with).
+    fn build(&self) -> <Self::Entity as Entity>::Model {
+        self.build_with(next_sequence())
+    }
+
+    /// Build `count` models, each numbered with its own sequence.
+    fn build_many(&self, count: usize) -> Vec<<Self::Entity as Entity>::Model> {
+        (0..count).map(|_| self.build()).collect()
+    }
+
+    /// Build a model and insert it, returning the inserted model.
+    ///
+    /// # Errors
+    ///
+    /// If there's been a problem communicating with the database, or the model violates
+    /// a constraint, e.g. because a foreign key has not been set. See [`Error`](crate::Error) for
+    /// more information.
+    fn create<'c, C>(
+        &self,
+        connection: &'c mut C,
+    ) -> impl Future<Output = Result<<Self::Entity as Entity>::Model>>
+    where
+        <<Self::Entity as Entity>::Database as Database>::QueryResult: IntoExecResult,
+        C: Connection<Database = <Self::Entity as Entity>::Database>,
+        for<'e> &'e mut C: Executor<'e, Database = <Self::Entity as Entity>::Database>,
+        for<'e> &'e mut <<Self::Entity as Entity>::Database as Database>::Connection:
+            Executor<'e, Database = <Self::Entity as Entity>::Database>,
+        for<'q> <<Self::Entity as Entity>::Database as Database>::Arguments<'q>:
+            IntoArguments<'q, <Self::Entity as Entity>::Database> + 'static,
+    {
+        async move {
+            let model = self.build();
+
+            Self::Entity::insert()
+                .row(&model.clone().into_active())
+                .exec(connection)
+                .await?;
+
+            Ok(model)
+        }
+    }
+
+    /// Build `count` models and insert them with a single statement, returning the inserted
+    /// models.
+    ///
+    /// # Errors
+    ///
+    /// See [`create`](Self::create).
+    fn create_many<'c, C>(
+        &self,
+        count: usize,
+        connection: &'c mut C,
+    ) -> impl Future<Output = Result<Vec<<Self::Entity as Entity>::Model>>>
+    where
+        <<Self::Entity as Entity>::Database as Database>::QueryResult: IntoExecResult,
+        C: Connection<Database = <Self::Entity as Entity>::Database>,
+        for<'e> &'e mut C: Executor<'e, Database = <Self::Entity as Entity>::Database>,
+        for<'e> &'e mut <<Self::Entity as Entity>::Database as Database>::Connection:
+            Executor<'e, Database = <Self::Entity as Entity>::Database>,
+        for<'q> <<Self::Entity as Entity>::Database as Database>::Arguments<'q>:
+            IntoArguments<'q, <Self::Entity as Entity>::Database> + 'static,
+    {
+        async move {
+            let models = self.build_many(count);
+
+            if !models.is_empty() {
+                let active_models = models
+                    .iter()
+                    .cloned()
+                    .map(IntoActiveModel::into_active)
+                    .collect::<Vec<_>>();
+
+                Self::Entity::insert()
+                    .rows(&active_models)
+                    .exec(connection)
+                    .await?;
+            }
+
+            Ok(models)
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Integrations with web frameworks, so that handlers using the ORM stay small.
+//!
+//! With the `axum` or `actix` feature, [`Db`] extracts a pool from the state of the application,
+//! and [`Error`](crate::Error) is turned into a response with its
+//! [`http_status`](crate::Error::http_status) and [`response_body`](crate::Error::response_body),
+//! i.e. 404 if nothing was found and 409 for constraint violations. With axum:
+//!
+//! ```ignore
+//! async fn show(db: Db<Postgres>, Path(id): Path<i64>) -> Result<Json<user::Model>, sky_orm::Error> {
+//!     let mut connection = db.acquire().await?;
+//!
+//!     Ok(Json(user::Entity::find_by_id(id).one(&mut *connection).await?))
+//! }
+//!
+//! let app = Router::new().route("/users/{id}", get(show)).with_state(pool);
+//! ```
+//!
+//! With actix-web, the pool is registered with `App::app_data(web::Data::new(pool))`.
+
+use std::ops::Deref;
+
+use sqlx::{Database, Pool};
+
+#[cfg(feature = "actix")]
+mod actix;
+#[cfg(feature = "axum")]
+mod axum;
+
+/// A handle to the pool of connections of the application, extracted from the state of axum
+/// (through `FromRef`) or the app data of actix-web.
+#[derive(Debug, Clone)]
+pub struct Db<DB: Database>(pub Pool<DB>);
+
+impl<DB: Database> Deref for Db<DB> {
+    type Target = Pool<DB>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
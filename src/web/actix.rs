@@ -0,0 +1,40 @@
+use std::future::{Ready, ready};
+
+use actix_web::{
+    FromRequest, HttpRequest, HttpResponse, ResponseError, dev::Payload,
+    error::ErrorInternalServerError, http::StatusCode, web::Data,
+};
+use sqlx::{Database, Pool};
+
+use super::Db;
+use crate::Error;
+
+impl<DB: Database> FromRequest for Db<DB> {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        // Pools are usually registered wrapped in `Data`, but can be given as they are, too.
+        let pool = req
+            .app_data::<Data<Pool<DB>>>()
+            .map(Data::get_ref)
+            .or_else(|| req.app_data::<Pool<DB>>())
+            .cloned()
+            .map(Self)
+            .ok_or_else(|| {
+                ErrorInternalServerError("No pool of connections was registered as app data.")
+            });
+
+        ready(pool)
+    }
+}
+
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(self.response_body())
+    }
+}
@@ -0,0 +1,34 @@
+use std::convert::Infallible;
+
+use ::axum::{
+    Json,
+    extract::{FromRef, FromRequestParts},
+    http::{StatusCode, request::Parts},
+    response::{IntoResponse, Response},
+};
+use sqlx::{Database, Pool};
+
+use super::Db;
+use crate::Error;
+
+impl<S, DB> FromRequestParts<S> for Db<DB>
+where
+    S: Send + Sync,
+    DB: Database,
+    Pool<DB>: FromRef<S>,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(Pool::from_ref(state)))
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let status =
+            StatusCode::from_u16(self.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        (status, Json(self.response_body())).into_response()
+    }
+}